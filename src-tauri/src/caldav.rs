@@ -0,0 +1,462 @@
+use crate::credentials;
+use crate::todotxt::CONFLICT_ERROR_PREFIX;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// The password never leaves the backend; the webview only holds the reference it is stored under
+const CREDENTIALS_FILE: &str = "caldav_credentials.json";
+
+const TODO_QUERY: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <d:getetag/>
+    <c:calendar-data/>
+  </d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">
+      <c:comp-filter name="VTODO"/>
+    </c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#;
+
+// Stored with the password, so it is only ever sent to the server and user it was saved for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalDavConfig {
+    collection_url: String,
+    username: String,
+    password: String,
+}
+
+// One calendar object in the collection
+#[derive(Debug, Clone, Serialize)]
+pub struct CalDavResource {
+    url: String,
+    etag: Option<String>,
+    data: String,
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build()
+}
+
+fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+fn authorize(request: ureq::Request, config: &CalDavConfig) -> ureq::Request {
+    if config.username.is_empty() {
+        return request;
+    }
+    let credentials = format!("{}:{}", config.username, config.password);
+    request.set(
+        "Authorization",
+        &format!("Basic {}", base64_encode(credentials.as_bytes())),
+    )
+}
+
+fn collection_url(config: &CalDavConfig) -> String {
+    format!("{}/", config.collection_url.trim_end_matches('/'))
+}
+
+// Turns an href from a multistatus response into a full URL
+fn resolve_href(config: &CalDavConfig, href: &str) -> String {
+    if href.starts_with("http://") || href.starts_with("https://") {
+        return href.to_string();
+    }
+    let base = &config.collection_url;
+    let origin_end = base
+        .find("://")
+        .and_then(|scheme_end| base[scheme_end + 3..].find('/').map(|i| scheme_end + 3 + i))
+        .unwrap_or(base.len());
+    format!("{}{}", &base[..origin_end], href)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#13;", "\r")
+        .replace("&#xD;", "\r")
+        .replace("&#xd;", "\r")
+        .replace("&amp;", "&")
+}
+
+// Text content of an element, which may be wrapped in CDATA or entity-escaped
+fn element_text(raw: &str) -> String {
+    let raw = raw.trim();
+    match raw.strip_prefix("<![CDATA[").and_then(|t| t.strip_suffix("]]>")) {
+        Some(cdata) => cdata.to_string(),
+        None => decode_entities(raw),
+    }
+}
+
+// Inner content of every element with the given local name, whatever its namespace prefix
+fn find_elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        let after = &rest[start + 1..];
+        let Some(tag_end) = after.find('>') else {
+            break;
+        };
+        let tag = &after[..tag_end];
+        let tag_name = tag.split_whitespace().next().unwrap_or("");
+        let local_name = tag_name.rsplit(':').next().unwrap_or("");
+        let content_start = start + 1 + tag_end + 1;
+
+        if local_name == name && !tag.starts_with('/') && !tag.ends_with('/') {
+            let close = format!("</{}>", tag_name);
+            if let Some(close_at) = rest[content_start..].find(&close) {
+                found.push(&rest[content_start..content_start + close_at]);
+                rest = &rest[content_start + close_at + close.len()..];
+                continue;
+            }
+        }
+        rest = &rest[content_start..];
+    }
+
+    found
+}
+
+fn parse_multistatus(config: &CalDavConfig, xml: &str) -> Vec<CalDavResource> {
+    find_elements(xml, "response")
+        .into_iter()
+        .filter_map(|response| {
+            let href = find_elements(response, "href").into_iter().next()?;
+            let data = find_elements(response, "calendar-data").into_iter().next()?;
+            let etag = find_elements(response, "getetag")
+                .into_iter()
+                .next()
+                .map(element_text)
+                .filter(|e| !e.is_empty());
+            Some(CalDavResource {
+                url: resolve_href(config, &element_text(href)),
+                etag,
+                data: element_text(data),
+            })
+        })
+        .filter(|resource| resource.data.contains("BEGIN:VTODO"))
+        .collect()
+}
+
+fn describe_error(error: ureq::Error) -> String {
+    match error {
+        ureq::Error::Status(412, _) => format!(
+            "{} the item was changed on the CalDAV server since the last sync",
+            CONFLICT_ERROR_PREFIX
+        ),
+        ureq::Error::Status(code, response) => {
+            let body = response.into_string().unwrap_or_default();
+            format!("CalDAV server returned {}: {}", code, body.trim())
+        }
+        ureq::Error::Transport(transport) => format!("CalDAV request failed: {}", transport),
+    }
+}
+
+fn list_todos(config: &CalDavConfig) -> Result<Vec<CalDavResource>, String> {
+    let response = authorize(agent().request("REPORT", &collection_url(config)), config)
+        .set("Depth", "1")
+        .set("Content-Type", "application/xml; charset=utf-8")
+        .send_string(TODO_QUERY)
+        .map_err(describe_error)?;
+
+    let xml = response
+        .into_string()
+        .map_err(|e| format!("Failed to read CalDAV response: {}", e))?;
+    Ok(parse_multistatus(config, &xml))
+}
+
+fn put_todo(
+    config: &CalDavConfig,
+    url: Option<String>,
+    uid: &str,
+    data: &str,
+    etag: Option<String>,
+) -> Result<CalDavResource, String> {
+    let is_new = url.is_none();
+    let url = url.unwrap_or_else(|| {
+        let file_name: String = uid
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+            .collect();
+        format!("{}{}.ics", collection_url(config), file_name)
+    });
+
+    // Updates must match the version we last saw; new items must not overwrite anything
+    let request = authorize(agent().put(&url), config)
+        .set("Content-Type", "text/calendar; charset=utf-8");
+    let request = match &etag {
+        Some(etag) => request.set("If-Match", etag),
+        None if is_new => request.set("If-None-Match", "*"),
+        None => request,
+    };
+
+    let response = request.send_string(data).map_err(describe_error)?;
+    Ok(CalDavResource {
+        url,
+        etag: response.header("ETag").map(str::to_string),
+        data: data.to_string(),
+    })
+}
+
+// Deletes only the version we last saw; an item that is already gone counts as deleted
+fn delete_todo(config: &CalDavConfig, url: &str, etag: Option<String>) -> Result<(), String> {
+    let request = authorize(agent().delete(url), config);
+    let request = match &etag {
+        Some(etag) => request.set("If-Match", etag),
+        None => request,
+    };
+
+    match request.call() {
+        Ok(_) | Err(ureq::Error::Status(404 | 410, _)) => Ok(()),
+        Err(e) => Err(describe_error(e)),
+    }
+}
+
+fn credentials_path(app: &AppHandle) -> Result<PathBuf, String> {
+    credentials::credentials_path(app, CREDENTIALS_FILE)
+}
+
+fn lookup_config(path: &Path, credential_ref: &str) -> Result<CalDavConfig, String> {
+    credentials::lookup_credential(path, credential_ref)?
+        .ok_or_else(|| "No CalDAV login saved for this server; enter the password again in Settings".to_string())
+}
+
+// Saves the server and login and returns the reference the frontend keeps
+#[tauri::command]
+pub async fn save_caldav_credentials(
+    app: AppHandle,
+    collection_url: String,
+    username: String,
+    password: String,
+    replaces: Option<String>,
+) -> Result<String, String> {
+    let config = CalDavConfig {
+        collection_url: collection_url.trim().to_string(),
+        username: username.trim().to_string(),
+        password,
+    };
+    credentials::store_credential(&credentials_path(&app)?, "caldav", config, replaces.as_deref())
+}
+
+// All VTODO objects in the collection with their ETags
+#[tauri::command]
+pub async fn caldav_list_todos(app: AppHandle, credential_ref: String) -> Result<Vec<CalDavResource>, String> {
+    let config = lookup_config(&credentials_path(&app)?, &credential_ref)?;
+    tauri::async_runtime::spawn_blocking(move || list_todos(&config))
+        .await
+        .map_err(|e| format!("CalDAV task failed: {}", e))?
+}
+
+// Creates (no `url`/`etag`) or updates a VTODO. A stale `etag` fails with a "conflict:" error.
+#[tauri::command]
+pub async fn caldav_put_todo(
+    app: AppHandle,
+    credential_ref: String,
+    url: Option<String>,
+    uid: String,
+    data: String,
+    etag: Option<String>,
+) -> Result<CalDavResource, String> {
+    let config = lookup_config(&credentials_path(&app)?, &credential_ref)?;
+    tauri::async_runtime::spawn_blocking(move || put_todo(&config, url, &uid, &data, etag))
+        .await
+        .map_err(|e| format!("CalDAV task failed: {}", e))?
+}
+
+// Deletes a VTODO. A stale `etag` fails with a "conflict:" error.
+#[tauri::command]
+pub async fn caldav_delete_todo(
+    app: AppHandle,
+    credential_ref: String,
+    url: String,
+    etag: Option<String>,
+) -> Result<(), String> {
+    let config = lookup_config(&credentials_path(&app)?, &credential_ref)?;
+    tauri::async_runtime::spawn_blocking(move || delete_todo(&config, &url, etag))
+        .await
+        .map_err(|e| format!("CalDAV task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{TestResponse, TestServer};
+
+    const TODO: &str = "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:a@pomodoro-app\r\nSUMMARY:Write report\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+
+    fn config(server: &TestServer) -> CalDavConfig {
+        CalDavConfig {
+            collection_url: format!("{}/calendars/me/tasks", server.url),
+            username: "me".to_string(),
+            password: "secret".to_string(),
+        }
+    }
+
+    #[test]
+    fn lists_todos_from_a_multistatus_report() {
+        let multistatus = format!(
+            r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response>
+    <d:href>/calendars/me/tasks/a.ics</d:href>
+    <d:propstat><d:prop>
+      <d:getetag>&quot;1&quot;</d:getetag>
+      <cal:calendar-data>{}</cal:calendar-data>
+    </d:prop></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/calendars/me/tasks/event.ics</d:href>
+    <d:propstat><d:prop>
+      <cal:calendar-data><![CDATA[BEGIN:VCALENDAR
+BEGIN:VEVENT
+END:VEVENT
+END:VCALENDAR]]></cal:calendar-data>
+    </d:prop></d:propstat>
+  </d:response>
+</d:multistatus>"#,
+            TODO.replace('\r', "&#13;")
+        );
+        let server = TestServer::start(vec![TestResponse::new(207, &multistatus)]);
+
+        let todos = list_todos(&config(&server)).unwrap();
+
+        let request = server.request();
+        assert_eq!(request.line, "REPORT /calendars/me/tasks/");
+        assert_eq!(request.header("depth"), Some("1"));
+        assert_eq!(request.header("authorization"), Some("Basic bWU6c2VjcmV0"));
+        assert!(request.body.contains("<c:comp-filter name=\"VTODO\"/>"));
+
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].url, format!("{}/calendars/me/tasks/a.ics", server.url));
+        assert_eq!(todos[0].etag.as_deref(), Some("\"1\""));
+        assert!(todos[0].data.starts_with("BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\n"));
+        assert!(todos[0].data.contains("SUMMARY:Write report\r\n"));
+    }
+
+    #[test]
+    fn creates_new_todos_without_overwriting() {
+        let server = TestServer::start(vec![TestResponse::new(201, "").header("ETag", "\"7\"")]);
+
+        let written = put_todo(&config(&server), None, "a@pomodoro-app", TODO, None).unwrap();
+
+        let request = server.request();
+        assert_eq!(request.line, "PUT /calendars/me/tasks/a-pomodoro-app.ics");
+        assert_eq!(request.header("if-none-match"), Some("*"));
+        assert_eq!(request.header("if-match"), None);
+        assert_eq!(request.body, TODO);
+        assert_eq!(written.url, format!("{}/calendars/me/tasks/a-pomodoro-app.ics", server.url));
+        assert_eq!(written.etag.as_deref(), Some("\"7\""));
+    }
+
+    #[test]
+    fn updates_with_the_last_seen_etag() {
+        let server = TestServer::start(vec![TestResponse::new(204, "").header("ETag", "\"2\"")]);
+        let url = format!("{}/calendars/me/tasks/a.ics", server.url);
+
+        let written = put_todo(&config(&server), Some(url.clone()), "a@pomodoro-app", TODO, Some("\"1\"".to_string()))
+            .unwrap();
+
+        let request = server.request();
+        assert_eq!(request.line, "PUT /calendars/me/tasks/a.ics");
+        assert_eq!(request.header("if-match"), Some("\"1\""));
+        assert_eq!(request.header("if-none-match"), None);
+        assert_eq!(written.url, url);
+        assert_eq!(written.etag.as_deref(), Some("\"2\""));
+    }
+
+    #[test]
+    fn reports_a_stale_etag_as_a_conflict() {
+        let server = TestServer::start(vec![
+            TestResponse::new(412, "Precondition Failed"),
+            TestResponse::new(403, "Read-only calendar"),
+        ]);
+        let url = format!("{}/calendars/me/tasks/a.ics", server.url);
+
+        let etag = Some("\"1\"".to_string());
+        let conflict = put_todo(&config(&server), Some(url.clone()), "a@pomodoro-app", TODO, etag.clone()).unwrap_err();
+        assert!(conflict.starts_with(CONFLICT_ERROR_PREFIX), "{}", conflict);
+
+        let error = put_todo(&config(&server), Some(url), "a@pomodoro-app", TODO, etag).unwrap_err();
+        assert!(!error.starts_with(CONFLICT_ERROR_PREFIX));
+        assert!(error.contains("403") && error.contains("Read-only calendar"), "{}", error);
+    }
+
+    #[test]
+    fn sends_no_credentials_without_a_username() {
+        let server = TestServer::start(vec![TestResponse::new(207, "<d:multistatus xmlns:d=\"DAV:\"/>")]);
+        let anonymous = CalDavConfig { username: String::new(), ..config(&server) };
+
+        assert!(list_todos(&anonymous).unwrap().is_empty());
+        assert_eq!(server.request().header("authorization"), None);
+    }
+
+    #[test]
+    fn deletes_only_the_last_seen_version() {
+        let server = TestServer::start(vec![
+            TestResponse::new(204, ""),
+            TestResponse::new(412, "Precondition Failed"),
+            TestResponse::new(404, "Not Found"),
+        ]);
+        let url = format!("{}/calendars/me/tasks/a.ics", server.url);
+        let etag = Some("\"1\"".to_string());
+
+        delete_todo(&config(&server), &url, etag.clone()).unwrap();
+        let request = server.request();
+        assert_eq!(request.line, "DELETE /calendars/me/tasks/a.ics");
+        assert_eq!(request.header("if-match"), Some("\"1\""));
+        assert_eq!(request.header("authorization"), Some("Basic bWU6c2VjcmV0"));
+
+        let conflict = delete_todo(&config(&server), &url, etag.clone()).unwrap_err();
+        assert!(conflict.starts_with(CONFLICT_ERROR_PREFIX), "{}", conflict);
+
+        // Someone else deleted it first
+        delete_todo(&config(&server), &url, etag).unwrap();
+    }
+
+    #[test]
+    fn keeps_logins_under_references() {
+        let dir = std::env::temp_dir().join(format!("pomodoro-caldav-credentials-{:?}", std::time::SystemTime::now()));
+        let path = dir.join(CREDENTIALS_FILE);
+        let login = |url: &str| CalDavConfig {
+            collection_url: url.to_string(),
+            username: "me".to_string(),
+            password: "secret".to_string(),
+        };
+
+        let first = credentials::store_credential(&path, "caldav", login("https://dav.example.com/tasks"), None).unwrap();
+        assert_eq!(lookup_config(&path, &first).unwrap(), login("https://dav.example.com/tasks"));
+
+        let second =
+            credentials::store_credential(&path, "caldav", login("https://dav.example.org/tasks"), Some(&first)).unwrap();
+        assert!(lookup_config(&path, &first).is_err());
+        assert_eq!(lookup_config(&path, &second).unwrap().collection_url, "https://dav.example.org/tasks");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+// Secrets the webview must not hold, kept in files in the app config dir that only the
+// current user can read. The frontend only keeps the reference an entry is stored under.
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+pub fn credentials_path(app: &AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config dir: {}", e))?;
+    Ok(config_dir.join(file_name))
+}
+
+fn load_credentials<T: DeserializeOwned>(path: &Path) -> Result<BTreeMap<String, T>, String> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read saved credentials: {}", e))?;
+    serde_json::from_str(&json).map_err(|e| format!("Invalid credentials file: {}", e))
+}
+
+// Readable by the current user only
+fn write_credentials<T: Serialize>(path: &Path, store: &BTreeMap<String, T>) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to save credentials: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to protect saved credentials: {}", e))?;
+    }
+    Ok(())
+}
+
+// Stores the entry under a new "<prefix>-..." reference, dropping the one it replaces
+pub fn store_credential<T: Serialize + DeserializeOwned>(
+    path: &Path,
+    prefix: &str,
+    entry: T,
+    replaces: Option<&str>,
+) -> Result<String, String> {
+    let mut store = load_credentials::<T>(path)?;
+    if let Some(old_ref) = replaces {
+        store.remove(old_ref);
+    }
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let reference = format!("{}-{:x}", prefix, nanos);
+    store.insert(reference.clone(), entry);
+    write_credentials(path, &store)?;
+    Ok(reference)
+}
+
+pub fn lookup_credential<T: DeserializeOwned>(path: &Path, reference: &str) -> Result<Option<T>, String> {
+    Ok(load_credentials(path)?.remove(reference))
+}
@@ -0,0 +1,449 @@
+use crate::credentials;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+
+const PER_PAGE: usize = 100;
+const MAX_PAGES: usize = 10;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+// Tokens never leave the backend; the webview only holds the reference they are stored under
+const CREDENTIALS_FILE: &str = "issue_credentials.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueProvider {
+    GitHub,
+    GitLab,
+}
+
+// Where to fetch issues from. `base_url` is the API root, e.g. https://api.github.com or
+// https://gitlab.example.com/api/v4, so self-hosted instances and mock servers work too.
+// It is stored with the token, so a token is only ever sent to the server it was saved for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueSourceConfig {
+    provider: IssueProvider,
+    base_url: String,
+    token: String,
+}
+
+// Provider-neutral issue handed to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteIssue {
+    id: String, // "owner/repo#12" on GitHub, "<project id>#12" on GitLab
+    title: String,
+    body: Option<String>,
+    url: String,
+    open: bool,
+    labels: Vec<String>,
+    updated_at: String,
+    repository: Option<String>,
+    due_date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitHubLabel {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct GitHubRepository {
+    full_name: String,
+}
+
+#[derive(Deserialize)]
+struct GitHubIssue {
+    number: u64,
+    title: String,
+    body: Option<String>,
+    html_url: String,
+    state: String,
+    #[serde(default)]
+    labels: Vec<GitHubLabel>,
+    updated_at: String,
+    repository: Option<GitHubRepository>,
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct GitLabReferences {
+    full: String,
+}
+
+#[derive(Deserialize)]
+struct GitLabIssue {
+    iid: u64,
+    project_id: u64,
+    title: String,
+    description: Option<String>,
+    web_url: String,
+    state: String,
+    #[serde(default)]
+    labels: Vec<String>,
+    updated_at: String,
+    due_date: Option<String>,
+    references: Option<GitLabReferences>,
+}
+
+impl GitHubIssue {
+    // The /issues endpoint always includes the repository; the URL is the fallback
+    fn repository_name(&self) -> Option<String> {
+        self.repository.as_ref().map(|r| r.full_name.clone()).or_else(|| {
+            let path = self.html_url.split("://").nth(1)?;
+            let mut parts = path.split('/').skip(1);
+            Some(format!("{}/{}", parts.next()?, parts.next()?))
+        })
+    }
+
+    fn into_remote(self) -> Option<RemoteIssue> {
+        let repository = self.repository_name()?;
+        Some(RemoteIssue {
+            id: format!("{}#{}", repository, self.number),
+            title: self.title,
+            body: self.body.filter(|b| !b.trim().is_empty()),
+            url: self.html_url,
+            open: self.state == "open",
+            labels: self.labels.into_iter().map(|l| l.name).collect(),
+            updated_at: self.updated_at,
+            repository: Some(repository),
+            due_date: None,
+        })
+    }
+}
+
+impl GitLabIssue {
+    fn into_remote(self) -> RemoteIssue {
+        // "group/project#12" -> "group/project"
+        let repository = self
+            .references
+            .map(|r| r.full.rsplit_once('#').map(|(path, _)| path.to_string()).unwrap_or(r.full));
+        RemoteIssue {
+            id: format!("{}#{}", self.project_id, self.iid),
+            title: self.title,
+            body: self.description.filter(|d| !d.trim().is_empty()),
+            url: self.web_url,
+            open: self.state == "opened",
+            labels: self.labels,
+            updated_at: self.updated_at,
+            repository,
+            due_date: self.due_date,
+        }
+    }
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build()
+}
+
+fn api_url(config: &IssueSourceConfig, path: &str) -> String {
+    format!("{}/{}", config.base_url.trim_end_matches('/'), path)
+}
+
+fn authorize(request: ureq::Request, config: &IssueSourceConfig) -> ureq::Request {
+    match config.provider {
+        IssueProvider::GitHub => request
+            .set("Authorization", &format!("Bearer {}", config.token))
+            .set("Accept", "application/vnd.github+json")
+            .set("User-Agent", "pomodoro-app"),
+        IssueProvider::GitLab => request.set("PRIVATE-TOKEN", &config.token),
+    }
+}
+
+fn describe_error(error: ureq::Error) -> String {
+    match error {
+        ureq::Error::Status(code, response) => {
+            let body = response.into_string().unwrap_or_default();
+            format!("Issue API returned {}: {}", code, body.trim())
+        }
+        ureq::Error::Transport(transport) => format!("Issue API request failed: {}", transport),
+    }
+}
+
+// One page of issues, with the number of items the server sent before pull requests and
+// unusable items were dropped; only that number tells whether more pages follow
+fn fetch_page(config: &IssueSourceConfig, page: usize) -> Result<(Vec<RemoteIssue>, usize), String> {
+    let path = match config.provider {
+        IssueProvider::GitHub => "issues?filter=assigned&state=all",
+        IssueProvider::GitLab => "issues?scope=assigned_to_me&state=all",
+    };
+    let url = format!("{}&per_page={}&page={}", api_url(config, path), PER_PAGE, page);
+
+    let response = authorize(agent().get(&url), config)
+        .call()
+        .map_err(describe_error)?;
+
+    match config.provider {
+        IssueProvider::GitHub => {
+            let issues: Vec<GitHubIssue> = response
+                .into_json()
+                .map_err(|e| format!("Unexpected GitHub response: {}", e))?;
+            let count = issues.len();
+            // The issues endpoint also lists pull requests
+            let issues = issues
+                .into_iter()
+                .filter(|i| i.pull_request.is_none())
+                .filter_map(GitHubIssue::into_remote)
+                .collect();
+            Ok((issues, count))
+        }
+        IssueProvider::GitLab => {
+            let issues: Vec<GitLabIssue> = response
+                .into_json()
+                .map_err(|e| format!("Unexpected GitLab response: {}", e))?;
+            let count = issues.len();
+            Ok((issues.into_iter().map(GitLabIssue::into_remote).collect(), count))
+        }
+    }
+}
+
+fn fetch_all(config: &IssueSourceConfig) -> Result<Vec<RemoteIssue>, String> {
+    let mut issues = Vec::new();
+    for page in 1..=MAX_PAGES {
+        let (batch, count) = fetch_page(config, page)?;
+        issues.extend(batch);
+        if count < PER_PAGE {
+            break;
+        }
+    }
+    Ok(issues)
+}
+
+fn post_comment(config: &IssueSourceConfig, issue_id: &str, body: &str) -> Result<String, String> {
+    let (container, number) = issue_id
+        .rsplit_once('#')
+        .ok_or_else(|| format!("Invalid issue reference: {}", issue_id))?;
+
+    let path = match config.provider {
+        IssueProvider::GitHub => format!("repos/{}/issues/{}/comments", container, number),
+        IssueProvider::GitLab => format!("projects/{}/issues/{}/notes", container, number),
+    };
+
+    authorize(agent().post(&api_url(config, &path)), config)
+        .send_json(serde_json::json!({ "body": body }))
+        .map_err(describe_error)?;
+
+    Ok(format!("Commented on {}", issue_id))
+}
+
+fn credentials_path(app: &AppHandle) -> Result<PathBuf, String> {
+    credentials::credentials_path(app, CREDENTIALS_FILE)
+}
+
+fn store_credential(
+    path: &Path,
+    source: IssueSourceConfig,
+    replaces: Option<&str>,
+) -> Result<String, String> {
+    if source.token.trim().is_empty() {
+        return Err("Access token is empty".to_string());
+    }
+    credentials::store_credential(path, "issues", source, replaces)
+}
+
+fn lookup_credential(path: &Path, token_ref: &str) -> Result<IssueSourceConfig, String> {
+    credentials::lookup_credential(path, token_ref)?
+        .ok_or_else(|| "No access token saved for this issue source; enter it again in Settings".to_string())
+}
+
+// Saves the token for a provider and API root and returns the reference the frontend keeps
+#[tauri::command]
+pub async fn save_issue_token(
+    app: AppHandle,
+    provider: IssueProvider,
+    base_url: String,
+    token: String,
+    replaces: Option<String>,
+) -> Result<String, String> {
+    let source = IssueSourceConfig {
+        provider,
+        base_url: base_url.trim().to_string(),
+        token: token.trim().to_string(),
+    };
+    store_credential(&credentials_path(&app)?, source, replaces.as_deref())
+}
+
+// Issues assigned to the token's user, open and closed
+#[tauri::command]
+pub async fn fetch_assigned_issues(app: AppHandle, token_ref: String) -> Result<Vec<RemoteIssue>, String> {
+    let config = lookup_credential(&credentials_path(&app)?, &token_ref)?;
+    tauri::async_runtime::spawn_blocking(move || fetch_all(&config))
+        .await
+        .map_err(|e| format!("Issue import task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn post_issue_comment(
+    app: AppHandle,
+    token_ref: String,
+    issue_id: String,
+    body: String,
+) -> Result<String, String> {
+    let config = lookup_credential(&credentials_path(&app)?, &token_ref)?;
+    tauri::async_runtime::spawn_blocking(move || post_comment(&config, &issue_id, &body))
+        .await
+        .map_err(|e| format!("Issue comment task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{TestResponse, TestServer};
+    use std::time::SystemTime;
+
+    fn github(url: &str) -> IssueSourceConfig {
+        IssueSourceConfig {
+            provider: IssueProvider::GitHub,
+            base_url: format!("{}/", url),
+            token: "secret".to_string(),
+        }
+    }
+
+    fn github_issue(number: u64, state: &str, extra: &str) -> String {
+        format!(
+            r#"{{"number":{},"title":"Issue {}","body":" ","html_url":"https://github.com/acme/app/issues/{}","state":"{}","labels":[{{"name":"bug"}}],"updated_at":"2024-03-01T10:00:00Z"{}}}"#,
+            number, number, number, state, extra
+        )
+    }
+
+    #[test]
+    fn fetches_github_issues_without_pull_requests() {
+        let page = format!(
+            "[{},{}]",
+            github_issue(12, "open", r#","repository":{"full_name":"acme/app"}"#),
+            github_issue(13, "open", r#","pull_request":{}"#)
+        );
+        let server = TestServer::start(vec![TestResponse::new(200, &page)]);
+
+        let issues = fetch_all(&github(&server.url)).unwrap();
+
+        let request = server.request();
+        assert_eq!(request.line, "GET /issues?filter=assigned&state=all&per_page=100&page=1");
+        assert_eq!(request.header("authorization"), Some("Bearer secret"));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].id, "acme/app#12");
+        assert_eq!(issues[0].repository.as_deref(), Some("acme/app"));
+        assert_eq!(issues[0].body, None);
+        assert_eq!(issues[0].labels, vec!["bug"]);
+        assert!(issues[0].open);
+    }
+
+    #[test]
+    fn follows_pages_until_a_short_one() {
+        let full_page = format!(
+            "[{}]",
+            (1..=PER_PAGE as u64)
+                .map(|n| github_issue(n, "open", ""))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        let last_page = format!("[{}]", github_issue(500, "closed", ""));
+        let server = TestServer::start(vec![
+            TestResponse::new(200, &full_page),
+            TestResponse::new(200, &last_page),
+        ]);
+
+        let issues = fetch_all(&github(&server.url)).unwrap();
+
+        assert!(server.request().line.ends_with("&page=1"));
+        assert!(server.request().line.ends_with("&page=2"));
+        assert_eq!(issues.len(), PER_PAGE + 1);
+        // Without a repository object the name comes from the URL
+        assert_eq!(issues[PER_PAGE].id, "acme/app#500");
+        assert!(!issues[PER_PAGE].open);
+    }
+
+    #[test]
+    fn pages_with_pull_requests_are_not_mistaken_for_the_last() {
+        let full_page = format!(
+            "[{},{}]",
+            (1..PER_PAGE as u64)
+                .map(|n| github_issue(n, "open", ""))
+                .collect::<Vec<_>>()
+                .join(","),
+            github_issue(100, "open", r#","pull_request":{}"#)
+        );
+        let next_page = format!("[{},{}]", github_issue(101, "open", ""), github_issue(102, "open", ""));
+        let server = TestServer::start(vec![
+            TestResponse::new(200, &full_page),
+            TestResponse::new(200, &next_page),
+        ]);
+
+        let issues = fetch_all(&github(&server.url)).unwrap();
+
+        assert!(server.request().line.ends_with("&page=1"));
+        assert!(server.request().line.ends_with("&page=2"));
+        assert_eq!(issues.len(), PER_PAGE + 1);
+        assert!(!issues.iter().any(|i| i.id == "acme/app#100"));
+        assert_eq!(issues[PER_PAGE].id, "acme/app#102");
+    }
+
+    #[test]
+    fn maps_gitlab_issues_and_sends_private_token() {
+        let body = r#"[{"iid":7,"project_id":42,"title":"Fix login","description":"Steps","web_url":"https://gitlab.example.com/team/web/-/issues/7","state":"opened","labels":["auth"],"updated_at":"2024-03-02T09:00:00Z","due_date":"2024-03-10","references":{"full":"team/web#7"}}]"#;
+        let server = TestServer::start(vec![TestResponse::new(200, body)]);
+        let config = IssueSourceConfig {
+            provider: IssueProvider::GitLab,
+            base_url: server.url.clone(),
+            token: "glpat".to_string(),
+        };
+
+        let issues = fetch_all(&config).unwrap();
+
+        let request = server.request();
+        assert!(request.line.starts_with("GET /issues?scope=assigned_to_me&state=all"));
+        assert_eq!(request.header("private-token"), Some("glpat"));
+        assert_eq!(issues[0].id, "42#7");
+        assert_eq!(issues[0].repository.as_deref(), Some("team/web"));
+        assert_eq!(issues[0].due_date.as_deref(), Some("2024-03-10"));
+        assert_eq!(issues[0].body.as_deref(), Some("Steps"));
+    }
+
+    #[test]
+    fn posts_focus_comments() {
+        let server = TestServer::start(vec![TestResponse::new(201, "{}")]);
+
+        let result = post_comment(&github(&server.url), "acme/app#12", "🍅 25m").unwrap();
+
+        let request = server.request();
+        assert_eq!(request.line, "POST /repos/acme/app/issues/12/comments");
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&request.body).unwrap()["body"], "🍅 25m");
+        assert_eq!(result, "Commented on acme/app#12");
+        assert!(post_comment(&github(&server.url), "no-number", "x").is_err());
+    }
+
+    #[test]
+    fn reports_api_errors() {
+        let server = TestServer::start(vec![TestResponse::new(401, r#"{"message":"Bad credentials"}"#)]);
+
+        let error = fetch_all(&github(&server.url)).unwrap_err();
+
+        assert!(error.contains("401") && error.contains("Bad credentials"), "{}", error);
+    }
+
+    #[test]
+    fn stores_tokens_under_references() {
+        let dir = std::env::temp_dir().join(format!("pomodoro-issue-credentials-{:?}", SystemTime::now()));
+        let path = dir.join(CREDENTIALS_FILE);
+
+        let first = store_credential(&path, github("https://api.github.com"), None).unwrap();
+        assert_eq!(lookup_credential(&path, &first).unwrap(), github("https://api.github.com"));
+
+        let second = store_credential(&path, github("https://ghe.example.com/api/v3"), Some(&first)).unwrap();
+        assert!(lookup_credential(&path, &first).is_err());
+        assert_eq!(lookup_credential(&path, &second).unwrap().base_url, "https://ghe.example.com/api/v3/");
+
+        let mut empty = github("https://api.github.com");
+        empty.token = " ".to_string();
+        assert!(store_credential(&path, empty, None).is_err());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
@@ -3,6 +3,15 @@ use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::Manager;
 
+mod caldav;
+mod credentials;
+mod issues;
+mod reports;
+mod taskwarrior;
+mod todotxt;
+#[cfg(test)]
+mod test_server;
+
 
 #[tauri::command]
 async fn get_videos_dir(app: tauri::AppHandle) -> Result<String, String> {
@@ -559,6 +568,7 @@ async fn initialize_app_cleanup(app: tauri::AppHandle) -> Result<String, String>
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
+        .manage(todotxt::TodoTxtWatcher::default())
         .setup(|app| {
             // Run initial cleanup on app startup
             let app_handle = app.handle().clone();
@@ -582,7 +592,26 @@ pub fn run() {
             session_completed_notification,
             cleanup_old_videos,
             get_video_storage_info,
-            initialize_app_cleanup
+            initialize_app_cleanup,
+            todotxt::todo_txt_path,
+            todotxt::set_todo_txt_path,
+            todotxt::read_todo_txt,
+            todotxt::write_todo_txt,
+            todotxt::watch_todo_txt,
+            taskwarrior::taskwarrior_binary,
+            taskwarrior::taskwarrior_export,
+            taskwarrior::taskwarrior_annotate,
+            taskwarrior::taskwarrior_set_uda,
+            taskwarrior::taskwarrior_set_done,
+            issues::save_issue_token,
+            issues::fetch_assigned_issues,
+            issues::post_issue_comment,
+            caldav::save_caldav_credentials,
+            caldav::caldav_list_todos,
+            caldav::caldav_put_todo,
+            caldav::caldav_delete_todo,
+            reports::generate_weekly_report,
+            reports::export_timesheet
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,576 @@
+// Weekly productivity reports and billing timesheets. The frontend owns the session data,
+// so it sends the aggregated numbers and this module renders them as files.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeeklyReportTask {
+    name: String,
+    pomodoros: u32,  // completed work sessions this week
+    focus_time: u32, // seconds this week
+    estimated_pomodoros: Option<u32>,
+    total_pomodoros: u32, // completed work sessions overall, compared against the estimate
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeeklyReportBudgetEvent {
+    date: String, // YYYY-MM-DD the threshold was crossed
+    name: String,
+    threshold: u32, // percent of the estimate
+    estimated_pomodoros: u32,
+    actual_pomodoros: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeeklyReport {
+    week_start: String, // Monday YYYY-MM-DD
+    week_end: String,   // Sunday YYYY-MM-DD
+    total_focus_time: u32,
+    pomodoros: u32,
+    completed_sessions: u32,
+    interruptions: u32, // sessions stopped before they finished
+    interrupted_time: u32,
+    tasks: Vec<WeeklyReportTask>,
+    best_hours: Vec<(u32, u32)>,     // (hour of day, focus seconds), best first
+    daily_focus: Vec<(String, u32)>, // (YYYY-MM-DD, focus seconds), Monday first
+    current_streak: u32,
+    longest_streak: u32,
+    #[serde(default)]
+    scope_creep: Vec<WeeklyReportBudgetEvent>, // estimate thresholds crossed this week
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklyReportFiles {
+    markdown_path: String,
+    html_path: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimesheetEntry {
+    date: String,
+    task_id: String,
+    name: String, // "Task" or "Task → Subtask"
+    sessions: u32,
+    focus_time: u32,  // seconds tracked
+    billed_time: u32, // seconds after rounding
+    hourly_rate: Option<f64>,
+    amount: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimesheetTaskTotal {
+    task_id: String,
+    name: String,
+    focus_time: u32,
+    billed_time: u32,
+    amount: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Timesheet {
+    from: String,
+    to: String,
+    currency: String,
+    rounding: String,
+    entries: Vec<TimesheetEntry>, // grouped by task, then by day
+    tasks: Vec<TimesheetTaskTotal>,
+    total_focus_time: u32,
+    total_billed_time: u32,
+    total_amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimesheetFiles {
+    csv_path: String,
+    html_path: String,
+}
+
+fn format_duration(seconds: u32) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn format_hour(hour: u32) -> String {
+    format!("{:02}:00–{:02}:00", hour, (hour + 1) % 24)
+}
+
+fn estimate_text(task: &WeeklyReportTask) -> String {
+    match task.estimated_pomodoros {
+        Some(estimate) if task.total_pomodoros > estimate => {
+            format!("{} / {} (+{})", task.total_pomodoros, estimate, task.total_pomodoros - estimate)
+        }
+        Some(estimate) => format!("{} / {}", task.total_pomodoros, estimate),
+        None => format!("{} / –", task.total_pomodoros),
+    }
+}
+
+fn budget_event_text(event: &WeeklyReportBudgetEvent) -> String {
+    format!(
+        "{}: {} reached {}% of its estimate ({} / {} pomodoros)",
+        event.date, event.name, event.threshold, event.actual_pomodoros, event.estimated_pomodoros
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Pipes would split a Markdown table cell
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn render_markdown(report: &WeeklyReport) -> String {
+    let mut md = format!("# Weekly report: {} – {}\n\n", report.week_start, report.week_end);
+
+    md.push_str("## Summary\n\n");
+    md.push_str(&format!("- Total focus: **{}**\n", format_duration(report.total_focus_time)));
+    md.push_str(&format!("- Pomodoros: **{}**\n", report.pomodoros));
+    md.push_str(&format!("- Completed sessions: {}\n", report.completed_sessions));
+    md.push_str(&format!(
+        "- Interruptions: {} ({} lost)\n",
+        report.interruptions,
+        format_duration(report.interrupted_time)
+    ));
+    md.push_str(&format!(
+        "- Streak: {} days (longest {} days)\n\n",
+        report.current_streak, report.longest_streak
+    ));
+
+    md.push_str("## Daily focus\n\n| Day | Focus |\n| --- | --- |\n");
+    for (day, focus) in &report.daily_focus {
+        md.push_str(&format!("| {} | {} |\n", day, format_duration(*focus)));
+    }
+
+    md.push_str("\n## Tasks\n\n");
+    if report.tasks.is_empty() {
+        md.push_str("No task work this week.\n");
+    } else {
+        md.push_str("| Task | Pomodoros | Focus | Done vs estimate |\n| --- | --- | --- | --- |\n");
+        for task in &report.tasks {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                escape_cell(&task.name),
+                task.pomodoros,
+                format_duration(task.focus_time),
+                estimate_text(task)
+            ));
+        }
+    }
+
+    md.push_str("\n## Best focus hours\n\n");
+    if report.best_hours.is_empty() {
+        md.push_str("No focus time recorded.\n");
+    }
+    for (hour, focus) in &report.best_hours {
+        md.push_str(&format!("- {}: {}\n", format_hour(*hour), format_duration(*focus)));
+    }
+
+    md.push_str("\n## Scope creep\n\n");
+    if report.scope_creep.is_empty() {
+        md.push_str("No task passed a budget threshold.\n");
+    }
+    for event in &report.scope_creep {
+        md.push_str(&format!("- {}\n", budget_event_text(event)));
+    }
+
+    md
+}
+
+fn render_html(report: &WeeklyReport) -> String {
+    let title = format!("Weekly report: {} – {}", report.week_start, report.week_end);
+    let max_daily = report.daily_focus.iter().map(|(_, f)| *f).max().unwrap_or(0).max(1);
+
+    let mut body = format!("<h1>{}</h1>\n", escape_html(&title));
+    body.push_str("<div class=\"cards\">\n");
+    for (label, value) in [
+        ("Total focus", format_duration(report.total_focus_time)),
+        ("Pomodoros", report.pomodoros.to_string()),
+        ("Completed sessions", report.completed_sessions.to_string()),
+        (
+            "Interruptions",
+            format!("{} ({})", report.interruptions, format_duration(report.interrupted_time)),
+        ),
+        (
+            "Streak",
+            format!("{} days (longest {})", report.current_streak, report.longest_streak),
+        ),
+    ] {
+        body.push_str(&format!(
+            "<div class=\"card\"><div class=\"value\">{}</div><div class=\"label\">{}</div></div>\n",
+            escape_html(&value),
+            label
+        ));
+    }
+    body.push_str("</div>\n");
+
+    body.push_str("<h2>Daily focus</h2>\n<table>\n");
+    for (day, focus) in &report.daily_focus {
+        body.push_str(&format!(
+            "<tr><td>{}</td><td class=\"bar-cell\"><div class=\"bar\" style=\"width: {:.1}%\"></div></td><td>{}</td></tr>\n",
+            day,
+            *focus as f64 / max_daily as f64 * 100.0,
+            format_duration(*focus)
+        ));
+    }
+    body.push_str("</table>\n");
+
+    body.push_str("<h2>Tasks</h2>\n");
+    if report.tasks.is_empty() {
+        body.push_str("<p>No task work this week.</p>\n");
+    } else {
+        body.push_str(
+            "<table>\n<tr><th>Task</th><th>Pomodoros</th><th>Focus</th><th>Done vs estimate</th></tr>\n",
+        );
+        for task in &report.tasks {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape_html(&task.name),
+                task.pomodoros,
+                format_duration(task.focus_time),
+                escape_html(&estimate_text(task))
+            ));
+        }
+        body.push_str("</table>\n");
+    }
+
+    body.push_str("<h2>Best focus hours</h2>\n<ul>\n");
+    for (hour, focus) in &report.best_hours {
+        body.push_str(&format!(
+            "<li>{}: {}</li>\n",
+            escape_html(&format_hour(*hour)),
+            format_duration(*focus)
+        ));
+    }
+    body.push_str("</ul>\n");
+
+    body.push_str("<h2>Scope creep</h2>\n");
+    if report.scope_creep.is_empty() {
+        body.push_str("<p>No task passed a budget threshold.</p>\n");
+    } else {
+        body.push_str("<ul>\n");
+        for event in &report.scope_creep {
+            body.push_str(&format!("<li>{}</li>\n", escape_html(&budget_event_text(event))));
+        }
+        body.push_str("</ul>\n");
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+<style>
+body {{ font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; max-width: 760px; margin: 2rem auto; color: #1f2937; }}
+h1 {{ font-size: 1.5rem; }}
+h2 {{ font-size: 1.1rem; margin-top: 2rem; }}
+.cards {{ display: flex; flex-wrap: wrap; gap: 0.75rem; }}
+.card {{ background: #f3f4f6; border-radius: 0.5rem; padding: 0.75rem 1rem; min-width: 120px; }}
+.value {{ font-size: 1.25rem; font-weight: bold; }}
+.label {{ font-size: 0.8rem; color: #6b7280; }}
+table {{ border-collapse: collapse; width: 100%; }}
+td, th {{ text-align: left; padding: 0.35rem 0.5rem; border-bottom: 1px solid #e5e7eb; }}
+.bar-cell {{ width: 60%; }}
+.bar {{ background: #ef4444; height: 0.75rem; border-radius: 0.25rem; }}
+</style>
+</head>
+<body>
+{}</body>
+</html>
+"#,
+        escape_html(&title),
+        body
+    )
+}
+
+fn format_hours(seconds: u32) -> String {
+    format!("{:.2}", seconds as f64 / 3600.0)
+}
+
+fn format_money(amount: Option<f64>) -> String {
+    amount.map(|a| format!("{:.2}", a)).unwrap_or_default()
+}
+
+// Quotes a CSV field when it contains a separator, quote or line break. Text that a
+// spreadsheet would run as a formula gets a leading apostrophe.
+fn csv_field(text: &str) -> String {
+    let text = if text.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", text)
+    } else {
+        text.to_string()
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+fn render_timesheet_csv(timesheet: &Timesheet) -> String {
+    let mut csv = format!(
+        "Date,Task,Sessions,Tracked minutes,Billed hours,Hourly rate ({0}),Amount ({0})\n",
+        csv_field(&timesheet.currency)
+    );
+    for entry in &timesheet.entries {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            csv_field(&entry.date),
+            csv_field(&entry.name),
+            entry.sessions,
+            entry.focus_time / 60,
+            format_hours(entry.billed_time),
+            format_money(entry.hourly_rate),
+            format_money(entry.amount)
+        ));
+    }
+    csv.push_str(&format!(
+        ",Total,,{},{},,{:.2}\n",
+        timesheet.total_focus_time / 60,
+        format_hours(timesheet.total_billed_time),
+        timesheet.total_amount
+    ));
+    csv
+}
+
+fn render_timesheet_html(timesheet: &Timesheet) -> String {
+    let title = format!("Timesheet: {} – {}", timesheet.from, timesheet.to);
+    let currency = escape_html(&timesheet.currency);
+    let money = |amount: Option<f64>| match amount {
+        Some(amount) => format!("{:.2} {}", amount, currency),
+        None => "–".to_string(),
+    };
+
+    let mut body = format!("<h1>{}</h1>\n", escape_html(&title));
+    body.push_str(&format!("<p class=\"muted\">{}</p>\n", escape_html(&timesheet.rounding)));
+    body.push_str(
+        "<table>\n<tr><th>Date</th><th>Task</th><th class=\"num\">Tracked</th><th class=\"num\">Hours</th><th class=\"num\">Rate</th><th class=\"num\">Amount</th></tr>\n",
+    );
+    for task in &timesheet.tasks {
+        for entry in timesheet.entries.iter().filter(|e| e.task_id == task.task_id) {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                entry.date,
+                escape_html(&entry.name),
+                format_duration(entry.focus_time),
+                format_hours(entry.billed_time),
+                money(entry.hourly_rate),
+                money(entry.amount)
+            ));
+        }
+        body.push_str(&format!(
+            "<tr class=\"subtotal\"><td></td><td>{} total</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td></td><td class=\"num\">{}</td></tr>\n",
+            escape_html(&task.name),
+            format_duration(task.focus_time),
+            format_hours(task.billed_time),
+            money(Some(task.amount))
+        ));
+    }
+    body.push_str(&format!(
+        "<tr class=\"total\"><td></td><td>Total</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td></td><td class=\"num\">{}</td></tr>\n</table>\n",
+        format_duration(timesheet.total_focus_time),
+        format_hours(timesheet.total_billed_time),
+        money(Some(timesheet.total_amount))
+    ));
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+<style>
+body {{ font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; max-width: 800px; margin: 2rem auto; color: #1f2937; }}
+h1 {{ font-size: 1.5rem; margin-bottom: 0.25rem; }}
+.muted {{ color: #6b7280; font-size: 0.85rem; }}
+table {{ border-collapse: collapse; width: 100%; margin-top: 1.5rem; font-size: 0.9rem; }}
+td, th {{ text-align: left; padding: 0.35rem 0.5rem; border-bottom: 1px solid #e5e7eb; }}
+.num {{ text-align: right; white-space: nowrap; }}
+.subtotal td {{ font-weight: 600; border-bottom: 2px solid #d1d5db; }}
+.total td {{ font-weight: bold; font-size: 1.05rem; border-bottom: none; padding-top: 0.75rem; }}
+@media print {{ body {{ margin: 0; max-width: none; }} }}
+</style>
+</head>
+<body>
+{}</body>
+</html>
+"#,
+        escape_html(&title),
+        body
+    )
+}
+
+// Dates end up in file names, so only real YYYY-MM-DD dates are accepted
+fn validate_date(date: &str) -> Result<&str, String> {
+    let invalid = || format!("Invalid date: {}", date);
+    let bytes = date.as_bytes();
+    let shape_ok = bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        });
+    if !shape_ok {
+        return Err(invalid());
+    }
+
+    let number = |range: std::ops::Range<usize>| date[range].parse::<u32>().unwrap_or(0);
+    let (year, month, day) = (number(0..4), number(5..7), number(8..10));
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return Err(invalid()),
+    };
+    if day == 0 || day > days_in_month {
+        return Err(invalid());
+    }
+    Ok(date)
+}
+
+fn reports_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("reports");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create reports directory: {}", e))?;
+    Ok(dir)
+}
+
+// Writes weekly-<monday>.md and .html, replacing an earlier report for the same week
+#[tauri::command]
+pub async fn generate_weekly_report(app: AppHandle, report: WeeklyReport) -> Result<WeeklyReportFiles, String> {
+    let week_start = validate_date(&report.week_start)?;
+    let dir = reports_dir(&app)?;
+    let markdown_path = dir.join(format!("weekly-{}.md", week_start));
+    let html_path = dir.join(format!("weekly-{}.html", week_start));
+
+    std::fs::write(&markdown_path, render_markdown(&report))
+        .map_err(|e| format!("Failed to write Markdown report: {}", e))?;
+    std::fs::write(&html_path, render_html(&report))
+        .map_err(|e| format!("Failed to write HTML report: {}", e))?;
+
+    Ok(WeeklyReportFiles {
+        markdown_path: markdown_path.to_string_lossy().to_string(),
+        html_path: html_path.to_string_lossy().to_string(),
+    })
+}
+
+// Writes timesheet-<from>-<to>.csv and .html next to the weekly reports
+#[tauri::command]
+pub async fn export_timesheet(app: AppHandle, timesheet: Timesheet) -> Result<TimesheetFiles, String> {
+    let from = validate_date(&timesheet.from)?;
+    let to = validate_date(&timesheet.to)?;
+    let dir = reports_dir(&app)?;
+    let csv_path = dir.join(format!("timesheet-{}-{}.csv", from, to));
+    let html_path = dir.join(format!("timesheet-{}-{}.html", from, to));
+
+    std::fs::write(&csv_path, render_timesheet_csv(&timesheet))
+        .map_err(|e| format!("Failed to write timesheet CSV: {}", e))?;
+    std::fs::write(&html_path, render_timesheet_html(&timesheet))
+        .map_err(|e| format!("Failed to write timesheet HTML: {}", e))?;
+
+    Ok(TimesheetFiles {
+        csv_path: csv_path.to_string_lossy().to_string(),
+        html_path: html_path.to_string_lossy().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(date: &str, name: &str, focus_time: u32, amount: Option<f64>) -> TimesheetEntry {
+        TimesheetEntry {
+            date: date.to_string(),
+            task_id: "task_1".to_string(),
+            name: name.to_string(),
+            sessions: 2,
+            focus_time,
+            billed_time: 3600,
+            hourly_rate: amount.map(|_| 80.0),
+            amount,
+        }
+    }
+
+    fn timesheet(entries: Vec<TimesheetEntry>) -> Timesheet {
+        Timesheet {
+            from: "2024-01-15".to_string(),
+            to: "2024-01-21".to_string(),
+            currency: "EUR".to_string(),
+            rounding: "Rounded up to 15 minutes".to_string(),
+            entries,
+            tasks: Vec::new(),
+            total_focus_time: 6000,
+            total_billed_time: 7200,
+            total_amount: 80.0,
+        }
+    }
+
+    #[test]
+    fn neutralises_formula_cells() {
+        assert_eq!(csv_field("=HYPERLINK(\"http://x\")"), "\"'=HYPERLINK(\"\"http://x\"\")\"");
+        assert_eq!(csv_field("+1"), "'+1");
+        assert_eq!(csv_field("-2"), "'-2");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("Write report"), "Write report");
+        assert_eq!(csv_field("a - b"), "a - b");
+    }
+
+    #[test]
+    fn renders_timesheet_csv() {
+        let csv = render_timesheet_csv(&timesheet(vec![
+            entry("2024-01-15", "Client, Inc. → \"Kickoff\"", 3000, Some(80.0)),
+            entry("2024-01-16", "=1+1", 3000, None),
+        ]));
+
+        assert_eq!(
+            csv,
+            "Date,Task,Sessions,Tracked minutes,Billed hours,Hourly rate (EUR),Amount (EUR)\n\
+             2024-01-15,\"Client, Inc. → \"\"Kickoff\"\"\",2,50,1.00,80.00,80.00\n\
+             2024-01-16,'=1+1,2,50,1.00,,\n\
+             ,Total,,100,2.00,,80.00\n"
+        );
+    }
+
+    #[test]
+    fn accepts_calendar_dates() {
+        assert_eq!(validate_date("2024-01-15"), Ok("2024-01-15"));
+        assert!(validate_date("2024-02-29").is_ok());
+        assert!(validate_date("2000-02-29").is_ok());
+        assert!(validate_date("2024-12-31").is_ok());
+    }
+
+    #[test]
+    fn rejects_anything_but_yyyy_mm_dd() {
+        for date in [
+            "",
+            "2024-1-15",
+            "2024/01/15",
+            "2024-01-15.md",
+            "../../etc/passwd",
+            "2024-01-1x",
+            "２０２４-01-15",
+            "2024-13-01",
+            "2024-00-10",
+            "2024-04-31",
+            "2023-02-29",
+            "1900-02-29",
+            "2024-01-00",
+        ] {
+            assert!(validate_date(date).is_err(), "{}", date);
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Manager};
+
+// Settings overrides passed on every call so Taskwarrior never prompts or prints extra output
+const RC_OVERRIDES: [&str; 2] = ["rc.confirmation=off", "rc.verbose=nothing"];
+
+// Backend-only config in the app config dir. The webview never chooses what gets executed.
+const CONFIG_FILE: &str = "taskwarrior.json";
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct TaskwarriorConfig {
+    #[serde(default)]
+    binary: Option<PathBuf>, // absolute path to `task` or a script that behaves like it
+}
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config dir: {}", e))?;
+    Ok(config_dir.join(CONFIG_FILE))
+}
+
+// A missing file means the default: `task` from PATH
+fn load_config(path: &Path) -> Result<TaskwarriorConfig, String> {
+    if !path.exists() {
+        return Ok(TaskwarriorConfig::default());
+    }
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&json).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+fn find_on_path(name: &str, path_var: Option<&OsStr>) -> Option<PathBuf> {
+    let names = if cfg!(windows) {
+        vec![format!("{}.exe", name), name.to_string()]
+    } else {
+        vec![name.to_string()]
+    };
+    std::env::split_paths(path_var?)
+        .flat_map(|dir| names.iter().map(move |n| dir.join(n)).collect::<Vec<_>>())
+        .find(|candidate| candidate.is_file())
+}
+
+// The configured binary must be an absolute path to an existing file; otherwise `task` is looked up on PATH
+fn resolve_binary(config: &TaskwarriorConfig, path_var: Option<&OsStr>) -> Result<PathBuf, String> {
+    match &config.binary {
+        Some(binary) if !binary.is_absolute() => Err(format!(
+            "Taskwarrior binary must be an absolute path: {}",
+            binary.display()
+        )),
+        Some(binary) if !binary.is_file() => Err(format!(
+            "Taskwarrior binary not found: {}",
+            binary.display()
+        )),
+        Some(binary) => Ok(binary.clone()),
+        None => find_on_path("task", path_var).ok_or_else(|| "`task` was not found on PATH".to_string()),
+    }
+}
+
+fn configured_binary(app: &AppHandle) -> Result<PathBuf, String> {
+    let path = config_path(app)?;
+    let config = load_config(&path)?;
+    resolve_binary(&config, std::env::var_os("PATH").as_deref())
+        .map_err(|e| format!("{} (configure it in {})", e, path.display()))
+}
+
+// Runs Taskwarrior, or the fake script standing in for it
+fn run_task(binary: &Path, args: &[String]) -> Result<String, String> {
+    let output = Command::new(binary)
+        .args(RC_OVERRIDES)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", binary.display(), e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "{} exited with {}: {}",
+            binary.display(),
+            output.status,
+            stderr.trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// UUIDs are passed as filters, so anything else could select unrelated tasks
+fn validate_uuid(uuid: &str) -> Result<(), String> {
+    let valid = uuid.len() == 36
+        && uuid
+            .chars()
+            .enumerate()
+            .all(|(i, c)| match i {
+                8 | 13 | 18 | 23 => c == '-',
+                _ => c.is_ascii_hexdigit(),
+            });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid Taskwarrior UUID: {}", uuid))
+    }
+}
+
+fn validate_uda_name(name: &str) -> Result<(), String> {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(())
+    } else {
+        Err(format!("Invalid UDA name: {}", name))
+    }
+}
+
+// Path of the binary that will be run, shown in settings
+#[tauri::command]
+pub async fn taskwarrior_binary(app: AppHandle) -> Result<String, String> {
+    configured_binary(&app).map(|binary| binary.to_string_lossy().to_string())
+}
+
+// Returns the raw `task export` JSON array
+#[tauri::command]
+pub async fn taskwarrior_export(app: AppHandle) -> Result<String, String> {
+    run_task(&configured_binary(&app)?, &["export".to_string()])
+}
+
+#[tauri::command]
+pub async fn taskwarrior_annotate(app: AppHandle, uuid: String, text: String) -> Result<String, String> {
+    validate_uuid(&uuid)?;
+    run_task(
+        &configured_binary(&app)?,
+        &[uuid, "annotate".to_string(), "--".to_string(), text],
+    )
+}
+
+// Sets a numeric UDA. The UDA is declared for this call, so it works without editing .taskrc.
+#[tauri::command]
+pub async fn taskwarrior_set_uda(
+    app: AppHandle,
+    uuid: String,
+    uda: String,
+    value: u32,
+) -> Result<String, String> {
+    validate_uuid(&uuid)?;
+    validate_uda_name(&uda)?;
+    run_task(
+        &configured_binary(&app)?,
+        &[
+            format!("rc.uda.{}.type=numeric", uda),
+            format!("rc.uda.{}.label=Focus (min)", uda),
+            uuid,
+            "modify".to_string(),
+            format!("{}:{}", uda, value),
+        ],
+    )
+}
+
+// Marks the task done, or back to pending when `done` is false
+#[tauri::command]
+pub async fn taskwarrior_set_done(app: AppHandle, uuid: String, done: bool) -> Result<String, String> {
+    validate_uuid(&uuid)?;
+    let args = if done {
+        vec![uuid, "done".to_string()]
+    } else {
+        vec![uuid, "modify".to_string(), "status:pending".to_string()]
+    };
+    run_task(&configured_binary(&app)?, &args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    const UUID: &str = "0f7b4a2e-5d1c-4c3b-9a8e-1b2c3d4e5f60";
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let dir = std::env::temp_dir().join(format!("pomodoro-taskwarrior-{}-{}", name, nanos));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // Writes an executable `task` script into `dir`
+    #[cfg(unix)]
+    fn fake_task(dir: &Path, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join("task");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_fake_script_with_overrides_and_arguments() {
+        let dir = temp_dir("args");
+        let binary = fake_task(&dir, "printf '%s|' \"$@\"");
+
+        let args = [UUID, "annotate", "--", "25m focus"].map(String::from);
+        let output = run_task(&binary, &args).unwrap();
+
+        assert_eq!(
+            output,
+            format!("rc.confirmation=off|rc.verbose=nothing|{}|annotate|--|25m focus|", UUID)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_failures_with_stderr() {
+        let dir = temp_dir("fail");
+        let binary = fake_task(&dir, "echo 'No matches.' >&2; exit 1");
+
+        let error = run_task(&binary, &["export".to_string()]).unwrap_err();
+
+        assert!(error.contains("No matches."), "{}", error);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolves_configured_binary_or_task_on_path() {
+        let dir = temp_dir("resolve");
+        let binary = fake_task(&dir, "echo '[]'");
+
+        let configured = TaskwarriorConfig { binary: Some(binary.clone()) };
+        assert_eq!(resolve_binary(&configured, None).unwrap(), binary);
+
+        let from_path = resolve_binary(&TaskwarriorConfig::default(), Some(dir.as_os_str())).unwrap();
+        assert_eq!(from_path, binary);
+        assert_eq!(run_task(&from_path, &["export".to_string()]).unwrap().trim(), "[]");
+    }
+
+    #[test]
+    fn rejects_relative_and_missing_binaries() {
+        let dir = temp_dir("reject");
+
+        let relative = TaskwarriorConfig { binary: Some(PathBuf::from("bin/task")) };
+        assert!(resolve_binary(&relative, None).unwrap_err().contains("absolute"));
+
+        let missing = TaskwarriorConfig { binary: Some(dir.join("nope")) };
+        assert!(resolve_binary(&missing, None).unwrap_err().contains("not found"));
+
+        assert!(resolve_binary(&TaskwarriorConfig::default(), Some(dir.as_os_str())).is_err());
+    }
+
+    #[test]
+    fn loads_config_file() {
+        let dir = temp_dir("config");
+        let path = dir.join(CONFIG_FILE);
+        assert!(load_config(&path).unwrap().binary.is_none());
+
+        std::fs::write(&path, r#"{ "binary": "/usr/local/bin/task" }"#).unwrap();
+        assert_eq!(load_config(&path).unwrap().binary, Some(PathBuf::from("/usr/local/bin/task")));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(load_config(&path).is_err());
+    }
+
+    #[test]
+    fn validates_uuids_and_uda_names() {
+        assert!(validate_uuid(UUID).is_ok());
+        assert!(validate_uuid("1").is_err());
+        assert!(validate_uuid(&UUID.replace('-', "x")).is_err());
+        assert!(validate_uda_name("pomodoro_focus").is_ok());
+        assert!(validate_uda_name("rc.data.location").is_err());
+    }
+}
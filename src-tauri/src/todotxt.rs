@@ -0,0 +1,255 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+// Prefix of the error returned when the file changed since the caller last read it
+pub const CONFLICT_ERROR_PREFIX: &str = "conflict:";
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+// Backend config in the app config dir. Only the file saved here can be read, written or watched.
+const CONFIG_FILE: &str = "todotxt.json";
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct TodoTxtConfig {
+    #[serde(default)]
+    path: Option<PathBuf>,
+}
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config dir: {}", e))?;
+    Ok(config_dir.join(CONFIG_FILE))
+}
+
+// A missing file means no todo.txt is configured
+fn load_config(path: &Path) -> Result<TodoTxtConfig, String> {
+    if !path.exists() {
+        return Ok(TodoTxtConfig::default());
+    }
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&json).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+fn save_config(path: &Path, config: &TodoTxtConfig) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
+
+// Only absolute paths to .txt files can be configured
+fn validate_todo_path(path: &str) -> Result<PathBuf, String> {
+    let file_path = PathBuf::from(path.trim());
+    if !file_path.is_absolute() {
+        return Err(format!("todo.txt path must be absolute: {}", path));
+    }
+    if !file_path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("txt")) {
+        return Err(format!("todo.txt path must end in .txt: {}", path));
+    }
+    if file_path.is_dir() {
+        return Err(format!("todo.txt path is a directory: {}", path));
+    }
+    Ok(file_path)
+}
+
+fn check_configured(config: &TodoTxtConfig, path: &str) -> Result<(), String> {
+    match &config.path {
+        Some(configured) if configured == Path::new(path) => Ok(()),
+        Some(_) => Err(format!("{} is not the todo.txt file configured in Settings", path)),
+        None => Err("No todo.txt file is configured in Settings".to_string()),
+    }
+}
+
+fn ensure_configured(app: &AppHandle, path: &str) -> Result<(), String> {
+    check_configured(&load_config(&config_path(app)?)?, path)
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct TodoFileSnapshot {
+    path: String,
+    content: String,
+    revision: String, // content hash, used for optimistic concurrency on writes
+}
+
+// Currently watched path and the flag that stops its polling thread
+#[derive(Default)]
+pub struct TodoTxtWatcher(Mutex<Option<(String, Arc<AtomicBool>)>>);
+
+fn content_revision(content: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn read_snapshot(path: &str) -> Result<TodoFileSnapshot, String> {
+    let file_path = PathBuf::from(path);
+
+    // A missing file is treated as empty so it can be created on first write
+    let content = if file_path.exists() {
+        std::fs::read_to_string(&file_path)
+            .map_err(|e| format!("Failed to read todo.txt: {}", e))?
+    } else {
+        String::new()
+    };
+
+    Ok(TodoFileSnapshot {
+        path: path.to_string(),
+        revision: content_revision(&content),
+        content,
+    })
+}
+
+// Configured file, shown in settings
+#[tauri::command]
+pub async fn todo_txt_path(app: AppHandle) -> Result<Option<String>, String> {
+    let config = load_config(&config_path(&app)?)?;
+    Ok(config.path.map(|path| path.to_string_lossy().to_string()))
+}
+
+// Saves the file the other todo.txt commands are limited to; no path clears it
+#[tauri::command]
+pub async fn set_todo_txt_path(app: AppHandle, path: Option<String>) -> Result<Option<String>, String> {
+    let path = path.filter(|p| !p.trim().is_empty()).map(|p| validate_todo_path(&p)).transpose()?;
+    save_config(&config_path(&app)?, &TodoTxtConfig { path: path.clone() })?;
+    Ok(path.map(|path| path.to_string_lossy().to_string()))
+}
+
+#[tauri::command]
+pub async fn read_todo_txt(app: AppHandle, path: String) -> Result<TodoFileSnapshot, String> {
+    ensure_configured(&app, &path)?;
+    read_snapshot(&path)
+}
+
+#[tauri::command]
+pub async fn write_todo_txt(
+    app: AppHandle,
+    path: String,
+    content: String,
+    expected_revision: String,
+) -> Result<TodoFileSnapshot, String> {
+    ensure_configured(&app, &path)?;
+    let current = read_snapshot(&path)?;
+    if current.revision != expected_revision {
+        return Err(format!(
+            "{} todo.txt was changed by another program since it was last read",
+            CONFLICT_ERROR_PREFIX
+        ));
+    }
+
+    // Write to a sibling file first so other editors never see a half-written file
+    let file_path = PathBuf::from(&path);
+    let temp_path = file_path.with_extension("txt.pomodoro-tmp");
+    std::fs::write(&temp_path, &content)
+        .map_err(|e| format!("Failed to write todo.txt: {}", e))?;
+    std::fs::rename(&temp_path, &file_path)
+        .map_err(|e| format!("Failed to replace todo.txt: {}", e))?;
+
+    read_snapshot(&path)
+}
+
+// Starts polling `path` and emits "todotxt-changed" with a fresh snapshot whenever it changes.
+// Passing no path stops the current watcher.
+#[tauri::command]
+pub async fn watch_todo_txt(
+    app: AppHandle,
+    watcher: State<'_, TodoTxtWatcher>,
+    path: Option<String>,
+) -> Result<String, String> {
+    let mut current = watcher
+        .inner()
+        .0
+        .lock()
+        .map_err(|_| "todo.txt watcher state is poisoned".to_string())?;
+
+    if let Some((_, stop)) = current.take() {
+        stop.store(true, Ordering::Relaxed);
+    }
+
+    let Some(path) = path else {
+        return Ok("Stopped watching todo.txt".to_string());
+    };
+    ensure_configured(&app, &path)?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    *current = Some((path.clone(), stop.clone()));
+
+    let watched_path = path.clone();
+    std::thread::spawn(move || {
+        let mut last_revision = read_snapshot(&watched_path).ok().map(|s| s.revision);
+
+        while !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(WATCH_INTERVAL);
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+
+            match read_snapshot(&watched_path) {
+                Ok(snapshot) => {
+                    if last_revision.as_deref() != Some(snapshot.revision.as_str()) {
+                        last_revision = Some(snapshot.revision.clone());
+                        if let Err(e) = app.emit("todotxt-changed", snapshot) {
+                            eprintln!("Failed to emit todo.txt change: {}", e);
+                        }
+                    }
+                }
+                Err(e) => eprintln!("todo.txt watcher: {}", e),
+            }
+        }
+    });
+
+    Ok(format!("Watching {}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let dir = std::env::temp_dir().join(format!("pomodoro-todotxt-{}-{}", name, nanos));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn only_absolute_txt_files_can_be_configured() {
+        let dir = temp_dir("validate");
+        let todo = dir.join("todo.txt");
+
+        assert_eq!(validate_todo_path(todo.to_str().unwrap()).unwrap(), todo);
+        assert!(validate_todo_path("todo.txt").unwrap_err().contains("absolute"));
+        assert!(validate_todo_path(dir.join(".bashrc").to_str().unwrap()).unwrap_err().contains(".txt"));
+
+        let folder = dir.join("notes.txt");
+        std::fs::create_dir_all(&folder).unwrap();
+        assert!(validate_todo_path(folder.to_str().unwrap()).unwrap_err().contains("directory"));
+    }
+
+    #[test]
+    fn rejects_paths_other_than_the_configured_one() {
+        let dir = temp_dir("config");
+        let path = dir.join(CONFIG_FILE);
+        let todo = dir.join("todo.txt");
+        let todo = todo.to_str().unwrap();
+
+        let config = load_config(&path).unwrap();
+        assert!(config.path.is_none());
+        assert!(check_configured(&config, todo).is_err());
+
+        save_config(&path, &TodoTxtConfig { path: Some(PathBuf::from(todo)) }).unwrap();
+        let config = load_config(&path).unwrap();
+        assert!(check_configured(&config, todo).is_ok());
+        assert!(check_configured(&config, "/etc/passwd").unwrap_err().contains("not the todo.txt file"));
+    }
+}
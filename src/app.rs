@@ -1,31 +1,65 @@
 use crate::components::{
-    CameraController, CameraRecorder, CameraSettings, SessionHistory, SessionSelector,
+    BudgetAlertBanner, BudgetAlertSettingsPanel, CameraController, CameraRecorder, CameraSettings, CommandPalette, DailyGoalProgress,
+    DailyGoalSettingsPanel, FocusChartsPanel, IntegrationSettings,
+    SessionCalendar, SessionHistory,
+    SessionSelector,
     SessionStats, TimerControls, TimerDisplay, TimerSettings, TaskSelector, TaskManager, TaskStats,
-    ThemeSettings,
+    ThemeSettings, TimesheetPanel, TodayPlanner, UndoToast, WeeklyReportPanel,
 };
+use crate::caldav::CalDavSync;
 use crate::cleanup_scheduler::CleanupScheduler;
+use crate::reports::ReportScheduler;
 use crate::console_log;
+use crate::issues::IssueSync;
 use crate::keyboard_shortcuts::{KeyboardShortcuts, KeyboardShortcutsHelp};
 use crate::theme::ThemeController;
 use crate::timer::TimerController;
 use crate::task::TaskController;
+use crate::taskwarrior::TaskwarriorSync;
+use crate::todotxt::TodoTxtSync;
 use crate::types::{CameraState, TimerState};
 use leptos::prelude::*;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum AppTab {
+pub enum AppTab {
     Timer,
+    Today,
     Tasks,
     History,
     Statistics,
     Settings,
 }
 
+impl AppTab {
+    pub fn all() -> Vec<AppTab> {
+        vec![
+            AppTab::Timer,
+            AppTab::Today,
+            AppTab::Tasks,
+            AppTab::History,
+            AppTab::Statistics,
+            AppTab::Settings,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AppTab::Timer => "Timer",
+            AppTab::Today => "Today",
+            AppTab::Tasks => "Tasks",
+            AppTab::History => "History",
+            AppTab::Statistics => "Stats",
+            AppTab::Settings => "Settings",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum SettingsTab {
     Timer,
     Camera,
     Theme,
+    Integrations,
 }
 
 #[component]
@@ -39,6 +73,21 @@ pub fn App() -> impl IntoView {
     // Initialize cleanup scheduler (cronjob-like functionality)
     let _cleanup_scheduler = CleanupScheduler::new();
 
+    // Initialize todo.txt sync (watches the configured file through the backend)
+    let todotxt_sync = TodoTxtSync::new(task_controller.clone());
+
+    // Initialize Taskwarrior import (runs the configured `task` binary)
+    let taskwarrior_sync = TaskwarriorSync::new(task_controller.clone());
+
+    // Initialize issue import (GitHub/GitLab-compatible APIs, fetched by the backend)
+    let issue_sync = IssueSync::new(task_controller.clone());
+
+    // Initialize CalDAV sync (runs on demand and on its own interval)
+    let caldav_sync = CalDavSync::new(task_controller.clone());
+
+    // Initialize weekly reports (written on demand, or every Monday when enabled)
+    let report_scheduler = ReportScheduler::new();
+
     // Initialize keyboard shortcuts
     let _keyboard_shortcuts = KeyboardShortcuts::new(
         timer_controller.clone(),
@@ -117,6 +166,19 @@ pub fn App() -> impl IntoView {
                     >
                         "Timer"
                     </button>
+                    <button
+                        class={move || format!(
+                            "flex-1 py-3 px-4 text-center font-medium transition-colors {}",
+                            if active_tab.get() == AppTab::Today {
+                                "border-b-2 border-blue-500 text-blue-600 dark:text-blue-400"
+                            } else {
+                                "text-gray-600 dark:text-gray-400 hover:text-gray-800 dark:hover:text-gray-200"
+                            }
+                        )}
+                        on:click=move |_| active_tab.set(AppTab::Today)
+                    >
+                        "Today"
+                    </button>
                     <button
                         class={move || format!(
                             "flex-1 py-3 px-4 text-center font-medium transition-colors {}",
@@ -178,6 +240,10 @@ pub fn App() -> impl IntoView {
                         let camera_controller_clone = camera_controller.clone();
                         let task_controller_clone = task_controller.clone();
                         let theme_controller_clone = theme_controller.clone();
+                        let todotxt_sync_clone = todotxt_sync.clone();
+                        let taskwarrior_sync_clone = taskwarrior_sync.clone();
+                        let issue_sync_clone = issue_sync.clone();
+                        let caldav_sync_clone = caldav_sync.clone();
                         
                         move || {
                             match active_tab.get() {
@@ -219,6 +285,12 @@ pub fn App() -> impl IntoView {
                                             </div>
                                         </div>
 
+                                        // Daily Goal
+                                        <DailyGoalProgress timer_controller=timer_controller_timer.clone() />
+
+                                        // Budget Alerts
+                                        <BudgetAlertBanner timer_controller=timer_controller_timer.clone() />
+
                                         // Task Selection
                                         <TaskSelector task_controller=task_controller_timer.clone() />
 
@@ -314,6 +386,15 @@ pub fn App() -> impl IntoView {
                                 }.into_any()
                             },
 
+                            AppTab::Today => view! {
+                                <div class="max-w-3xl mx-auto">
+                                    <TodayPlanner
+                                        task_controller=task_controller_clone.clone()
+                                        timer_controller=timer_controller_clone.clone()
+                                    />
+                                </div>
+                            }.into_any(),
+
                             AppTab::Tasks => view! {
                                 <div class="max-w-6xl mx-auto">
                                     <TaskManager task_controller=task_controller_clone.clone() />
@@ -322,7 +403,14 @@ pub fn App() -> impl IntoView {
 
                             AppTab::History => view! {
                                 <div>
-                                    <SessionHistory controller=timer_controller_clone.clone() />
+                                    <SessionCalendar
+                                        task_controller=task_controller_clone.clone()
+                                        timer_controller=timer_controller_clone.clone()
+                                    />
+                                    <SessionHistory
+                                        controller=timer_controller_clone.clone()
+                                        task_controller=task_controller_clone.clone()
+                                    />
                                 </div>
                             }.into_any(),
 
@@ -339,6 +427,18 @@ pub fn App() -> impl IntoView {
                                             <TaskStats task_controller=task_controller_clone.clone() />
                                         </div>
                                     </div>
+
+                                    <FocusChartsPanel
+                                        task_controller=task_controller_clone.clone()
+                                        timer_controller=timer_controller_clone.clone()
+                                    />
+
+                                    <WeeklyReportPanel scheduler=report_scheduler />
+
+                                    <TimesheetPanel
+                                        task_controller=task_controller_clone.clone()
+                                        timer_controller=timer_controller_clone.clone()
+                                    />
                                 </div>
                             }.into_any(),
 
@@ -387,6 +487,19 @@ pub fn App() -> impl IntoView {
                                         >
                                             "🎨 Theme"
                                         </button>
+                                        <button
+                                            class={move || format!(
+                                                "py-2 px-4 font-medium transition-colors {}",
+                                                if active_settings_tab.get() == SettingsTab::Integrations {
+                                                    "border-b-2 border-blue-500 text-blue-600 dark:text-blue-400"
+                                                } else {
+                                                    "text-gray-600 dark:text-gray-400 hover:text-gray-800 dark:hover:text-gray-200"
+                                                }
+                                            )}
+                                            on:click=move |_| active_settings_tab.set(SettingsTab::Integrations)
+                                        >
+                                            "🔗 Integrations"
+                                        </button>
                                     </div>
 
                                     // Settings content
@@ -394,11 +507,18 @@ pub fn App() -> impl IntoView {
                                         let timer_controller_settings = timer_controller_clone.clone();
                                         let camera_controller_settings = camera_controller_clone.clone();
                                         let theme_controller_settings = theme_controller_clone.clone();
+                                        let task_controller_settings = task_controller_clone.clone();
+                                        let todotxt_sync_settings = todotxt_sync_clone.clone();
+                                        let taskwarrior_sync_settings = taskwarrior_sync_clone.clone();
+                                        let issue_sync_settings = issue_sync_clone.clone();
+                                        let caldav_sync_settings = caldav_sync_clone.clone();
                                         
                                         move || {
                                             match active_settings_tab.get() {
                                                 SettingsTab::Timer => view! {
                                                     <TimerSettings controller=timer_controller_settings.clone() />
+                                                    <DailyGoalSettingsPanel timer_controller=timer_controller_settings.clone() />
+                                                    <BudgetAlertSettingsPanel timer_controller=timer_controller_settings.clone() />
                                                 }.into_any(),
                                                 SettingsTab::Camera => view! {
                                                     <CameraSettings controller=camera_controller_settings.clone() />
@@ -406,6 +526,15 @@ pub fn App() -> impl IntoView {
                                                 SettingsTab::Theme => view! {
                                                     <ThemeSettings theme_controller=theme_controller_settings.clone() />
                                                 }.into_any(),
+                                                SettingsTab::Integrations => view! {
+                                                    <IntegrationSettings
+                                                        todotxt_sync=todotxt_sync_settings.clone()
+                                                        taskwarrior_sync=taskwarrior_sync_settings.clone()
+                                                        issue_sync=issue_sync_settings.clone()
+                                                        caldav_sync=caldav_sync_settings.clone()
+                                                        task_controller=task_controller_settings.clone()
+                                                    />
+                                                }.into_any(),
                                             }
                                         }
                                     }
@@ -417,6 +546,20 @@ pub fn App() -> impl IntoView {
                 </div>
             </div>
 
+            // Ctrl+K command palette
+            <CommandPalette
+                task_controller=task_controller.clone()
+                timer_controller=timer_controller.clone()
+                theme_controller=theme_controller.clone()
+                active_tab=active_tab
+            />
+
+            // Undo/redo toast and Ctrl+Z
+            <UndoToast
+                task_controller=task_controller.clone()
+                timer_controller=timer_controller.clone()
+            />
+
             // Floating Keyboard Shortcuts Help Button
             <KeyboardShortcutsHelp />
         </main>
@@ -0,0 +1,1075 @@
+// Two-way sync between tasks/subtasks and VTODO items in a CalDAV collection.
+//
+// Each linked record keeps the server ETag in its ExternalRef. A separate map of
+// "fingerprints" (the synced fields as they were after the last sync) tells whether the
+// local side changed since then, so every item falls into one of four cases: unchanged,
+// changed on the server (pull), changed locally (push with If-Match) or both (conflict).
+// The map also keeps each item's last ETag, so items deleted locally can be deleted on the
+// server with If-Match even though their record is gone.
+
+use crate::console_log;
+use crate::task::TaskController;
+use crate::todotxt::CONFLICT_ERROR_PREFIX;
+use crate::types::{
+    delete_subtask_from_db, get_all_subtasks, get_all_tasks, local_start, save_all_subtasks,
+    save_all_tasks, save_subtask_to_db, save_task_to_db, ExternalRef, LocalStart, NewSubTask,
+    NewTask, SubTask, SyncConflict, Task, TaskPriority,
+};
+use crate::utils::{iso_from_basic_date_time, zoned_time_to_utc_iso};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{window, Storage};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
+    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+}
+
+pub const CALDAV_SOURCE: &str = "caldav";
+
+const UID_SUFFIX: &str = "@pomodoro-app";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalDavSettings {
+    pub enabled: bool,
+    pub collection_url: String,
+    pub username: String,
+    #[serde(default)]
+    pub credential_ref: Option<String>, // the backend keeps the URL, username and password under this
+    pub project_id: Option<String>, // only tasks of this project are pushed; None pushes all
+    pub interval_minutes: u32,
+    // Plaintext password saved by older versions, moved to the backend on startup
+    #[serde(default, rename = "password", skip_serializing)]
+    pub legacy_password: Option<String>,
+}
+
+impl Default for CalDavSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            collection_url: String::new(),
+            username: String::new(),
+            credential_ref: None,
+            project_id: None,
+            interval_minutes: 15,
+            legacy_password: None,
+        }
+    }
+}
+
+impl CalDavSettings {
+    pub fn save_to_storage(&self) {
+        if let Ok(settings_json) = serde_json::to_string(&self) {
+            if let Some(storage) = get_local_storage() {
+                let _ = storage.set_item("pomodoro_caldav_settings", &settings_json);
+            }
+        }
+    }
+
+    pub fn load_from_storage() -> Self {
+        if let Some(storage) = get_local_storage() {
+            if let Ok(Some(settings_json)) = storage.get_item("pomodoro_caldav_settings") {
+                if let Ok(settings) = serde_json::from_str::<CalDavSettings>(&settings_json) {
+                    return settings;
+                }
+            }
+        }
+        Self::default()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.collection_url.trim().is_empty() && self.credential_ref.is_some()
+    }
+
+    fn credential_ref(&self) -> Result<&str, String> {
+        self.credential_ref.as_deref().ok_or_else(|| "No CalDAV login saved".to_string())
+    }
+}
+
+fn get_local_storage() -> Option<Storage> {
+    window()?.local_storage().ok()?
+}
+
+// An item as it was after the last successful sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncedItem {
+    fingerprint: String, // of the synced fields
+    etag: Option<String>,
+}
+
+impl SyncedItem {
+    fn new(todo: &VTodo, resource: &CalDavResource) -> Self {
+        Self {
+            fingerprint: todo.fingerprint(),
+            etag: resource.etag.clone(),
+        }
+    }
+}
+
+// UID -> item after the last successful sync. Older versions stored only the fingerprint.
+fn load_sync_state() -> HashMap<String, SyncedItem> {
+    let Some(json) = get_local_storage()
+        .and_then(|storage| storage.get_item("pomodoro_caldav_sync_state").ok().flatten())
+    else {
+        return HashMap::new();
+    };
+    serde_json::from_str(&json)
+        .or_else(|_| {
+            serde_json::from_str::<HashMap<String, String>>(&json).map(|fingerprints| {
+                fingerprints
+                    .into_iter()
+                    .map(|(uid, fingerprint)| (uid, SyncedItem { fingerprint, etag: None }))
+                    .collect()
+            })
+        })
+        .unwrap_or_default()
+}
+
+fn save_sync_state(state: &HashMap<String, SyncedItem>) -> Result<(), String> {
+    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
+    let json = serde_json::to_string(state).map_err(|e| e.to_string())?;
+    storage
+        .set_item("pomodoro_caldav_sync_state", &json)
+        .map_err(|e| format!("{:?}", e))
+}
+
+// The fields that are synced in both directions
+#[derive(Debug, Clone, PartialEq)]
+pub struct VTodo {
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub due: Option<String>, // YYYY-MM-DD; only synced for tasks
+    pub completed: bool,
+    pub parent_uid: Option<String>,
+}
+
+impl VTodo {
+    fn from_task(task: &Task, uid: String) -> Self {
+        Self {
+            uid,
+            summary: task.name.clone(),
+            description: task.description.clone(),
+            due: task.due_date.clone(),
+            completed: task.completed,
+            parent_uid: None,
+        }
+    }
+
+    fn from_subtask(subtask: &SubTask, uid: String, parent_uid: String) -> Self {
+        Self {
+            uid,
+            summary: subtask.name.clone(),
+            description: subtask.description.clone(),
+            due: None,
+            completed: subtask.completed,
+            parent_uid: Some(parent_uid),
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        format!(
+            "{}\u{1f}{}\u{1f}{}\u{1f}{}\u{1f}{}",
+            self.summary,
+            self.description.as_deref().unwrap_or_default(),
+            self.due.as_deref().unwrap_or_default(),
+            self.completed,
+            self.parent_uid.as_deref().unwrap_or_default()
+        )
+    }
+
+    fn describe(&self) -> String {
+        let mut text = self.summary.clone();
+        if let Some(due) = &self.due {
+            text.push_str(&format!(" · due {}", due));
+        }
+        text.push_str(if self.completed { " · done" } else { " · open" });
+        text
+    }
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result
+}
+
+// Joins folded continuation lines
+fn unfold_lines(data: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in data.split('\n') {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')), lines.last_mut()) {
+            (Some(continuation), Some(previous)) => previous.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+// Folds a content line at 75 octets
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+// Splits "NAME;PARAM=x:value" into (NAME, params, value)
+fn split_property(line: &str) -> Option<(String, &str, &str)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find(|&(_, c)| {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        }
+        c == ':' && !in_quotes
+    })?;
+    let (head, value) = (&line[..colon.0], &line[colon.0 + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name.to_ascii_uppercase(), params, value))
+}
+
+fn param<'a>(params: &'a str, name: &str) -> Option<&'a str> {
+    params
+        .split(';')
+        .filter_map(|p| p.split_once('='))
+        .find_map(|(key, value)| key.eq_ignore_ascii_case(name).then_some(value.trim_matches('"')))
+}
+
+fn is_parent_relation(params: &str) -> bool {
+    param(params, "RELTYPE").is_none_or(|reltype| reltype.eq_ignore_ascii_case("PARENT"))
+}
+
+// Only VALUE=DATE is a bare date. A DATE-TIME is in UTC ("...Z"), in its TZID zone or floating
+// on the local clock, and falls on the local day of that moment.
+fn parse_ical_date(
+    value: &str,
+    params: &str,
+    local_start: &impl Fn(&str) -> Option<LocalStart>,
+) -> Option<String> {
+    if param(params, "VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE")) {
+        if value.len() != 8 || !value.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        return Some(format!("{}-{}-{}", &value[..4], &value[4..6], &value[6..8]));
+    }
+    let mut iso = iso_from_basic_date_time(value)?;
+    if let Some(time_zone) = param(params, "TZID").filter(|_| !iso.ends_with('Z')) {
+        iso = zoned_time_to_utc_iso(&iso, time_zone).unwrap_or(iso);
+    }
+    local_start(&iso).map(|start| start.day)
+}
+
+// Reads the first VTODO of a calendar object, skipping nested components such as VALARM.
+// `local_start` places date-times on the local clock.
+pub fn parse_vtodo(data: &str, local_start: impl Fn(&str) -> Option<LocalStart>) -> Option<VTodo> {
+    let mut todo: Option<VTodo> = None;
+    let mut nested_depth = 0;
+
+    for line in unfold_lines(data) {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+        match (name.as_str(), todo.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VTODO") => {
+                todo = Some(VTodo {
+                    uid: String::new(),
+                    summary: String::new(),
+                    description: None,
+                    due: None,
+                    completed: false,
+                    parent_uid: None,
+                });
+            }
+            ("BEGIN", Some(_)) => nested_depth += 1,
+            ("END", Some(_)) if nested_depth > 0 => nested_depth -= 1,
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VTODO") => break,
+            (_, Some(_)) if nested_depth > 0 => {}
+            ("UID", Some(todo)) => todo.uid = value.to_string(),
+            ("SUMMARY", Some(todo)) => todo.summary = unescape_text(value),
+            ("DESCRIPTION", Some(todo)) => {
+                todo.description = Some(unescape_text(value)).filter(|d| !d.trim().is_empty())
+            }
+            ("DUE", Some(todo)) => todo.due = parse_ical_date(value, params, &local_start),
+            ("STATUS", Some(todo)) => todo.completed = value.eq_ignore_ascii_case("COMPLETED"),
+            ("COMPLETED", Some(todo)) => todo.completed = true,
+            ("RELATED-TO", Some(todo)) if is_parent_relation(params) => {
+                todo.parent_uid = Some(value.to_string())
+            }
+            _ => {}
+        }
+    }
+
+    todo.filter(|t| !t.uid.is_empty())
+}
+
+fn ical_timestamp() -> String {
+    // 2024-01-15T10:20:30.123Z -> 20240115T102030Z
+    let iso: String = js_sys::Date::new_0().to_iso_string().into();
+    let compact: String = iso.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    format!("{}Z", &compact[..15])
+}
+
+// Properties this app owns; everything else on the server copy is left untouched
+fn managed_lines(todo: &VTodo, manage_due: bool) -> Vec<String> {
+    let now = ical_timestamp();
+    let mut lines = vec![
+        format!("DTSTAMP:{}", now),
+        format!("LAST-MODIFIED:{}", now),
+        format!("SUMMARY:{}", escape_text(&todo.summary)),
+    ];
+    if let Some(description) = &todo.description {
+        lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+    }
+    if manage_due {
+        if let Some(due) = &todo.due {
+            lines.push(format!("DUE;VALUE=DATE:{}", due.replace('-', "")));
+        }
+    }
+    if todo.completed {
+        lines.push("STATUS:COMPLETED".to_string());
+        lines.push("PERCENT-COMPLETE:100".to_string());
+        lines.push(format!("COMPLETED:{}", now));
+    } else {
+        lines.push("STATUS:NEEDS-ACTION".to_string());
+    }
+    if let Some(parent) = &todo.parent_uid {
+        lines.push(format!("RELATED-TO;RELTYPE=PARENT:{}", parent));
+    }
+    lines
+}
+
+fn is_managed_property(name: &str, params: &str, manage_due: bool) -> bool {
+    match name {
+        "DTSTAMP" | "LAST-MODIFIED" | "SUMMARY" | "DESCRIPTION" | "STATUS" | "PERCENT-COMPLETE"
+        | "COMPLETED" => true,
+        "DUE" => manage_due,
+        "RELATED-TO" => is_parent_relation(params),
+        _ => false,
+    }
+}
+
+pub fn build_vtodo(todo: &VTodo) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Pomodoro App//Tasks//EN".to_string(),
+        "BEGIN:VTODO".to_string(),
+        format!("UID:{}", todo.uid),
+    ];
+    lines.extend(managed_lines(todo, todo.parent_uid.is_none()));
+    lines.push("END:VTODO".to_string());
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut data: Vec<String> = lines.iter().map(|l| fold_line(l)).collect();
+    data.push(String::new());
+    data.join("\r\n")
+}
+
+// Rewrites the managed properties of an existing calendar object, keeping the rest
+pub fn update_vtodo(existing: &str, todo: &VTodo) -> String {
+    let manage_due = todo.parent_uid.is_none();
+    let mut output = Vec::new();
+    let mut in_todo = false;
+    let mut nested_depth = 0;
+
+    for line in unfold_lines(existing) {
+        if line.is_empty() {
+            continue;
+        }
+        let property = split_property(&line);
+        let (name, params, value) = property.unwrap_or((String::new(), "", ""));
+
+        if !in_todo {
+            in_todo = name == "BEGIN" && value.eq_ignore_ascii_case("VTODO");
+            output.push(line);
+            continue;
+        }
+
+        match name.as_str() {
+            "BEGIN" => nested_depth += 1,
+            "END" if nested_depth > 0 => nested_depth -= 1,
+            "END" if value.eq_ignore_ascii_case("VTODO") => {
+                output.extend(managed_lines(todo, manage_due));
+                in_todo = false;
+            }
+            _ if nested_depth == 0 && is_managed_property(&name, params, manage_due) => continue,
+            _ => {}
+        }
+        output.push(line);
+    }
+
+    let mut data: Vec<String> = output.iter().map(|l| fold_line(l)).collect();
+    data.push(String::new());
+    data.join("\r\n")
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CalDavResource {
+    url: String,
+    etag: Option<String>,
+    data: String,
+}
+
+// Hands the login to the backend, which keeps the password with the server it is for
+async fn save_caldav_credentials(
+    settings: &CalDavSettings,
+    password: &str,
+    replaces: Option<&str>,
+) -> Result<String, String> {
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({
+        "collectionUrl": settings.collection_url,
+        "username": settings.username,
+        "password": password,
+        "replaces": replaces,
+    }))
+    .map_err(|e| e.to_string())?;
+    let result = invoke("save_caldav_credentials", args).await;
+    serde_wasm_bindgen::from_value::<Result<String, String>>(result)
+        .map_err(|_| "Unexpected response from save_caldav_credentials".to_string())?
+}
+
+async fn list_todos(settings: &CalDavSettings) -> Result<Vec<CalDavResource>, String> {
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({ "credentialRef": settings.credential_ref()? }))
+        .map_err(|e| e.to_string())?;
+    let result = invoke("caldav_list_todos", args).await;
+    serde_wasm_bindgen::from_value::<Result<Vec<CalDavResource>, String>>(result)
+        .map_err(|_| "Unexpected response from caldav_list_todos".to_string())?
+}
+
+async fn put_todo(
+    settings: &CalDavSettings,
+    url: Option<String>,
+    todo: &VTodo,
+    data: String,
+    etag: Option<String>,
+) -> Result<CalDavResource, String> {
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({
+        "credentialRef": settings.credential_ref()?,
+        "url": url,
+        "uid": todo.uid,
+        "data": data,
+        "etag": etag,
+    }))
+    .map_err(|e| e.to_string())?;
+    let result = invoke("caldav_put_todo", args).await;
+    serde_wasm_bindgen::from_value::<Result<CalDavResource, String>>(result)
+        .map_err(|_| "Unexpected response from caldav_put_todo".to_string())?
+}
+
+async fn delete_todo(settings: &CalDavSettings, url: &str, etag: Option<String>) -> Result<(), String> {
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({
+        "credentialRef": settings.credential_ref()?,
+        "url": url,
+        "etag": etag,
+    }))
+    .map_err(|e| e.to_string())?;
+    let result = invoke("caldav_delete_todo", args).await;
+    serde_wasm_bindgen::from_value::<Result<(), String>>(result)
+        .map_err(|_| "Unexpected response from caldav_delete_todo".to_string())?
+}
+
+fn caldav_uid(external: &Option<ExternalRef>) -> Option<&str> {
+    external
+        .as_ref()
+        .filter(|e| e.source == CALDAV_SOURCE)
+        .map(|e| e.id.as_str())
+}
+
+fn link(uid: &str, resource: &CalDavResource) -> Option<ExternalRef> {
+    Some(ExternalRef {
+        source: CALDAV_SOURCE.to_string(),
+        id: uid.to_string(),
+        url: Some(resource.url.clone()),
+        etag: resource.etag.clone(),
+    })
+}
+
+fn apply_to_task(task: &mut Task, todo: &VTodo) {
+    task.name = todo.summary.clone();
+    task.description = todo.description.clone();
+    task.due_date = todo.due.clone();
+    task.completed = todo.completed;
+}
+
+fn apply_to_subtask(subtask: &mut SubTask, todo: &VTodo) {
+    subtask.name = todo.summary.clone();
+    subtask.description = todo.description.clone();
+    subtask.completed = todo.completed;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CalDavSyncSummary {
+    pub pulled: u32,
+    pub pushed: u32,
+    pub removed: u32,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+// What to do with a conflicting item on this run, keyed by local task/subtask ID, or by UID
+// for items deleted locally
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictResolution {
+    KeepLocal,
+    UseRemote,
+}
+
+enum LocalRecord {
+    Task(usize),
+    Subtask(usize),
+}
+
+// Saves always start from the stored records rather than the copy read at the start of the
+// sync, so edits made while a request was in flight are kept
+async fn patch_task(task_id: &str, patch: impl FnOnce(&mut Task)) -> Result<(), String> {
+    let mut tasks = get_all_tasks().await?;
+    if let Some(task) = tasks.iter_mut().find(|t| t.id == task_id) {
+        patch(task);
+        save_all_tasks(&tasks)?;
+    }
+    Ok(())
+}
+
+async fn patch_subtask(subtask_id: &str, patch: impl FnOnce(&mut SubTask)) -> Result<(), String> {
+    let mut subtasks = get_all_subtasks().await?;
+    if let Some(subtask) = subtasks.iter_mut().find(|st| st.id == subtask_id) {
+        patch(subtask);
+        save_all_subtasks(&subtasks)?;
+    }
+    Ok(())
+}
+
+pub async fn sync_caldav(
+    settings: &CalDavSettings,
+    resolutions: &HashMap<String, ConflictResolution>,
+) -> Result<CalDavSyncSummary, String> {
+    let remote: Vec<(CalDavResource, VTodo)> = list_todos(settings)
+        .await?
+        .into_iter()
+        .filter_map(|resource| {
+            let todo = parse_vtodo(&resource.data, local_start)?;
+            Some((resource, todo))
+        })
+        .collect();
+    let remote_by_uid: HashMap<&str, &(CalDavResource, VTodo)> =
+        remote.iter().map(|item| (item.1.uid.as_str(), item)).collect();
+
+    // Nested VTODOs are flattened onto the top-level ancestor, which becomes the task
+    let top_level_uid = |uid: &str| -> String {
+        let mut current = uid;
+        for _ in 0..remote.len() {
+            match remote_by_uid.get(current).and_then(|(_, t)| t.parent_uid.as_deref()) {
+                Some(parent) if remote_by_uid.contains_key(parent) => current = parent,
+                _ => break,
+            }
+        }
+        current.to_string()
+    };
+
+    let mut state = load_sync_state();
+    let mut summary = CalDavSyncSummary::default();
+    // Working copies used to decide what to do; they are never saved back as a whole
+    let mut tasks = get_all_tasks().await?;
+    let mut subtasks = get_all_subtasks().await?;
+
+    let local_todo = |record: &LocalRecord, tasks: &[Task], subtasks: &[SubTask], uid: &str| match record {
+        LocalRecord::Task(i) => VTodo::from_task(&tasks[*i], uid.to_string()),
+        LocalRecord::Subtask(i) => {
+            let parent_uid = tasks
+                .iter()
+                .find(|t| t.id == subtasks[*i].task_id)
+                .and_then(|t| caldav_uid(&t.external))
+                .unwrap_or_default()
+                .to_string();
+            VTodo::from_subtask(&subtasks[*i], uid.to_string(), parent_uid)
+        }
+    };
+
+    // 1. Items linked on both sides
+    let mut linked_uids: Vec<String> = Vec::new();
+    let records: Vec<(String, LocalRecord)> = tasks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, t)| caldav_uid(&t.external).map(|uid| (uid.to_string(), LocalRecord::Task(i))))
+        .chain(subtasks.iter().enumerate().filter_map(|(i, st)| {
+            caldav_uid(&st.external).map(|uid| (uid.to_string(), LocalRecord::Subtask(i)))
+        }))
+        .collect();
+    let mut removed_tasks = Vec::new();
+    let mut removed_subtasks = Vec::new();
+
+    for (uid, record) in &records {
+        linked_uids.push(uid.clone());
+        let (local_id, local_etag, local_name) = match record {
+            LocalRecord::Task(i) => (tasks[*i].id.clone(), tasks[*i].external.as_ref().and_then(|e| e.etag.clone()), tasks[*i].name.clone()),
+            LocalRecord::Subtask(i) => (subtasks[*i].id.clone(), subtasks[*i].external.as_ref().and_then(|e| e.etag.clone()), subtasks[*i].name.clone()),
+        };
+
+        let Some((resource, remote_todo)) = remote_by_uid.get(uid.as_str()).copied() else {
+            // Deleted on the server
+            match record {
+                LocalRecord::Task(_) => removed_tasks.push(local_id),
+                LocalRecord::Subtask(_) => removed_subtasks.push(local_id),
+            }
+            state.remove(uid);
+            continue;
+        };
+
+        let local = local_todo(record, &tasks, &subtasks, uid);
+        let remote_changed = local_etag != resource.etag;
+        let local_changed = state.get(uid).is_some_and(|item| item.fingerprint != local.fingerprint());
+        let resolution = resolutions.get(&local_id).copied();
+
+        let pull = match (remote_changed, local_changed, resolution) {
+            (_, _, Some(ConflictResolution::UseRemote)) => true,
+            (_, _, Some(ConflictResolution::KeepLocal)) => false,
+            (false, false, None) => continue,
+            (true, false, None) => true,
+            (false, true, None) => false,
+            (true, true, None) => {
+                // Both sides made the same edit
+                let mut remote_as_local = remote_todo.clone();
+                if matches!(record, LocalRecord::Subtask(_)) {
+                    remote_as_local.due = None;
+                }
+                if remote_as_local.fingerprint() != local.fingerprint() {
+                    summary.conflicts.push(SyncConflict {
+                        source: CALDAV_SOURCE.to_string(),
+                        task_id: local_id,
+                        task_name: local_name,
+                        local_version: local.describe(),
+                        remote_version: remote_todo.describe(),
+                        message: "Changed both here and on the CalDAV server".to_string(),
+                    });
+                    continue;
+                }
+                true
+            }
+        };
+
+        if pull {
+            match record {
+                LocalRecord::Task(i) => {
+                    let pull_into = |task: &mut Task| {
+                        apply_to_task(task, remote_todo);
+                        task.external = link(uid, resource);
+                    };
+                    pull_into(&mut tasks[*i]);
+                    patch_task(&local_id, pull_into).await?;
+                }
+                LocalRecord::Subtask(i) => {
+                    // Follow a move to another parent if that parent is synced too
+                    let new_parent = remote_todo
+                        .parent_uid
+                        .as_ref()
+                        .map(|_| top_level_uid(uid))
+                        .and_then(|parent_uid| tasks.iter().find(|t| caldav_uid(&t.external) == Some(parent_uid.as_str())))
+                        .map(|t| t.id.clone());
+                    let pull_into = |subtask: &mut SubTask| {
+                        apply_to_subtask(subtask, remote_todo);
+                        subtask.external = link(uid, resource);
+                        if let Some(parent_id) = &new_parent {
+                            subtask.task_id = parent_id.clone();
+                        }
+                    };
+                    pull_into(&mut subtasks[*i]);
+                    patch_subtask(&local_id, pull_into).await?;
+                }
+            }
+            let updated = local_todo(record, &tasks, &subtasks, uid);
+            state.insert(uid.clone(), SyncedItem::new(&updated, resource));
+            summary.pulled += 1;
+            continue;
+        }
+
+        let data = update_vtodo(&resource.data, &local);
+        match put_todo(settings, Some(resource.url.clone()), &local, data, resource.etag.clone()).await {
+            Ok(written) => {
+                let external = link(uid, &written);
+                match record {
+                    LocalRecord::Task(_) => patch_task(&local_id, |t| t.external = external).await?,
+                    LocalRecord::Subtask(_) => patch_subtask(&local_id, |st| st.external = external).await?,
+                }
+                state.insert(uid.clone(), SyncedItem::new(&local, &written));
+                summary.pushed += 1;
+            }
+            Err(e) if e.starts_with(CONFLICT_ERROR_PREFIX) => summary.conflicts.push(SyncConflict {
+                source: CALDAV_SOURCE.to_string(),
+                task_id: local_id,
+                task_name: local_name,
+                local_version: local.describe(),
+                remote_version: remote_todo.describe(),
+                message: e.trim_start_matches(CONFLICT_ERROR_PREFIX).trim().to_string(),
+            }),
+            Err(e) => return Err(e),
+        }
+    }
+
+    // 2. Items deleted on the server: tasks are archived (keeping their history), subtasks removed
+    for task_id in &removed_tasks {
+        patch_task(task_id, |task| {
+            task.archived = true;
+            task.external = None;
+        })
+        .await?;
+    }
+    summary.removed = (removed_tasks.len() + removed_subtasks.len()) as u32;
+    for subtask_id in removed_subtasks {
+        delete_subtask_from_db(subtask_id).await?;
+    }
+
+    // 3. Items deleted locally: synced before but no longer linked to a task or subtask. They are
+    // deleted on the server too, unless it changed them since the last sync.
+    let mut deleted_uids: Vec<&str> = Vec::new();
+    for (resource, todo) in remote.iter().filter(|(_, todo)| !linked_uids.contains(&todo.uid)) {
+        let Some(synced) = state.get(&todo.uid) else {
+            continue;
+        };
+        let keep_local = match resolutions.get(&todo.uid).copied() {
+            // Imported again below
+            Some(ConflictResolution::UseRemote) => {
+                state.remove(&todo.uid);
+                continue;
+            }
+            Some(ConflictResolution::KeepLocal) => true,
+            None => false,
+        };
+        // State from before ETags were kept can only compare the synced fields
+        let unchanged = match &synced.etag {
+            Some(etag) => resource.etag.as_ref() == Some(etag),
+            None => synced.fingerprint == todo.fingerprint(),
+        };
+
+        let result = if keep_local {
+            delete_todo(settings, &resource.url, None).await
+        } else if unchanged {
+            // If-Match still catches changes made after the listing
+            delete_todo(settings, &resource.url, resource.etag.clone()).await
+        } else {
+            Err(format!(
+                "{} the item was changed on the CalDAV server since the last sync",
+                CONFLICT_ERROR_PREFIX
+            ))
+        };
+        match result {
+            Ok(()) => {
+                state.remove(&todo.uid);
+                summary.removed += 1;
+            }
+            Err(e) if e.starts_with(CONFLICT_ERROR_PREFIX) => summary.conflicts.push(SyncConflict {
+                source: CALDAV_SOURCE.to_string(),
+                task_id: todo.uid.clone(),
+                task_name: todo.summary.clone(),
+                local_version: "Deleted".to_string(),
+                remote_version: todo.describe(),
+                message: e.trim_start_matches(CONFLICT_ERROR_PREFIX).trim().to_string(),
+            }),
+            Err(e) => return Err(e),
+        }
+        deleted_uids.push(&todo.uid);
+    }
+
+    // 4. New server items: top-level ones become tasks, the rest subtasks of their top-level task
+    let new_items: Vec<&(CalDavResource, VTodo)> = remote
+        .iter()
+        .filter(|(_, todo)| !linked_uids.contains(&todo.uid) && !deleted_uids.contains(&todo.uid.as_str()))
+        .collect();
+    let (new_tasks, new_subtasks): (Vec<_>, Vec<_>) = new_items
+        .into_iter()
+        .partition(|(_, todo)| top_level_uid(&todo.uid) == todo.uid);
+
+    let mut created_task_ids = Vec::new();
+    for (_, todo) in &new_tasks {
+        let task_id = save_task_to_db(NewTask {
+            name: todo.summary.clone(),
+            description: todo.description.clone(),
+            color: Task::get_random_color(),
+            estimated_pomodoros: None,
+            due_date: todo.due.clone(),
+            priority: TaskPriority::default(),
+            recurrence: None,
+            project_id: settings.project_id.clone(),
+        })
+        .await?;
+        created_task_ids.push(task_id);
+    }
+
+    let mut tasks = get_all_tasks().await?;
+    for (task_id, (resource, todo)) in created_task_ids.iter().zip(&new_tasks) {
+        if let Some(task) = tasks.iter_mut().find(|t| &t.id == task_id) {
+            task.completed = todo.completed;
+            task.external = link(&todo.uid, resource);
+            state.insert(todo.uid.clone(), SyncedItem::new(&VTodo::from_task(task, todo.uid.clone()), resource));
+            summary.pulled += 1;
+        }
+    }
+    save_all_tasks(&tasks)?;
+
+    let mut created_subtask_ids = Vec::new();
+    for (resource, todo) in &new_subtasks {
+        let parent_uid = top_level_uid(&todo.uid);
+        let Some(parent) = tasks.iter().find(|t| caldav_uid(&t.external) == Some(parent_uid.as_str())) else {
+            continue;
+        };
+        let subtask_id = save_subtask_to_db(NewSubTask {
+            task_id: parent.id.clone(),
+            name: todo.summary.clone(),
+            description: todo.description.clone(),
+            estimated_pomodoros: None,
+        })
+        .await?;
+        created_subtask_ids.push((subtask_id, resource, todo, parent_uid));
+    }
+
+    let mut subtasks = get_all_subtasks().await?;
+    for (subtask_id, resource, todo, parent_uid) in &created_subtask_ids {
+        if let Some(subtask) = subtasks.iter_mut().find(|st| &st.id == subtask_id) {
+            subtask.completed = todo.completed;
+            subtask.external = link(&todo.uid, resource);
+            let synced = VTodo::from_subtask(subtask, todo.uid.clone(), parent_uid.clone());
+            state.insert(todo.uid.clone(), SyncedItem::new(&synced, resource));
+            summary.pulled += 1;
+        }
+    }
+    save_all_subtasks(&subtasks)?;
+
+    // 5. Local items not on the server yet: tasks in the synced project and subtasks of synced tasks
+    for task in tasks.iter().filter(|t| {
+        t.external.is_none()
+            && !t.archived
+            && (settings.project_id.is_none() || t.project_id == settings.project_id)
+    }) {
+        let uid = format!("{}{}", task.id, UID_SUFFIX);
+        let todo = VTodo::from_task(task, uid.clone());
+        let written = put_todo(settings, None, &todo, build_vtodo(&todo), None).await?;
+        patch_task(&task.id, |t| t.external = link(&uid, &written)).await?;
+        state.insert(uid, SyncedItem::new(&todo, &written));
+        summary.pushed += 1;
+    }
+
+    let tasks = get_all_tasks().await?;
+    for subtask in subtasks.iter().filter(|st| st.external.is_none()) {
+        let Some(parent_uid) = tasks
+            .iter()
+            .find(|t| t.id == subtask.task_id)
+            .and_then(|t| caldav_uid(&t.external))
+        else {
+            continue;
+        };
+        let uid = format!("{}{}", subtask.id, UID_SUFFIX);
+        let todo = VTodo::from_subtask(subtask, uid.clone(), parent_uid.to_string());
+        let written = put_todo(settings, None, &todo, build_vtodo(&todo), None).await?;
+        patch_subtask(&subtask.id, |st| st.external = link(&uid, &written)).await?;
+        state.insert(uid, SyncedItem::new(&todo, &written));
+        summary.pushed += 1;
+    }
+
+    save_sync_state(&state)?;
+    Ok(summary)
+}
+
+#[derive(Clone)]
+pub struct CalDavSync {
+    pub settings: RwSignal<CalDavSettings>,
+    pub status: RwSignal<Option<String>>,
+    pub syncing: RwSignal<bool>,
+    task_controller: TaskController,
+}
+
+impl CalDavSync {
+    pub fn new(task_controller: TaskController) -> Self {
+        let sync = Self {
+            settings: RwSignal::new(CalDavSettings::load_from_storage()),
+            status: RwSignal::new(None),
+            syncing: RwSignal::new(false),
+            task_controller,
+        };
+
+        let scheduler = sync.clone();
+        spawn_local(async move {
+            let settings = scheduler.settings.get_untracked();
+            if let Some(password) = settings.legacy_password.clone() {
+                match scheduler.store_settings(settings, &password).await {
+                    Ok(()) => console_log!("Moved the CalDAV password to the backend"),
+                    Err(e) => console_log!("Error moving the CalDAV password: {}", e),
+                }
+            }
+            scheduler.run_sync_loop().await;
+        });
+
+        sync
+    }
+
+    async fn run_sync_loop(&self) {
+        loop {
+            if self.settings.get_untracked().is_active() {
+                self.run_sync(HashMap::new()).await;
+            }
+
+            let minutes = self.settings.get_untracked().interval_minutes.max(1);
+            gloo_timers::future::sleep(std::time::Duration::from_secs(minutes as u64 * 60)).await;
+        }
+    }
+
+    // Saves the settings; a non-empty `password` replaces the stored one
+    pub fn update_settings(&self, settings: CalDavSettings, password: String) {
+        let sync = self.clone();
+        spawn_local(async move {
+            if let Err(e) = sync.store_settings(settings, &password).await {
+                sync.status.set(Some(format!("Error: {}", e)));
+            }
+        });
+    }
+
+    async fn store_settings(&self, mut settings: CalDavSettings, password: &str) -> Result<(), String> {
+        let current = self.settings.get_untracked();
+        let same_login = settings.collection_url == current.collection_url && settings.username == current.username;
+        if !password.is_empty() || (settings.username.is_empty() && !settings.collection_url.is_empty()) {
+            settings.credential_ref =
+                Some(save_caldav_credentials(&settings, password, current.credential_ref.as_deref()).await?);
+        } else if !same_login {
+            // A saved password is only ever sent to the server and user it was entered for
+            if current.credential_ref.is_some() {
+                return Err("Enter the password again to use it with a different server or user".to_string());
+            }
+        } else {
+            settings.credential_ref = current.credential_ref;
+        }
+        settings.legacy_password = None;
+        settings.save_to_storage();
+        self.settings.set(settings);
+        Ok(())
+    }
+
+    pub fn sync_now(&self) {
+        let sync = self.clone();
+        spawn_local(async move {
+            sync.run_sync(HashMap::new()).await;
+        });
+    }
+
+    pub fn resolve_conflict(&self, conflict: SyncConflict, keep_local: bool) {
+        let resolution = if keep_local {
+            ConflictResolution::KeepLocal
+        } else {
+            ConflictResolution::UseRemote
+        };
+        let sync = self.clone();
+        spawn_local(async move {
+            sync.run_sync(HashMap::from([(conflict.task_id, resolution)])).await;
+        });
+    }
+
+    async fn run_sync(&self, resolutions: HashMap<String, ConflictResolution>) {
+        if self.syncing.get_untracked() {
+            return;
+        }
+        self.syncing.set(true);
+
+        match sync_caldav(&self.settings.get_untracked(), &resolutions).await {
+            Ok(summary) => {
+                self.status.set(Some(format!(
+                    "Synced at {}: {} pulled, {} pushed, {} removed, {} conflict(s)",
+                    js_sys::Date::new_0().to_locale_time_string("en-US"),
+                    summary.pulled,
+                    summary.pushed,
+                    summary.removed,
+                    summary.conflicts.len()
+                )));
+                // Conflicts are recomputed on every run
+                self.task_controller.sync_conflicts.update(|list| {
+                    list.retain(|c| c.source != CALDAV_SOURCE);
+                    list.extend(summary.conflicts);
+                });
+                self.task_controller.load_tasks();
+                self.task_controller.load_task_stats();
+            }
+            Err(e) => {
+                console_log!("Error syncing CalDAV: {}", e);
+                self.status.set(Some(format!("Error: {}", e)));
+            }
+        }
+
+        self.syncing.set(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{offset_start, utc_start};
+
+    #[test]
+    fn parses_folded_escaped_vtodo_and_skips_alarms() {
+        let data = "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:a@pomodoro-app\r\nSUMMARY:Write the quarterly\r\n  report\\, part 1\r\nDESCRIPTION:First line\\nsecond\r\nDUE;VALUE=DATE:20240115\r\nBEGIN:VALARM\r\nSUMMARY:Reminder\r\nEND:VALARM\r\nSTATUS:COMPLETED\r\nRELATED-TO;RELTYPE=PARENT:p@example\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+
+        let todo = parse_vtodo(data, utc_start).unwrap();
+
+        assert_eq!(todo.uid, "a@pomodoro-app");
+        assert_eq!(todo.summary, "Write the quarterly report, part 1");
+        assert_eq!(todo.description.as_deref(), Some("First line\nsecond"));
+        assert_eq!(todo.due.as_deref(), Some("2024-01-15"));
+        assert!(todo.completed);
+        assert_eq!(todo.parent_uid.as_deref(), Some("p@example"));
+    }
+
+    #[test]
+    fn ignores_sibling_relations_and_objects_without_uid() {
+        let sibling = "BEGIN:VTODO\r\nUID:b\r\nRELATED-TO;RELTYPE=SIBLING:a\r\nEND:VTODO\r\n";
+        assert_eq!(parse_vtodo(sibling, utc_start).unwrap().parent_uid, None);
+
+        assert!(parse_vtodo("BEGIN:VTODO\r\nSUMMARY:No uid\r\nEND:VTODO\r\n", utc_start).is_none());
+        assert!(parse_vtodo("BEGIN:VEVENT\r\nUID:e\r\nEND:VEVENT\r\n", utc_start).is_none());
+    }
+
+    fn due_with(property: &str, local_start: impl Fn(&str) -> Option<LocalStart>) -> Option<String> {
+        let data = format!("BEGIN:VTODO\r\nUID:d\r\n{}\r\nEND:VTODO\r\n", property);
+        parse_vtodo(&data, local_start).unwrap().due
+    }
+
+    #[test]
+    fn due_date_times_fall_on_the_local_day() {
+        // 23:00 UTC on the 15th is already the 16th an hour ahead of UTC
+        assert_eq!(due_with("DUE:20240115T230000Z", offset_start(1, 0)).as_deref(), Some("2024-01-16"));
+        assert_eq!(due_with("DUE:20240116T030000Z", offset_start(-5, 0)).as_deref(), Some("2024-01-15"));
+        assert_eq!(due_with("DUE:20240116T020000Z", offset_start(0, 4)).as_deref(), Some("2024-01-15"));
+        // Floating times are already on the local clock
+        assert_eq!(due_with("DUE:20240115T230000", offset_start(1, 0)).as_deref(), Some("2024-01-15"));
+    }
+
+    #[test]
+    fn only_value_date_is_a_bare_date() {
+        assert_eq!(due_with("DUE;VALUE=DATE:20240115", offset_start(-5, 0)).as_deref(), Some("2024-01-15"));
+        assert_eq!(due_with("DUE:20240115", offset_start(0, 0)), None);
+        assert_eq!(due_with("DUE;VALUE=DATE:20240115T230000Z", offset_start(0, 0)), None);
+    }
+}
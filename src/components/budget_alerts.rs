@@ -0,0 +1,127 @@
+use crate::timer::TimerController;
+use crate::types::BudgetAlertSettings;
+use leptos::prelude::*;
+
+fn update_budget(settings: RwSignal<BudgetAlertSettings>, change: impl FnOnce(&mut BudgetAlertSettings)) {
+    let mut updated = settings.get_untracked();
+    change(&mut updated);
+    updated.save_to_storage();
+    settings.set(updated);
+}
+
+// "80, 100, 150" -> [80, 100, 150]; None when nothing valid was entered
+fn parse_thresholds(text: &str) -> Option<Vec<u32>> {
+    let mut thresholds: Vec<u32> = text
+        .split(',')
+        .filter_map(|part| part.trim().trim_end_matches('%').parse().ok())
+        .filter(|t| *t > 0)
+        .collect();
+    thresholds.sort();
+    thresholds.dedup();
+    (!thresholds.is_empty()).then_some(thresholds)
+}
+
+// Thresholds crossed by recent sessions, shown on the Timer tab until dismissed
+#[component]
+pub fn BudgetAlertBanner(timer_controller: TimerController) -> impl IntoView {
+    let alerts = timer_controller.budget_alerts;
+
+    view! {
+        {move || {
+            let events = alerts.get();
+            if events.is_empty() {
+                return view! { <div></div> }.into_any();
+            }
+            view! {
+                <div class="mb-4 space-y-2">
+                    {events.into_iter().map(|event| {
+                        let id = event.id.clone();
+                        let class = if event.threshold >= 100 {
+                            "flex justify-between items-center p-3 rounded-lg text-sm bg-red-50 dark:bg-red-900/30 text-red-700 dark:text-red-300"
+                        } else {
+                            "flex justify-between items-center p-3 rounded-lg text-sm bg-amber-50 dark:bg-amber-900/30 text-amber-700 dark:text-amber-300"
+                        };
+                        let icon = if event.threshold >= 100 { "⚠️" } else { "⏳" };
+                        view! {
+                            <div class=class>
+                                <span>{format!("{} {}", icon, event.message())}</span>
+                                <button
+                                    class="ml-3 opacity-70 hover:opacity-100"
+                                    title="Dismiss"
+                                    on:click=move |_| alerts.update(|list| list.retain(|e| e.id != id))
+                                >
+                                    "✕"
+                                </button>
+                            </div>
+                        }
+                    }).collect_view()}
+                </div>
+            }.into_any()
+        }}
+    }
+}
+
+// Which percentages of a task's estimate trigger a banner and system notification
+#[component]
+pub fn BudgetAlertSettingsPanel(timer_controller: TimerController) -> impl IntoView {
+    let settings = timer_controller.budget_settings;
+    let thresholds_text = RwSignal::new(String::new());
+    let error = RwSignal::new(None::<String>);
+
+    Effect::new(move |_| {
+        let text = settings
+            .get()
+            .thresholds
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        thresholds_text.set(text);
+    });
+
+    view! {
+        <div class="mt-6 p-4 bg-gray-50 dark:bg-gray-700 rounded-lg space-y-3">
+            <div class="flex justify-between items-center">
+                <h4 class="text-md font-semibold text-gray-800 dark:text-white">"Budget Alerts"</h4>
+                <label class="flex items-center space-x-2 text-sm text-gray-700 dark:text-gray-300">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || settings.get().enabled
+                        on:change=move |ev| {
+                            let enabled = event_target_checked(&ev);
+                            update_budget(settings, |s| s.enabled = enabled);
+                        }
+                    />
+                    <span>"Enabled"</span>
+                </label>
+            </div>
+            <div>
+                <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">
+                    "Alert when a task reaches these percentages of its estimated pomodoros"
+                </label>
+                <input
+                    type="text"
+                    placeholder="80, 100, 150"
+                    class="w-full px-2 py-1 border rounded text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                    prop:value=move || thresholds_text.get()
+                    on:change=move |ev| {
+                        match parse_thresholds(&event_target_value(&ev)) {
+                            Some(thresholds) => {
+                                error.set(None);
+                                update_budget(settings, |s| s.thresholds = thresholds);
+                            }
+                            None => error.set(Some("Enter comma-separated percentages, e.g. 80, 100, 150".to_string())),
+                        }
+                    }
+                />
+                {move || {
+                    if let Some(err) = error.get() {
+                        view! { <p class="text-red-500 text-xs mt-1">{err}</p> }.into_any()
+                    } else {
+                        view! { <div></div> }.into_any()
+                    }
+                }}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,389 @@
+use crate::app::AppTab;
+use crate::console_log;
+use crate::task::TaskController;
+use crate::theme::{ThemeController, ThemeType};
+use crate::timer::TimerController;
+use crate::types::{SessionType, SubTask, Task, TimerState, RECENT_SELECTION_LIMIT};
+use crate::utils::fuzzy_score;
+use leptos::prelude::*;
+use web_sys::{window, KeyboardEvent, MouseEvent};
+
+const MAX_RESULTS: usize = 30;
+const RECENT_BONUS: u32 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PaletteAction {
+    StartTimer,
+    PauseTimer,
+    StopTimer,
+    SwitchSession(SessionType),
+    OpenTab(AppTab),
+    SetTheme(ThemeType),
+}
+
+#[derive(Debug, Clone)]
+enum PaletteItem {
+    Task(Box<Task>),
+    Subtask { subtask: Box<SubTask>, parent: Box<Task> },
+    Action { action: PaletteAction, label: String },
+}
+
+impl PaletteItem {
+    fn search_text(&self) -> String {
+        match self {
+            PaletteItem::Task(task) => format!("{} {}", task.name, task.tags.join(" ")),
+            PaletteItem::Subtask { subtask, parent } => format!("{} {}", subtask.name, parent.name),
+            PaletteItem::Action { label, .. } => label.clone(),
+        }
+    }
+
+    fn selection_id(&self) -> Option<&str> {
+        match self {
+            PaletteItem::Task(task) => Some(&task.id),
+            PaletteItem::Subtask { subtask, .. } => Some(&subtask.id),
+            PaletteItem::Action { .. } => None,
+        }
+    }
+}
+
+fn available_actions(
+    timer_controller: &TimerController,
+    theme_controller: &ThemeController,
+) -> Vec<PaletteItem> {
+    let timer_state = timer_controller.timer_state.get();
+    let mut actions = Vec::new();
+
+    match timer_state {
+        TimerState::Stopped => actions.push((PaletteAction::StartTimer, "Start timer".to_string())),
+        TimerState::Paused => actions.push((PaletteAction::StartTimer, "Resume timer".to_string())),
+        TimerState::Running => actions.push((PaletteAction::PauseTimer, "Pause timer".to_string())),
+    }
+    if timer_state != TimerState::Stopped {
+        actions.push((PaletteAction::StopTimer, "Stop timer".to_string()));
+    }
+
+    // Session type can only change while the timer is stopped
+    if timer_state == TimerState::Stopped {
+        let current = timer_controller.session_type.get();
+        for session_type in [SessionType::Work, SessionType::ShortBreak, SessionType::LongBreak] {
+            if session_type != current {
+                actions.push((
+                    PaletteAction::SwitchSession(session_type),
+                    format!("Switch to {} session", session_type.name()),
+                ));
+            }
+        }
+    }
+
+    for tab in AppTab::all() {
+        actions.push((PaletteAction::OpenTab(tab), format!("Open {} tab", tab.label())));
+    }
+
+    let themes = ThemeType::all_themes();
+    let current_theme = theme_controller.get_current_theme();
+    let position = themes.iter().position(|t| *t == current_theme).unwrap_or(0);
+    let next_theme = themes[(position + 1) % themes.len()];
+    actions.push((
+        PaletteAction::SetTheme(next_theme),
+        format!("Toggle theme ({})", next_theme.display_name()),
+    ));
+
+    actions
+        .into_iter()
+        .map(|(action, label)| PaletteItem::Action { action, label })
+        .collect()
+}
+
+// Matching items, best first. Recently selected tasks and subtasks get a bonus that puts them
+// ahead of other matches; with an empty query they are followed by the actions, then the rest.
+fn ranked_items(
+    task_controller: &TaskController,
+    timer_controller: &TimerController,
+    theme_controller: &ThemeController,
+    query: &str,
+) -> Vec<(PaletteItem, bool)> {
+    let recent = task_controller.recent_selections.get();
+    let tasks = task_controller.tasks.get();
+    let open_tasks: Vec<&Task> = tasks.iter().filter(|t| !t.archived && !t.completed).collect();
+
+    let mut candidates: Vec<PaletteItem> = open_tasks
+        .iter()
+        .map(|task| PaletteItem::Task(Box::new((*task).clone())))
+        .collect();
+    for subtask in task_controller.subtasks.get().into_iter().filter(|st| !st.completed) {
+        if let Some(parent) = open_tasks.iter().find(|t| t.id == subtask.task_id) {
+            candidates.push(PaletteItem::Subtask {
+                subtask: Box::new(subtask),
+                parent: Box::new((*parent).clone()),
+            });
+        }
+    }
+    candidates.extend(available_actions(timer_controller, theme_controller));
+
+    let empty_query = query.trim().is_empty();
+    let mut scored: Vec<(u32, PaletteItem, bool)> = candidates
+        .into_iter()
+        .filter_map(|item| {
+            let score = fuzzy_score(query, &item.search_text())?;
+            let recent_position = item
+                .selection_id()
+                .and_then(|id| recent.iter().position(|r| r == id));
+            let bonus = match recent_position {
+                Some(position) => RECENT_BONUS + (RECENT_SELECTION_LIMIT.saturating_sub(position) as u32),
+                None if empty_query && matches!(item, PaletteItem::Action { .. }) => 1,
+                None => 0,
+            };
+            Some((score + bonus, item, recent_position.is_some()))
+        })
+        .collect();
+
+    scored.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+    scored.truncate(MAX_RESULTS);
+    scored.into_iter().map(|(_, item, recent)| (item, recent)).collect()
+}
+
+fn scroll_to_result(index: usize) {
+    let selector = format!("[data-palette-index=\"{}\"]", index);
+    if let Some(element) = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.query_selector(&selector).ok().flatten())
+    {
+        element.scroll_into_view_with_bool(false);
+    }
+}
+
+#[component]
+pub fn CommandPalette(
+    task_controller: TaskController,
+    timer_controller: TimerController,
+    theme_controller: ThemeController,
+    active_tab: RwSignal<AppTab>,
+) -> impl IntoView {
+    let open = RwSignal::new(false);
+    let query = RwSignal::new(String::new());
+    let highlighted = RwSignal::new(0usize);
+    let input_ref = NodeRef::<leptos::tachys::html::element::Input>::new();
+
+    // Ctrl+K (Cmd+K on macOS) toggles the palette from anywhere, including text fields
+    let _ = window_event_listener(leptos::ev::keydown, move |event: KeyboardEvent| {
+        if (event.ctrl_key() || event.meta_key()) && event.key().eq_ignore_ascii_case("k") {
+            event.prevent_default();
+            if !open.get_untracked() {
+                query.set(String::new());
+                highlighted.set(0);
+            }
+            open.set(!open.get_untracked());
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(input) = input_ref.get() {
+            if open.get() {
+                let _ = input.focus();
+            }
+        }
+    });
+
+    let results = {
+        let task_controller = task_controller.clone();
+        let timer_controller = timer_controller.clone();
+        let theme_controller = theme_controller.clone();
+        move || ranked_items(&task_controller, &timer_controller, &theme_controller, &query.get())
+    };
+
+    // Shift+Enter or Shift+click on a task also starts a work session for it
+    let run_item = {
+        let task_controller = task_controller.clone();
+        let timer_controller = timer_controller.clone();
+        let theme_controller = theme_controller.clone();
+        move |item: PaletteItem, start_session: bool| {
+            open.set(false);
+
+            let is_task = match item {
+                PaletteItem::Task(task) => {
+                    console_log!("🔍 Selected task '{}' from the command palette", task.name);
+                    task_controller.select_task(Some(*task));
+                    true
+                }
+                PaletteItem::Subtask { subtask, parent } => {
+                    console_log!("🔍 Selected subtask '{}' from the command palette", subtask.name);
+                    // Selecting the task clears the subtask, so the parent goes first
+                    task_controller.select_task(Some(*parent));
+                    task_controller.select_subtask(Some(*subtask));
+                    true
+                }
+                PaletteItem::Action { action, .. } => {
+                    match action {
+                        PaletteAction::StartTimer => timer_controller.start_timer(),
+                        PaletteAction::PauseTimer => timer_controller.pause_timer(),
+                        PaletteAction::StopTimer => timer_controller.stop_timer(),
+                        PaletteAction::SwitchSession(session_type) => {
+                            timer_controller.set_session_type(session_type)
+                        }
+                        PaletteAction::OpenTab(tab) => active_tab.set(tab),
+                        PaletteAction::SetTheme(theme) => theme_controller.set_theme(theme),
+                    }
+                    false
+                }
+            };
+
+            if is_task && start_session {
+                match timer_controller.timer_state.get_untracked() {
+                    TimerState::Stopped => {
+                        timer_controller.set_session_type(SessionType::Work);
+                        timer_controller.start_timer();
+                    }
+                    TimerState::Paused => timer_controller.start_timer(),
+                    TimerState::Running => {}
+                }
+                active_tab.set(AppTab::Timer);
+            }
+        }
+    };
+
+    let on_keydown = {
+        let results = results.clone();
+        let run_item = run_item.clone();
+        move |event: KeyboardEvent| match event.key().as_str() {
+            "ArrowDown" => {
+                event.prevent_default();
+                let count = results().len();
+                if count > 0 {
+                    let next = (highlighted.get_untracked() + 1).min(count - 1);
+                    highlighted.set(next);
+                    scroll_to_result(next);
+                }
+            }
+            "ArrowUp" => {
+                event.prevent_default();
+                let next = highlighted.get_untracked().saturating_sub(1);
+                highlighted.set(next);
+                scroll_to_result(next);
+            }
+            "Enter" => {
+                event.prevent_default();
+                if let Some((item, _)) = results().into_iter().nth(highlighted.get_untracked()) {
+                    run_item(item, event.shift_key());
+                }
+            }
+            "Escape" => {
+                event.prevent_default();
+                open.set(false);
+            }
+            _ => {}
+        }
+    };
+
+    view! {
+        {move || {
+            if open.get() {
+                let results = results.clone();
+                let run_item = run_item.clone();
+                let on_keydown = on_keydown.clone();
+                view! {
+                    <div
+                        class="fixed inset-0 bg-black bg-opacity-50 flex items-start justify-center z-50 p-4 pt-24"
+                        on:click=move |_| open.set(false)
+                    >
+                        <div
+                            class="bg-white dark:bg-gray-800 rounded-lg shadow-xl max-w-xl w-full overflow-hidden"
+                            on:click=|event: MouseEvent| event.stop_propagation()
+                        >
+                            <input
+                                node_ref=input_ref
+                                type="text"
+                                class="w-full px-4 py-3 text-lg bg-transparent text-gray-800 dark:text-white border-b border-gray-200 dark:border-gray-700 focus:outline-none"
+                                placeholder="Search tasks and actions..."
+                                prop:value=move || query.get()
+                                on:input=move |ev| {
+                                    query.set(event_target_value(&ev));
+                                    highlighted.set(0);
+                                }
+                                on:keydown=on_keydown
+                            />
+
+                            <div class="max-h-96 overflow-y-auto py-1">
+                                {move || {
+                                    let items = results();
+                                    if items.is_empty() {
+                                        view! {
+                                            <p class="px-4 py-6 text-center text-sm text-gray-500 dark:text-gray-400">
+                                                "No matching tasks or actions"
+                                            </p>
+                                        }.into_any()
+                                    } else {
+                                        items.into_iter().enumerate().map(|(index, (item, recent))| {
+                                            let (icon, color, title, subtitle) = match &item {
+                                                PaletteItem::Task(task) => (
+                                                    "📋",
+                                                    Some(task.color.clone()),
+                                                    task.name.clone(),
+                                                    "Task".to_string(),
+                                                ),
+                                                PaletteItem::Subtask { subtask, parent } => (
+                                                    "↳",
+                                                    Some(parent.color.clone()),
+                                                    subtask.name.clone(),
+                                                    format!("Subtask of {}", parent.name),
+                                                ),
+                                                PaletteItem::Action { label, .. } => (
+                                                    "⚡",
+                                                    None,
+                                                    label.clone(),
+                                                    "Action".to_string(),
+                                                ),
+                                            };
+                                            let run_item = run_item.clone();
+
+                                            view! {
+                                                <button
+                                                    data-palette-index=index
+                                                    class=move || format!(
+                                                        "w-full flex items-center gap-3 px-4 py-2 text-left transition-colors {}",
+                                                        if highlighted.get() == index {
+                                                            "bg-blue-50 dark:bg-gray-700"
+                                                        } else {
+                                                            "hover:bg-gray-50 dark:hover:bg-gray-700"
+                                                        }
+                                                    )
+                                                    on:mouseenter=move |_| highlighted.set(index)
+                                                    on:click=move |event: MouseEvent| run_item(item.clone(), event.shift_key())
+                                                >
+                                                    <span class="w-5 text-center">{icon}</span>
+                                                    {color.map(|color| view! {
+                                                        <span
+                                                            class="w-2 h-2 rounded-full flex-shrink-0"
+                                                            style=format!("background-color: {}", color)
+                                                        ></span>
+                                                    })}
+                                                    <div class="flex-1 min-w-0">
+                                                        <div class="text-sm text-gray-800 dark:text-white truncate">{title}</div>
+                                                        <div class="text-xs text-gray-500 dark:text-gray-400 truncate">{subtitle}</div>
+                                                    </div>
+                                                    {recent.then(|| view! {
+                                                        <span class="text-xs px-2 py-0.5 bg-gray-100 dark:bg-gray-600 text-gray-600 dark:text-gray-300 rounded">
+                                                            "Recent"
+                                                        </span>
+                                                    })}
+                                                </button>
+                                            }
+                                        }).collect_view().into_any()
+                                    }
+                                }}
+                            </div>
+
+                            <div class="px-4 py-2 border-t border-gray-200 dark:border-gray-700 text-xs text-gray-500 dark:text-gray-400 flex gap-4">
+                                <span>"↑↓ navigate"</span>
+                                <span>"↵ select"</span>
+                                <span>"⇧↵ select and start"</span>
+                                <span>"Esc close"</span>
+                            </div>
+                        </div>
+                    </div>
+                }.into_any()
+            } else {
+                view! { <div></div> }.into_any()
+            }
+        }}
+    }
+}
@@ -0,0 +1,210 @@
+use crate::console_log;
+use crate::timer::TimerController;
+use crate::types::{get_daily_goal_status_from_db, DailyGoalSettings, DailyGoalStatus, GoalMetric};
+use crate::utils::get_today_date_string;
+use leptos::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+
+fn update_goal(goal: RwSignal<DailyGoalSettings>, change: impl FnOnce(&mut DailyGoalSettings)) {
+    let mut settings = goal.get_untracked();
+    change(&mut settings);
+    settings.save_to_storage();
+    goal.set(settings);
+}
+
+fn days_label(days: u32) -> String {
+    if days == 1 {
+        "1 day".to_string()
+    } else {
+        format!("{} days", days)
+    }
+}
+
+// Today's progress toward the daily goal with the current and best streak
+#[component]
+pub fn DailyGoalProgress(timer_controller: TimerController) -> impl IntoView {
+    let status = RwSignal::new(None::<DailyGoalStatus>);
+    let goal = timer_controller.daily_goal;
+    let session_stats = timer_controller.session_stats;
+
+    Effect::new(move |_| {
+        let settings = goal.get();
+        session_stats.track();
+        if !settings.enabled {
+            status.set(None);
+            return;
+        }
+        spawn_local(async move {
+            match get_daily_goal_status_from_db(&settings).await {
+                Ok(goal_status) => status.set(Some(goal_status)),
+                Err(e) => console_log!("Error loading daily goal: {}", e),
+            }
+        });
+    });
+
+    view! {
+        {move || {
+            if let Some(status) = status.get() {
+                let percentage = if status.target > 0 {
+                    (status.progress as f64 / status.target as f64 * 100.0).min(100.0)
+                } else {
+                    100.0
+                };
+                let summary = if status.target == 0 {
+                    format!("Rest day · {} {}", status.progress, status.metric.unit())
+                } else if status.progress >= status.target {
+                    format!("🎉 Goal met · {} / {} {}", status.progress, status.target, status.metric.unit())
+                } else {
+                    format!("{} / {} {}", status.progress, status.target, status.metric.unit())
+                };
+                view! {
+                    <div class="mb-4 p-3 bg-white/80 dark:bg-gray-800/80 rounded-lg shadow-sm">
+                        <div class="flex justify-between items-center text-sm mb-2">
+                            <span class="font-medium text-gray-700 dark:text-gray-300">"Daily goal"</span>
+                            <span class="text-gray-600 dark:text-gray-400">{summary}</span>
+                        </div>
+                        <div class="w-full h-2 bg-gray-200 dark:bg-gray-700 rounded-full overflow-hidden">
+                            <div
+                                class="h-2 bg-green-500 rounded-full transition-all"
+                                style=format!("width: {:.1}%", percentage)
+                            ></div>
+                        </div>
+                        <div class="flex justify-between text-xs text-gray-500 dark:text-gray-400 mt-2">
+                            <span>{format!("🔥 Streak: {}", days_label(status.current_streak))}</span>
+                            <span>{format!("Best: {}", days_label(status.longest_streak))}</span>
+                        </div>
+                    </div>
+                }.into_any()
+            } else {
+                view! { <div></div> }.into_any()
+            }
+        }}
+    }
+}
+
+// Goal metric, weekday/weekend targets and skipped days (holidays don't break a streak)
+#[component]
+pub fn DailyGoalSettingsPanel(timer_controller: TimerController) -> impl IntoView {
+    let goal = timer_controller.daily_goal;
+    let new_skipped_day = RwSignal::new(get_today_date_string());
+
+    view! {
+        <div class="mt-6 p-4 bg-gray-50 dark:bg-gray-700 rounded-lg space-y-4">
+            <div class="flex justify-between items-center">
+                <h4 class="text-md font-semibold text-gray-800 dark:text-white">"Daily Goal"</h4>
+                <label class="flex items-center space-x-2 text-sm text-gray-700 dark:text-gray-300">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || goal.get().enabled
+                        on:change=move |ev| {
+                            let enabled = event_target_checked(&ev);
+                            update_goal(goal, |s| s.enabled = enabled);
+                        }
+                    />
+                    <span>"Enabled"</span>
+                </label>
+            </div>
+
+            <div class="grid grid-cols-1 md:grid-cols-3 gap-3">
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Measure"</label>
+                    <select
+                        class="w-full px-2 py-1 border rounded text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        on:change=move |ev| {
+                            let metric = if event_target_value(&ev) == "Pomodoros" {
+                                GoalMetric::Pomodoros
+                            } else {
+                                GoalMetric::FocusMinutes
+                            };
+                            update_goal(goal, |s| s.metric = metric);
+                        }
+                    >
+                        <option value="FocusMinutes" selected=move || goal.get().metric == GoalMetric::FocusMinutes>
+                            "Focus minutes"
+                        </option>
+                        <option value="Pomodoros" selected=move || goal.get().metric == GoalMetric::Pomodoros>
+                            "Completed pomodoros"
+                        </option>
+                    </select>
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Weekday target"</label>
+                    <input
+                        type="number"
+                        min="0"
+                        class="w-full px-2 py-1 border rounded text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        prop:value=move || goal.get().weekday_target.to_string()
+                        on:change=move |ev| {
+                            if let Ok(target) = event_target_value(&ev).parse::<u32>() {
+                                update_goal(goal, |s| s.weekday_target = target);
+                            }
+                        }
+                    />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Weekend target (0 = rest)"</label>
+                    <input
+                        type="number"
+                        min="0"
+                        class="w-full px-2 py-1 border rounded text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        prop:value=move || goal.get().weekend_target.to_string()
+                        on:change=move |ev| {
+                            if let Ok(target) = event_target_value(&ev).parse::<u32>() {
+                                update_goal(goal, |s| s.weekend_target = target);
+                            }
+                        }
+                    />
+                </div>
+            </div>
+
+            <div>
+                <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">
+                    "Skipped days (holidays and days off keep your streak)"
+                </label>
+                <div class="flex items-center gap-2">
+                    <input
+                        type="date"
+                        class="px-2 py-1 border rounded text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        prop:value=move || new_skipped_day.get()
+                        on:input=move |ev| new_skipped_day.set(event_target_value(&ev))
+                    />
+                    <button
+                        class="px-3 py-1 text-xs bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-700 dark:text-gray-200 rounded transition-colors"
+                        on:click=move |_| {
+                            let day = new_skipped_day.get_untracked();
+                            if day.is_empty() {
+                                return;
+                            }
+                            update_goal(goal, |s| {
+                                if !s.skipped_days.contains(&day) {
+                                    s.skipped_days.push(day.clone());
+                                    s.skipped_days.sort();
+                                }
+                            });
+                        }
+                    >
+                        "Add"
+                    </button>
+                </div>
+                <div class="flex flex-wrap gap-2 mt-2">
+                    {move || {
+                        goal.get().skipped_days.into_iter().map(|day| {
+                            let removed = day.clone();
+                            view! {
+                                <span class="flex items-center space-x-1 text-xs bg-gray-200 dark:bg-gray-600 text-gray-700 dark:text-gray-200 px-2 py-1 rounded">
+                                    <span>{day}</span>
+                                    <button
+                                        class="text-gray-500 hover:text-red-500"
+                                        on:click=move |_| update_goal(goal, |s| s.skipped_days.retain(|d| d != &removed))
+                                    >
+                                        "✕"
+                                    </button>
+                                </span>
+                            }
+                        }).collect_view()
+                    }}
+                </div>
+            </div>
+        </div>
+    }
+}
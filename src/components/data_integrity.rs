@@ -0,0 +1,86 @@
+use crate::task::TaskController;
+use crate::types::IntegrityRepair;
+use crate::utils::format_iso_date;
+use leptos::prelude::*;
+
+// Result of the last verify & repair run, with buttons to check again or fix everything
+#[component]
+pub fn DataIntegrityPanel(task_controller: TaskController) -> impl IntoView {
+    let report = task_controller.integrity_report;
+    let controller_verify = task_controller.clone();
+    let controller_repair = task_controller.clone();
+
+    view! {
+        <div class="mb-6 p-4 border rounded-lg bg-gray-50 dark:bg-gray-700 border-gray-200 dark:border-gray-600">
+            <div class="flex justify-between items-center mb-3">
+                <h4 class="text-md font-medium text-gray-800 dark:text-white">"Data Integrity"</h4>
+                <div class="flex space-x-2">
+                    <button
+                        class="text-xs px-2 py-1 bg-blue-500 hover:bg-blue-600 text-white rounded transition-colors"
+                        on:click=move |_| controller_verify.verify_integrity(IntegrityRepair::None)
+                    >
+                        "Verify"
+                    </button>
+                    <button
+                        class="text-xs px-2 py-1 bg-red-500 hover:bg-red-600 text-white rounded transition-colors disabled:opacity-50"
+                        disabled=move || report.get().is_none_or(|r| r.unresolved() == 0)
+                        on:click=move |_| {
+                            if web_sys::window()
+                                .and_then(|w| w.confirm_with_message(
+                                    "Repair all issues? Orphaned subtasks are deleted and sessions linked to deleted tasks are detached."
+                                ).ok())
+                                .unwrap_or(false)
+                            {
+                                controller_repair.verify_integrity(IntegrityRepair::All);
+                            }
+                        }
+                    >
+                        "Repair all"
+                    </button>
+                </div>
+            </div>
+
+            {move || {
+                let Some(report) = report.get() else {
+                    return view! {
+                        <p class="text-sm text-gray-500 dark:text-gray-400">"Checking..."</p>
+                    }.into_any();
+                };
+                let summary = format!(
+                    "Checked {} tasks, {} subtasks and {} sessions on {}",
+                    report.tasks,
+                    report.subtasks,
+                    report.sessions,
+                    format_iso_date(&report.checked_at)
+                );
+                if report.issues.is_empty() {
+                    return view! {
+                        <div class="text-sm text-gray-600 dark:text-gray-400">
+                            <p>"✅ No problems found."</p>
+                            <p class="text-xs mt-1">{summary}</p>
+                        </div>
+                    }.into_any();
+                }
+                view! {
+                    <div class="text-sm text-gray-700 dark:text-gray-300">
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mb-2">{summary}</p>
+                        <ul class="space-y-1">
+                            {report.issues.iter().map(|issue| {
+                                let fixed = report.is_fixed(issue);
+                                view! {
+                                    <li class="flex items-start space-x-2">
+                                        <span>{if fixed { "🔧" } else { "⚠️" }}</span>
+                                        <span class=if fixed { "text-gray-500 dark:text-gray-400" } else { "" }>
+                                            {issue.describe()}
+                                            {if fixed { " (fixed)" } else { "" }}
+                                        </span>
+                                    </li>
+                                }
+                            }).collect_view()}
+                        </ul>
+                    </div>
+                }.into_any()
+            }}
+        </div>
+    }
+}
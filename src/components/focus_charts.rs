@@ -0,0 +1,284 @@
+use crate::console_log;
+use crate::task::TaskController;
+use crate::timer::TimerController;
+use crate::types::{get_focus_charts_from_db, FocusCharts, TaskFocusSegment, CHART_WEEK_OPTIONS};
+use crate::utils::format_duration_hours_minutes;
+use leptos::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+
+const CHART_HEIGHT: f64 = 100.0;
+const LABEL_SPACE: f64 = 14.0;
+const CHART_WIDTH: f64 = 240.0;
+const MAX_AXIS_LABELS: usize = 8;
+const WEEKDAY_LABELS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+// Height of a bar for `value` when `max` fills the chart
+fn scaled(value: u32, max: u32) -> f64 {
+    if max == 0 {
+        0.0
+    } else {
+        value as f64 / max as f64 * CHART_HEIGHT
+    }
+}
+
+// Horizontal space per bar when `count` bars share the chart width
+fn slot_width(count: usize) -> f64 {
+    CHART_WIDTH / count.max(1) as f64
+}
+
+// "03/18" from a YYYY-MM-DD week start; only every few weeks are labelled on long ranges
+fn week_axis_label(index: usize, count: usize, date_key: &str) -> String {
+    if index.is_multiple_of(count.div_ceil(MAX_AXIS_LABELS).max(1)) {
+        date_key.get(5..).unwrap_or(date_key).replace('-', "/")
+    } else {
+        String::new()
+    }
+}
+
+#[component]
+fn ChartCard(title: &'static str, children: Children) -> impl IntoView {
+    view! {
+        <div class="bg-gray-50 dark:bg-gray-700 rounded-lg p-4">
+            <h4 class="text-sm font-medium text-gray-700 dark:text-gray-300 mb-3">{title}</h4>
+            {children()}
+        </div>
+    }
+}
+
+// One bar per value; `labels` may leave entries empty to thin out the axis
+#[component]
+fn BarChart(values: Vec<u32>, labels: Vec<String>, color: &'static str) -> impl IntoView {
+    let max = values.iter().copied().max().unwrap_or(0);
+    let slot = slot_width(values.len());
+
+    view! {
+        <svg
+            class="w-full h-auto text-gray-500 dark:text-gray-400"
+            viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT + LABEL_SPACE)
+        >
+            {values.into_iter().zip(labels).enumerate().map(|(i, (value, label))| {
+                let height = scaled(value, max);
+                let x = i as f64 * slot;
+                view! {
+                    <g>
+                        <rect
+                            x=x + slot * 0.15
+                            y=CHART_HEIGHT - height
+                            width=slot * 0.7
+                            height=height
+                            rx="1.5"
+                            fill=color
+                        >
+                            <title>{format!("{}: {}", label, format_duration_hours_minutes(value))}</title>
+                        </rect>
+                        <text
+                            x=x + slot / 2.0
+                            y=CHART_HEIGHT + LABEL_SPACE - 3.0
+                            font-size="8"
+                            text-anchor="middle"
+                            fill="currentColor"
+                        >
+                            {label}
+                        </text>
+                    </g>
+                }
+            }).collect_view()}
+        </svg>
+    }
+}
+
+// Weekly focus stacked by task, plus a legend of every task that appears
+#[component]
+fn StackedWeeklyChart(charts: FocusCharts) -> impl IntoView {
+    let max = charts.weeks.iter().map(|w| w.focus_time()).max().unwrap_or(0);
+    let count = charts.weeks.len();
+    let slot = slot_width(count);
+
+    let mut legend: Vec<TaskFocusSegment> = Vec::new();
+    for segment in charts.weeks.iter().flat_map(|w| &w.segments) {
+        match legend.iter_mut().find(|l| l.task_id == segment.task_id) {
+            Some(entry) => entry.focus_time += segment.focus_time,
+            None => legend.push(segment.clone()),
+        }
+    }
+    legend.sort_by_key(|l| std::cmp::Reverse(l.focus_time));
+
+    view! {
+        <svg
+            class="w-full h-auto text-gray-500 dark:text-gray-400"
+            viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT + LABEL_SPACE)
+        >
+            {charts.weeks.into_iter().enumerate().map(|(i, week)| {
+                let x = i as f64 * slot;
+                let mut top = CHART_HEIGHT;
+                let week_label = week_axis_label(i, count, &week.week_start);
+                view! {
+                    <g>
+                        {week.segments.into_iter().map(|segment| {
+                            let height = scaled(segment.focus_time, max);
+                            top -= height;
+                            view! {
+                                <rect x=x + slot * 0.15 y=top width=slot * 0.7 height=height fill=segment.color>
+                                    <title>
+                                        {format!(
+                                            "Week of {}: {} · {}",
+                                            week.week_start,
+                                            segment.name,
+                                            format_duration_hours_minutes(segment.focus_time)
+                                        )}
+                                    </title>
+                                </rect>
+                            }
+                        }).collect_view()}
+                        <text
+                            x=x + slot / 2.0
+                            y=CHART_HEIGHT + LABEL_SPACE - 3.0
+                            font-size="6"
+                            text-anchor="middle"
+                            fill="currentColor"
+                        >
+                            {week_label}
+                        </text>
+                    </g>
+                }
+            }).collect_view()}
+        </svg>
+        <div class="flex flex-wrap gap-x-3 gap-y-1 mt-2">
+            {legend.into_iter().map(|segment| view! {
+                <span class="flex items-center space-x-1 text-xs text-gray-600 dark:text-gray-400">
+                    <span class="w-2 h-2 rounded-full" style=format!("background-color: {}", segment.color)></span>
+                    <span>{segment.name}</span>
+                </span>
+            }).collect_view()}
+        </div>
+    }
+}
+
+// Share of sessions completed per week; weeks without sessions leave a gap in the line
+#[component]
+fn CompletionRateChart(charts: FocusCharts) -> impl IntoView {
+    let count = charts.weeks.len();
+    let slot = slot_width(count);
+    let points: Vec<(f64, f64, String)> = charts
+        .weeks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, week)| {
+            let rate = week.completion_rate()?;
+            let x = i as f64 * slot + slot / 2.0;
+            let y = CHART_HEIGHT - rate / 100.0 * CHART_HEIGHT;
+            Some((x, y, format!("Week of {}: {:.0}%", week.week_start, rate)))
+        })
+        .collect();
+    let line = points
+        .iter()
+        .map(|(x, y, _)| format!("{:.1},{:.1}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    view! {
+        <svg
+            class="w-full h-auto text-gray-500 dark:text-gray-400"
+            viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT + LABEL_SPACE)
+        >
+            {[0.0, 50.0, 100.0].into_iter().map(|rate: f64| {
+                let y = CHART_HEIGHT - rate / 100.0 * CHART_HEIGHT;
+                view! {
+                    <line x1="0" y1=y x2=CHART_WIDTH y2=y stroke="currentColor" stroke-opacity="0.2" stroke-width="0.5" />
+                }
+            }).collect_view()}
+            <polyline points=line fill="none" stroke="#8B5CF6" stroke-width="1.5" />
+            {points.into_iter().map(|(x, y, title)| view! {
+                <circle cx=x cy=y r="2" fill="#8B5CF6">
+                    <title>{title}</title>
+                </circle>
+            }).collect_view()}
+            {charts.weeks.iter().enumerate().map(|(i, week)| view! {
+                <text
+                    x=i as f64 * slot + slot / 2.0
+                    y=CHART_HEIGHT + LABEL_SPACE - 3.0
+                    font-size="6"
+                    text-anchor="middle"
+                    fill="currentColor"
+                >
+                    {week_axis_label(i, count, &week.week_start)}
+                </text>
+            }).collect_view()}
+        </svg>
+    }
+}
+
+// Focus by hour, weekday and task over the last N weeks, drawn as plain SVG
+#[component]
+pub fn FocusChartsPanel(task_controller: TaskController, timer_controller: TimerController) -> impl IntoView {
+    let weeks = RwSignal::new(CHART_WEEK_OPTIONS[1]);
+    let charts = RwSignal::new(None::<FocusCharts>);
+    let session_stats = timer_controller.session_stats;
+    let include_manual = timer_controller.include_manual_sessions;
+    let history = task_controller.history;
+
+    Effect::new(move |_| {
+        let weeks = weeks.get();
+        let include_manual = include_manual.get();
+        session_stats.track();
+        history.revision.track();
+        spawn_local(async move {
+            match get_focus_charts_from_db(weeks, include_manual).await {
+                Ok(data) => charts.set(Some(data)),
+                Err(e) => console_log!("Error loading focus charts: {}", e),
+            }
+        });
+    });
+
+    view! {
+        <div class="mt-8">
+            <div class="flex justify-between items-center mb-4">
+                <h3 class="text-lg font-semibold text-gray-800 dark:text-white">"Charts"</h3>
+                <select
+                    class="px-3 py-1 border rounded text-sm bg-white dark:bg-gray-700 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                    on:change=move |ev| {
+                        if let Ok(value) = event_target_value(&ev).parse() {
+                            weeks.set(value);
+                        }
+                    }
+                >
+                    {CHART_WEEK_OPTIONS.into_iter().map(|option| view! {
+                        <option value=option.to_string() selected=move || weeks.get() == option>
+                            {format!("Last {} weeks", option)}
+                        </option>
+                    }).collect_view()}
+                </select>
+            </div>
+
+            {move || {
+                if let Some(data) = charts.get() {
+                    let hour_labels = (0..24)
+                        .map(|hour| if hour % 3 == 0 { format!("{}h", hour) } else { String::new() })
+                        .collect();
+                    let weekday_labels = WEEKDAY_LABELS.iter().map(|l| l.to_string()).collect();
+                    let by_hour = data.by_hour.to_vec();
+                    let by_weekday = data.by_weekday.to_vec();
+                    let stacked = data.clone();
+                    view! {
+                        <div class="grid grid-cols-1 lg:grid-cols-2 gap-4">
+                            <ChartCard title="Focus by hour of day">
+                                <BarChart values=by_hour labels=hour_labels color="#EF4444" />
+                            </ChartCard>
+                            <ChartCard title="Focus by weekday">
+                                <BarChart values=by_weekday labels=weekday_labels color="#F59E0B" />
+                            </ChartCard>
+                            <ChartCard title="Weekly focus by task">
+                                <StackedWeeklyChart charts=stacked />
+                            </ChartCard>
+                            <ChartCard title="Completion rate">
+                                <CompletionRateChart charts=data />
+                            </ChartCard>
+                        </div>
+                    }.into_any()
+                } else {
+                    view! { <div></div> }.into_any()
+                }
+            }}
+        </div>
+    }
+}
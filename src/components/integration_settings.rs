@@ -0,0 +1,561 @@
+use crate::issues::{IssueImportSettings, IssueProvider, IssueSync};
+use crate::caldav::{CalDavSettings, CalDavSync, CALDAV_SOURCE};
+use crate::task::TaskController;
+use crate::taskwarrior::{get_taskwarrior_binary, TaskwarriorSettings, TaskwarriorSync};
+use crate::todotxt::{TodoTxtSettings, TodoTxtSync, TODOTXT_SOURCE};
+use crate::types::SyncConflict;
+use leptos::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+
+#[component]
+pub fn IntegrationSettings(
+    todotxt_sync: TodoTxtSync,
+    taskwarrior_sync: TaskwarriorSync,
+    issue_sync: IssueSync,
+    caldav_sync: CalDavSync,
+    task_controller: TaskController,
+) -> impl IntoView {
+    view! {
+        <div class="integration-settings space-y-6">
+            <h4 class="text-lg font-medium text-gray-700 dark:text-gray-300 mb-4">
+                "Integrations"
+            </h4>
+
+            <TodoTxtSettingsPanel todotxt_sync=todotxt_sync task_controller=task_controller.clone() />
+            <TaskwarriorSettingsPanel taskwarrior_sync=taskwarrior_sync />
+            <IssueImportSettingsPanel issue_sync=issue_sync />
+            <CalDavSettingsPanel caldav_sync=caldav_sync task_controller=task_controller />
+        </div>
+    }
+}
+
+#[component]
+fn TodoTxtSettingsPanel(todotxt_sync: TodoTxtSync, task_controller: TaskController) -> impl IntoView {
+    let initial = todotxt_sync.settings.get_untracked();
+    let path_input = RwSignal::new(initial.path.clone());
+    let enabled_input = RwSignal::new(initial.enabled);
+
+    let save_settings = {
+        let todotxt_sync = todotxt_sync.clone();
+        move |_| {
+            todotxt_sync.update_settings(TodoTxtSettings {
+                enabled: enabled_input.get(),
+                path: path_input.get().trim().to_string(),
+            });
+        }
+    };
+
+    let sync_now = {
+        let todotxt_sync = todotxt_sync.clone();
+        move |_| todotxt_sync.sync_now()
+    };
+
+    let syncing = todotxt_sync.syncing;
+    let status = todotxt_sync.status;
+    let settings = todotxt_sync.settings;
+
+    view! {
+        <div class="p-4 bg-gray-50 dark:bg-gray-700 rounded-lg border space-y-4">
+            <div>
+                <h5 class="text-md font-medium text-gray-700 dark:text-gray-300">"todo.txt"</h5>
+                <p class="text-xs text-gray-600 dark:text-gray-400">
+                    "Each line becomes a task. +project picks the project, @context becomes a tag, pomo:N sets the estimate. Each line gets an id:… tag linking it to its task, so lines can be edited freely. Completing a task writes \"x YYYY-MM-DD\" back to the file."
+                </p>
+            </div>
+
+            <div class="space-y-2">
+                <label class="block text-sm font-medium text-gray-700 dark:text-gray-300">"File path"</label>
+                <input
+                    type="text"
+                    placeholder="/home/me/Dropbox/todo/todo.txt"
+                    class="w-full px-3 py-2 border rounded font-mono text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 placeholder-gray-500 dark:placeholder-gray-400"
+                    prop:value=move || path_input.get()
+                    on:input=move |ev| path_input.set(event_target_value(&ev))
+                />
+                <label class="flex items-center space-x-2 text-sm text-gray-700 dark:text-gray-300">
+                    <input
+                        type="checkbox"
+                        class="rounded border-gray-300 text-blue-600"
+                        prop:checked=move || enabled_input.get()
+                        on:change=move |ev| enabled_input.set(event_target_checked(&ev))
+                    />
+                    <span>"Watch the file and sync changes"</span>
+                </label>
+            </div>
+
+            <div class="flex flex-wrap gap-2 items-center">
+                <button
+                    class="px-3 py-1 bg-blue-500 hover:bg-blue-600 text-white text-sm rounded transition-colors"
+                    on:click=save_settings
+                >
+                    "Save"
+                </button>
+                <button
+                    class="px-3 py-1 bg-green-500 hover:bg-green-600 text-white text-sm rounded transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || {
+                        let current = settings.get();
+                        !current.enabled || current.path.is_empty() || syncing.get()
+                    }
+                    on:click=sync_now
+                >
+                    {move || if syncing.get() { "Syncing..." } else { "Sync now" }}
+                </button>
+                {move || status.get().map(|message| view! {
+                    <span class="text-xs text-gray-600 dark:text-gray-400">{message}</span>
+                })}
+            </div>
+
+            <SyncConflictList
+                task_controller=task_controller
+                source=TODOTXT_SOURCE
+                remote_label="todo.txt"
+                on_resolve=Callback::new(move |(conflict, keep_local)| todotxt_sync.resolve_conflict(conflict, keep_local))
+            />
+        </div>
+    }
+}
+
+#[component]
+fn TaskwarriorSettingsPanel(taskwarrior_sync: TaskwarriorSync) -> impl IntoView {
+    let initial = taskwarrior_sync.settings.get_untracked();
+    let binary = RwSignal::new(None::<Result<String, String>>);
+    spawn_local(async move { binary.set(Some(get_taskwarrior_binary().await)) });
+    let uda_input = RwSignal::new(initial.focus_uda.clone());
+    let enabled_input = RwSignal::new(initial.enabled);
+
+    let save_settings = {
+        let taskwarrior_sync = taskwarrior_sync.clone();
+        move |_| {
+            let defaults = TaskwarriorSettings::default();
+            let focus_uda = uda_input.get().trim().to_string();
+            taskwarrior_sync.update_settings(TaskwarriorSettings {
+                enabled: enabled_input.get(),
+                focus_uda: if focus_uda.is_empty() { defaults.focus_uda } else { focus_uda },
+            });
+        }
+    };
+
+    let import_now = {
+        let taskwarrior_sync = taskwarrior_sync.clone();
+        move |_| taskwarrior_sync.import_now()
+    };
+
+    let syncing = taskwarrior_sync.syncing;
+    let status = taskwarrior_sync.status;
+    let settings = taskwarrior_sync.settings;
+
+    view! {
+        <div class="p-4 bg-gray-50 dark:bg-gray-700 rounded-lg border space-y-4">
+            <div>
+                <h5 class="text-md font-medium text-gray-700 dark:text-gray-300">"Taskwarrior"</h5>
+                <p class="text-xs text-gray-600 dark:text-gray-400">
+                    "Imports pending tasks from `task export`. Finished pomodoros annotate the Taskwarrior task and update its focus UDA; completing a task here runs `task <uuid> done`."
+                </p>
+            </div>
+
+            <div class="grid grid-cols-1 md:grid-cols-2 gap-4">
+                <div class="space-y-1">
+                    <label class="block text-sm font-medium text-gray-700 dark:text-gray-300">"task binary"</label>
+                    // Only the backend decides what runs: `task` from PATH or "binary" in taskwarrior.json
+                    {move || match binary.get() {
+                        Some(Ok(path)) => view! {
+                            <p class="px-3 py-2 font-mono text-sm text-gray-700 dark:text-gray-300 break-all">{path}</p>
+                        }.into_any(),
+                        Some(Err(e)) => view! {
+                            <p class="px-3 py-2 text-xs text-red-600 dark:text-red-400 break-all">{e}</p>
+                        }.into_any(),
+                        None => view! { <div></div> }.into_any(),
+                    }}
+                </div>
+                <div class="space-y-1">
+                    <label class="block text-sm font-medium text-gray-700 dark:text-gray-300">"Focus time UDA (minutes)"</label>
+                    <input
+                        type="text"
+                        placeholder="pomodoro_focus"
+                        class="w-full px-3 py-2 border rounded font-mono text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 placeholder-gray-500 dark:placeholder-gray-400"
+                        prop:value=move || uda_input.get()
+                        on:input=move |ev| uda_input.set(event_target_value(&ev))
+                    />
+                </div>
+            </div>
+            <label class="flex items-center space-x-2 text-sm text-gray-700 dark:text-gray-300">
+                <input
+                    type="checkbox"
+                    class="rounded border-gray-300 text-blue-600"
+                    prop:checked=move || enabled_input.get()
+                    on:change=move |ev| enabled_input.set(event_target_checked(&ev))
+                />
+                <span>"Enable Taskwarrior integration"</span>
+            </label>
+
+            <div class="flex flex-wrap gap-2 items-center">
+                <button
+                    class="px-3 py-1 bg-blue-500 hover:bg-blue-600 text-white text-sm rounded transition-colors"
+                    on:click=save_settings
+                >
+                    "Save"
+                </button>
+                <button
+                    class="px-3 py-1 bg-green-500 hover:bg-green-600 text-white text-sm rounded transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || !settings.get().enabled || syncing.get()
+                    on:click=import_now
+                >
+                    {move || if syncing.get() { "Importing..." } else { "Import now" }}
+                </button>
+                {move || status.get().map(|message| view! {
+                    <span class="text-xs text-gray-600 dark:text-gray-400">{message}</span>
+                })}
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn IssueImportSettingsPanel(issue_sync: IssueSync) -> impl IntoView {
+    let initial = issue_sync.settings.get_untracked();
+    let provider_input = RwSignal::new(initial.provider);
+    let base_url_input = RwSignal::new(initial.base_url.clone());
+    let token_input = RwSignal::new(String::new());
+    let comments_input = RwSignal::new(initial.post_focus_comments);
+    let enabled_input = RwSignal::new(initial.enabled);
+
+    let change_provider = move |ev| {
+        let provider = if event_target_value(&ev) == "gitlab" { IssueProvider::GitLab } else { IssueProvider::GitHub };
+        // Swap the API root too, unless it was pointed somewhere custom
+        if base_url_input.get() == provider_input.get().default_base_url() {
+            base_url_input.set(provider.default_base_url().to_string());
+        }
+        provider_input.set(provider);
+    };
+
+    let save_settings = {
+        let issue_sync = issue_sync.clone();
+        move |_| {
+            let provider = provider_input.get();
+            let base_url = base_url_input.get().trim().to_string();
+            issue_sync.update_settings(
+                IssueImportSettings {
+                    enabled: enabled_input.get(),
+                    provider,
+                    base_url: if base_url.is_empty() { provider.default_base_url().to_string() } else { base_url },
+                    post_focus_comments: comments_input.get(),
+                    ..IssueImportSettings::default()
+                },
+                token_input.get(),
+            );
+            token_input.set(String::new());
+        }
+    };
+
+    let import_now = {
+        let issue_sync = issue_sync.clone();
+        move |_| issue_sync.import_now()
+    };
+
+    let syncing = issue_sync.syncing;
+    let status = issue_sync.status;
+    let settings = issue_sync.settings;
+
+    view! {
+        <div class="p-4 bg-gray-50 dark:bg-gray-700 rounded-lg border space-y-4">
+            <div>
+                <h5 class="text-md font-medium text-gray-700 dark:text-gray-300">"GitHub / GitLab issues"</h5>
+                <p class="text-xs text-gray-600 dark:text-gray-400">
+                    "Imports issues assigned to you as tasks, one project per repository. Closed issues complete their task on the next import."
+                </p>
+            </div>
+
+            <div class="grid grid-cols-1 md:grid-cols-2 gap-4">
+                <div class="space-y-1">
+                    <label class="block text-sm font-medium text-gray-700 dark:text-gray-300">"Provider"</label>
+                    <select
+                        class="w-full px-3 py-2 border rounded bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        on:change=change_provider
+                    >
+                        {[IssueProvider::GitHub, IssueProvider::GitLab].into_iter().map(|provider| {
+                            view! {
+                                <option value=provider.source() selected=move || provider_input.get() == provider>
+                                    {provider.display_name()}
+                                </option>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+                <div class="space-y-1">
+                    <label class="block text-sm font-medium text-gray-700 dark:text-gray-300">"API URL"</label>
+                    <input
+                        type="text"
+                        class="w-full px-3 py-2 border rounded font-mono text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        prop:value=move || base_url_input.get()
+                        on:input=move |ev| base_url_input.set(event_target_value(&ev))
+                    />
+                </div>
+            </div>
+            <div class="space-y-1">
+                <label class="block text-sm font-medium text-gray-700 dark:text-gray-300">"Access token"</label>
+                <input
+                    type="password"
+                    placeholder=move || if settings.get().token_ref.is_some() {
+                        "Saved (leave empty to keep it)"
+                    } else {
+                        "Personal access token"
+                    }
+                    class="w-full px-3 py-2 border rounded font-mono text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 placeholder-gray-500 dark:placeholder-gray-400"
+                    prop:value=move || token_input.get()
+                    on:input=move |ev| token_input.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="space-y-2">
+                <label class="flex items-center space-x-2 text-sm text-gray-700 dark:text-gray-300">
+                    <input
+                        type="checkbox"
+                        class="rounded border-gray-300 text-blue-600"
+                        prop:checked=move || comments_input.get()
+                        on:change=move |ev| comments_input.set(event_target_checked(&ev))
+                    />
+                    <span>"Comment on the issue after each finished pomodoro"</span>
+                </label>
+                <label class="flex items-center space-x-2 text-sm text-gray-700 dark:text-gray-300">
+                    <input
+                        type="checkbox"
+                        class="rounded border-gray-300 text-blue-600"
+                        prop:checked=move || enabled_input.get()
+                        on:change=move |ev| enabled_input.set(event_target_checked(&ev))
+                    />
+                    <span>"Enable issue import"</span>
+                </label>
+            </div>
+
+            <div class="flex flex-wrap gap-2 items-center">
+                <button
+                    class="px-3 py-1 bg-blue-500 hover:bg-blue-600 text-white text-sm rounded transition-colors"
+                    on:click=save_settings
+                >
+                    "Save"
+                </button>
+                <button
+                    class="px-3 py-1 bg-green-500 hover:bg-green-600 text-white text-sm rounded transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || {
+                        let current = settings.get();
+                        !current.enabled || current.token_ref.is_none() || syncing.get()
+                    }
+                    on:click=import_now
+                >
+                    {move || if syncing.get() { "Importing..." } else { "Import now" }}
+                </button>
+                {move || status.get().map(|message| view! {
+                    <span class="text-xs text-gray-600 dark:text-gray-400">{message}</span>
+                })}
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn CalDavSettingsPanel(caldav_sync: CalDavSync, task_controller: TaskController) -> impl IntoView {
+    let initial = caldav_sync.settings.get_untracked();
+    let url_input = RwSignal::new(initial.collection_url.clone());
+    let username_input = RwSignal::new(initial.username.clone());
+    let password_input = RwSignal::new(String::new());
+    let project_input = RwSignal::new(initial.project_id.clone().unwrap_or_default());
+    let interval_input = RwSignal::new(initial.interval_minutes);
+    let enabled_input = RwSignal::new(initial.enabled);
+
+    let save_settings = {
+        let caldav_sync = caldav_sync.clone();
+        move |_| {
+            let project_id = project_input.get();
+            caldav_sync.update_settings(
+                CalDavSettings {
+                    enabled: enabled_input.get(),
+                    collection_url: url_input.get().trim().to_string(),
+                    username: username_input.get().trim().to_string(),
+                    project_id: if project_id.is_empty() { None } else { Some(project_id) },
+                    interval_minutes: interval_input.get().max(1),
+                    ..CalDavSettings::default()
+                },
+                password_input.get(),
+            );
+            password_input.set(String::new());
+        }
+    };
+
+    let sync_now = {
+        let caldav_sync = caldav_sync.clone();
+        move |_| caldav_sync.sync_now()
+    };
+
+    let syncing = caldav_sync.syncing;
+    let status = caldav_sync.status;
+    let settings = caldav_sync.settings;
+    let projects = task_controller.projects;
+
+    view! {
+        <div class="p-4 bg-gray-50 dark:bg-gray-700 rounded-lg border space-y-4">
+            <div>
+                <h5 class="text-md font-medium text-gray-700 dark:text-gray-300">"CalDAV tasks"</h5>
+                <p class="text-xs text-gray-600 dark:text-gray-400">
+                    "Syncs tasks and subtasks with VTODO items in a CalDAV collection: summary, description, due date, completion and parent links. Edits made on both sides are flagged as conflicts."
+                </p>
+            </div>
+
+            <div class="space-y-1">
+                <label class="block text-sm font-medium text-gray-700 dark:text-gray-300">"Collection URL"</label>
+                <input
+                    type="text"
+                    placeholder="https://dav.example.com/calendars/me/tasks/"
+                    class="w-full px-3 py-2 border rounded font-mono text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 placeholder-gray-500 dark:placeholder-gray-400"
+                    prop:value=move || url_input.get()
+                    on:input=move |ev| url_input.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="grid grid-cols-1 md:grid-cols-2 gap-4">
+                <div class="space-y-1">
+                    <label class="block text-sm font-medium text-gray-700 dark:text-gray-300">"Username"</label>
+                    <input
+                        type="text"
+                        class="w-full px-3 py-2 border rounded text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        prop:value=move || username_input.get()
+                        on:input=move |ev| username_input.set(event_target_value(&ev))
+                    />
+                </div>
+                <div class="space-y-1">
+                    <label class="block text-sm font-medium text-gray-700 dark:text-gray-300">"Password"</label>
+                    <input
+                        type="password"
+                        placeholder=move || if settings.get().credential_ref.is_some() { "Saved (leave empty to keep it)" } else { "" }
+                        class="w-full px-3 py-2 border rounded text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 placeholder-gray-500 dark:placeholder-gray-400"
+                        prop:value=move || password_input.get()
+                        on:input=move |ev| password_input.set(event_target_value(&ev))
+                    />
+                </div>
+                <div class="space-y-1">
+                    <label class="block text-sm font-medium text-gray-700 dark:text-gray-300">"Project to sync"</label>
+                    <select
+                        class="w-full px-3 py-2 border rounded text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        on:change=move |ev| project_input.set(event_target_value(&ev))
+                    >
+                        <option value="" selected=move || project_input.get().is_empty()>"All tasks"</option>
+                        {move || projects.get().into_iter().filter(|p| !p.archived).map(|project| {
+                            let project_id = project.id.clone();
+                            view! {
+                                <option value=project.id.clone() selected=move || project_input.get() == project_id>
+                                    {project.name.clone()}
+                                </option>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+                <div class="space-y-1">
+                    <label class="block text-sm font-medium text-gray-700 dark:text-gray-300">"Sync every (minutes)"</label>
+                    <input
+                        type="number"
+                        min="1"
+                        class="w-full px-3 py-2 border rounded text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        prop:value=move || interval_input.get().to_string()
+                        on:input=move |ev| {
+                            if let Ok(minutes) = event_target_value(&ev).parse::<u32>() {
+                                interval_input.set(minutes);
+                            }
+                        }
+                    />
+                </div>
+            </div>
+            <label class="flex items-center space-x-2 text-sm text-gray-700 dark:text-gray-300">
+                <input
+                    type="checkbox"
+                    class="rounded border-gray-300 text-blue-600"
+                    prop:checked=move || enabled_input.get()
+                    on:change=move |ev| enabled_input.set(event_target_checked(&ev))
+                />
+                <span>"Enable CalDAV sync"</span>
+            </label>
+
+            <div class="flex flex-wrap gap-2 items-center">
+                <button
+                    class="px-3 py-1 bg-blue-500 hover:bg-blue-600 text-white text-sm rounded transition-colors"
+                    on:click=save_settings
+                >
+                    "Save"
+                </button>
+                <button
+                    class="px-3 py-1 bg-green-500 hover:bg-green-600 text-white text-sm rounded transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || {
+                        let current = settings.get();
+                        !current.is_active() || syncing.get()
+                    }
+                    on:click=sync_now
+                >
+                    {move || if syncing.get() { "Syncing..." } else { "Sync now" }}
+                </button>
+                {move || status.get().map(|message| view! {
+                    <span class="text-xs text-gray-600 dark:text-gray-400">{message}</span>
+                })}
+            </div>
+
+            <SyncConflictList
+                task_controller=task_controller
+                source=CALDAV_SOURCE
+                remote_label="server"
+                on_resolve=Callback::new(move |(conflict, keep_local)| caldav_sync.resolve_conflict(conflict, keep_local))
+            />
+        </div>
+    }
+}
+
+// Conflicts from concurrent edits for one source, resolved one task at a time
+#[component]
+fn SyncConflictList(
+    task_controller: TaskController,
+    source: &'static str,
+    remote_label: &'static str,
+    on_resolve: Callback<(SyncConflict, bool)>,
+) -> impl IntoView {
+    move || {
+        let conflicts: Vec<_> = task_controller
+            .sync_conflicts
+            .get()
+            .into_iter()
+            .filter(|c| c.source == source)
+            .collect();
+
+        if conflicts.is_empty() {
+            return view! { <div></div> }.into_any();
+        }
+
+        view! {
+            <div class="space-y-2">
+                <h6 class="text-sm font-medium text-red-600 dark:text-red-400">
+                    {format!("Conflicts ({})", conflicts.len())}
+                </h6>
+                {conflicts.into_iter().map(|conflict| {
+                    let conflict_remote = conflict.clone();
+                    let conflict_local = conflict.clone();
+
+                    view! {
+                        <div class="p-3 rounded border bg-white dark:bg-gray-800 border-red-200 dark:border-red-800 space-y-1">
+                            <div class="text-sm font-medium text-gray-800 dark:text-white">{conflict.task_name.clone()}</div>
+                            <div class="text-xs text-gray-600 dark:text-gray-400">{conflict.message.clone()}</div>
+                            <div class="text-xs font-mono text-gray-700 dark:text-gray-300">{format!("{}: ", remote_label)} {conflict.remote_version.clone()}</div>
+                            <div class="text-xs font-mono text-gray-700 dark:text-gray-300">"app: " {conflict.local_version.clone()}</div>
+                            <div class="flex space-x-2 pt-1">
+                                <button
+                                    class="text-xs px-2 py-1 bg-gray-500 hover:bg-gray-600 text-white rounded transition-colors"
+                                    on:click=move |_| on_resolve.run((conflict_remote.clone(), false))
+                                >
+                                    {format!("Use {}", remote_label)}
+                                </button>
+                                <button
+                                    class="text-xs px-2 py-1 bg-blue-500 hover:bg-blue-600 text-white rounded transition-colors"
+                                    on:click=move |_| on_resolve.run((conflict_local.clone(), true))
+                                >
+                                    "Keep app"
+                                </button>
+                            </div>
+                        </div>
+                    }
+                }).collect::<Vec<_>>()}
+            </div>
+        }.into_any()
+    }
+}
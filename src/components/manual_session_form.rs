@@ -0,0 +1,139 @@
+use crate::components::session_editor::{SessionTaskFields, INPUT_CLASS};
+use crate::console_log;
+use crate::task::TaskController;
+use crate::timer::TimerController;
+use crate::types::{log_manual_session, ManualSessionEntry, SessionType, TimerSettings};
+use crate::utils::{datetime_local_to_iso, iso_to_datetime_local};
+use leptos::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+
+// Form for logging a session that happened away from the timer. The times default to a
+// work session that just ended; `on_logged` runs once the session is saved.
+#[component]
+pub fn ManualSessionForm(
+    task_controller: TaskController,
+    timer_controller: TimerController,
+    on_logged: Callback<()>,
+) -> impl IntoView {
+    let history = task_controller.history;
+    let settings = TimerSettings::load_from_storage();
+
+    let now_ms = js_sys::Date::now();
+    let work_ms = SessionType::Work.duration_minutes(&settings) as f64 * 60_000.0;
+    let to_local = |ms: f64| iso_to_datetime_local(&String::from(js_sys::Date::new(&ms.into()).to_iso_string()));
+
+    let session_type = RwSignal::new(SessionType::Work);
+    let task_id = RwSignal::new(task_controller.selected_task.get_untracked().map(|t| t.id));
+    let subtask_id = RwSignal::new(task_controller.selected_subtask.get_untracked().map(|st| st.id));
+    let start = RwSignal::new(to_local(now_ms - work_ms));
+    let end = RwSignal::new(to_local(now_ms));
+    let error = RwSignal::new(None::<String>);
+    let saving = RwSignal::new(false);
+
+    let submit = move |_| {
+        let (Some(start_time), Some(end_time)) = (
+            datetime_local_to_iso(&start.get_untracked()),
+            datetime_local_to_iso(&end.get_untracked()),
+        ) else {
+            error.set(Some("Enter a valid start and end time".to_string()));
+            return;
+        };
+
+        let entry = ManualSessionEntry {
+            session_type: session_type.get_untracked(),
+            task_id: task_id.get_untracked(),
+            subtask_id: subtask_id.get_untracked(),
+            start_time,
+            end_time,
+        };
+        saving.set(true);
+        error.set(None);
+        let timer_controller = timer_controller.clone();
+        spawn_local(async move {
+            match history.record("Log session", log_manual_session(entry)).await {
+                Ok(session_id) => {
+                    console_log!("✍️ Logged past session {}", session_id);
+                    timer_controller.check_budget(&session_id).await;
+                    on_logged.run(());
+                }
+                Err(e) => {
+                    console_log!("Error logging session: {}", e);
+                    error.set(Some(e));
+                }
+            }
+            saving.set(false);
+        });
+    };
+
+    view! {
+        <div class="mb-4 p-4 bg-gray-50 dark:bg-gray-700/50 rounded-lg border border-gray-200 dark:border-gray-600 space-y-3">
+            <div class="grid grid-cols-1 md:grid-cols-3 gap-3">
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Type"</label>
+                    <select
+                        class=INPUT_CLASS
+                        on:change=move |ev| {
+                            session_type.set(match event_target_value(&ev).as_str() {
+                                "ShortBreak" => SessionType::ShortBreak,
+                                "LongBreak" => SessionType::LongBreak,
+                                _ => SessionType::Work,
+                            });
+                        }
+                    >
+                        <option value="Work">"Work"</option>
+                        <option value="ShortBreak">"Short Break"</option>
+                        <option value="LongBreak">"Long Break"</option>
+                    </select>
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Start"</label>
+                    <input
+                        type="datetime-local"
+                        class=INPUT_CLASS
+                        prop:value=move || start.get()
+                        on:input=move |ev| start.set(event_target_value(&ev))
+                    />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"End"</label>
+                    <input
+                        type="datetime-local"
+                        class=INPUT_CLASS
+                        prop:value=move || end.get()
+                        on:input=move |ev| end.set(event_target_value(&ev))
+                    />
+                </div>
+            </div>
+
+            {move || {
+                if session_type.get() == SessionType::Work {
+                    view! {
+                        <SessionTaskFields task_controller=task_controller.clone() task_id=task_id subtask_id=subtask_id />
+                    }.into_any()
+                } else {
+                    view! { <div></div> }.into_any()
+                }
+            }}
+
+            {move || {
+                if let Some(err) = error.get() {
+                    view! {
+                        <p class="text-red-500 text-xs">{err}</p>
+                    }.into_any()
+                } else {
+                    view! { <div></div> }.into_any()
+                }
+            }}
+
+            <div class="flex justify-end">
+                <button
+                    class="px-3 py-1 text-xs bg-blue-600 hover:bg-blue-700 text-white rounded transition-colors disabled:opacity-50"
+                    disabled=move || saving.get()
+                    on:click=submit
+                >
+                    "Log session"
+                </button>
+            </div>
+        </div>
+    }
+}
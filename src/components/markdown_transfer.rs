@@ -0,0 +1,144 @@
+use crate::console_log;
+use crate::markdown::{export_tasks_to_markdown, import_markdown_checklist};
+use crate::task::TaskController;
+use crate::types::ProjectFilter;
+use leptos::prelude::*;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+
+#[component]
+pub fn MarkdownTransfer(task_controller: TaskController) -> impl IntoView {
+    let markdown_text = RwSignal::new(String::new());
+    let export_scope = RwSignal::new("visible".to_string());
+    let status = RwSignal::new(None::<Result<String, String>>);
+    let importing = RwSignal::new(false);
+
+    let import_markdown = {
+        let task_controller = task_controller.clone();
+        move |_| {
+            let text = markdown_text.get();
+            // Imported tasks land in the project currently being viewed
+            let project_id = match task_controller.project_filter.get() {
+                ProjectFilter::Project(id) => Some(id),
+                _ => None,
+            };
+            let task_controller = task_controller.clone();
+            importing.set(true);
+            spawn_local(async move {
+                match import_markdown_checklist(&text, project_id).await {
+                    Ok(summary) => {
+                        console_log!("Imported {} tasks and {} subtasks from Markdown", summary.tasks, summary.subtasks);
+                        status.set(Some(Ok(format!(
+                            "Imported {} task(s) and {} subtask(s).",
+                            summary.tasks, summary.subtasks
+                        ))));
+                        markdown_text.set(String::new());
+                        task_controller.load_tasks();
+                        task_controller.load_task_stats();
+                    }
+                    Err(e) => {
+                        console_log!("Markdown import failed: {}", e);
+                        status.set(Some(Err(e)));
+                    }
+                }
+                importing.set(false);
+            });
+        }
+    };
+
+    let export_markdown = {
+        let task_controller = task_controller.clone();
+        move |_| {
+            let scope = export_scope.get();
+            let tasks: Vec<_> = if scope == "visible" {
+                task_controller
+                    .get_task_progress_summary()
+                    .into_iter()
+                    .map(|(task, ..)| task)
+                    .collect()
+            } else {
+                task_controller.get_task_by_id(&scope).into_iter().collect()
+            };
+            let markdown = export_tasks_to_markdown(&tasks, &task_controller.subtasks.get());
+            markdown_text.set(markdown);
+            status.set(Some(Ok(format!("Exported {} task(s).", tasks.len()))));
+        }
+    };
+
+    let copy_markdown = move |_| {
+        let text = markdown_text.get();
+        spawn_local(async move {
+            let Some(window) = web_sys::window() else {
+                return;
+            };
+            match JsFuture::from(window.navigator().clipboard().write_text(&text)).await {
+                Ok(_) => status.set(Some(Ok("Copied to clipboard.".to_string()))),
+                Err(e) => status.set(Some(Err(format!("Could not copy: {:?}", e)))),
+            }
+        });
+    };
+
+    view! {
+        <div class="mb-6 p-4 border rounded-lg bg-gray-50 dark:bg-gray-700 border-gray-200 dark:border-gray-600 space-y-3">
+            <div class="flex justify-between items-center">
+                <h4 class="text-md font-medium text-gray-800 dark:text-white">Markdown Import / Export</h4>
+                <span class="text-xs text-gray-500 dark:text-gray-400">"- [ ] Task (~3🍅) with indented subtasks"</span>
+            </div>
+
+            <textarea
+                placeholder="- [ ] Write report (~3🍅)\n  - [ ] Gather data (~1🍅)\n  - [x] Outline"
+                class="w-full px-3 py-2 border rounded font-mono text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 placeholder-gray-500 dark:placeholder-gray-400"
+                rows="8"
+                prop:value=move || markdown_text.get()
+                on:input=move |ev| markdown_text.set(event_target_value(&ev))
+            ></textarea>
+
+            <div class="flex flex-wrap gap-2 items-center">
+                <button
+                    class="px-3 py-1 bg-green-500 hover:bg-green-600 text-white text-sm rounded transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || markdown_text.get().trim().is_empty() || importing.get()
+                    on:click=import_markdown
+                >
+                    {move || if importing.get() { "Importing..." } else { "Import" }}
+                </button>
+
+                <select
+                    class="px-2 py-1 border rounded text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                    on:change=move |ev| export_scope.set(event_target_value(&ev))
+                >
+                    <option value="visible" selected=true>"All listed tasks"</option>
+                    {
+                        let task_controller = task_controller.clone();
+                        move || {
+                            task_controller.tasks.get().into_iter().map(|task| {
+                                view! { <option value=task.id.clone()>{task.name.clone()}</option> }
+                            }).collect::<Vec<_>>()
+                        }
+                    }
+                </select>
+                <button
+                    class="px-3 py-1 bg-blue-500 hover:bg-blue-600 text-white text-sm rounded transition-colors"
+                    on:click=export_markdown
+                >
+                    "Export"
+                </button>
+                <button
+                    class="px-3 py-1 bg-gray-500 hover:bg-gray-600 text-white text-sm rounded transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || markdown_text.get().is_empty()
+                    on:click=copy_markdown
+                >
+                    "Copy"
+                </button>
+            </div>
+
+            {move || match status.get() {
+                Some(Ok(message)) => view! {
+                    <p class="text-sm text-green-600 dark:text-green-400">{message}</p>
+                }.into_any(),
+                Some(Err(message)) => view! {
+                    <p class="text-sm text-red-600 dark:text-red-400">{message}</p>
+                }.into_any(),
+                None => view! { <div></div> }.into_any(),
+            }}
+        </div>
+    }
+}
@@ -1,5 +1,16 @@
+pub mod budget_alerts;
 pub mod camera_recorder;
 pub mod camera_settings;
+pub mod command_palette;
+pub mod daily_goal;
+pub mod data_integrity;
+pub mod focus_charts;
+pub mod integration_settings;
+pub mod manual_session_form;
+pub mod markdown_transfer;
+pub mod project_manager;
+pub mod session_calendar;
+pub mod session_editor;
 pub mod session_history;
 pub mod session_selector;
 pub mod session_stats;
@@ -9,10 +20,25 @@ pub mod timer_settings;
 pub mod task_selector;
 pub mod task_stats;
 pub mod theme_settings;           
+pub mod timesheet;
+pub mod today_planner;
+pub mod undo_toast;
+pub mod weekly_report;
 mod task_manager;
 
+pub use budget_alerts::{BudgetAlertBanner, BudgetAlertSettingsPanel};
 pub use camera_recorder::{CameraController, CameraRecorder};
 pub use camera_settings::CameraSettings;
+pub use command_palette::CommandPalette;
+pub use daily_goal::{DailyGoalProgress, DailyGoalSettingsPanel};
+pub use data_integrity::DataIntegrityPanel;
+pub use focus_charts::FocusChartsPanel;
+pub use integration_settings::IntegrationSettings;
+pub use manual_session_form::ManualSessionForm;
+pub use markdown_transfer::MarkdownTransfer;
+pub use project_manager::{ProjectFilterOptions, ProjectManager};
+pub use session_calendar::SessionCalendar;
+pub use session_editor::SessionEditor;
 pub use session_history::SessionHistory;
 pub use session_selector::SessionSelector;
 pub use session_stats::SessionStats;
@@ -21,5 +47,9 @@ pub use task_selector::TaskSelector;
 pub use task_stats::TaskStats;
 pub use theme_settings::ThemeSettings;
 pub use timer_controls::TimerControls;
+pub use timesheet::TimesheetPanel;
 pub use timer_display::TimerDisplay;
 pub use timer_settings::TimerSettings;
+pub use today_planner::TodayPlanner;
+pub use undo_toast::UndoToast;
+pub use weekly_report::WeeklyReportPanel;
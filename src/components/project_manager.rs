@@ -0,0 +1,261 @@
+use crate::task::TaskController;
+use crate::types::{NewProject, Project, TASK_COLORS};
+use crate::utils::format_duration_hours_minutes;
+use leptos::prelude::*;
+
+#[component]
+pub fn ProjectManager(task_controller: TaskController) -> impl IntoView {
+    let editing_project = RwSignal::new(None::<Project>);
+    let project_name = RwSignal::new(String::new());
+    let project_description = RwSignal::new(String::new());
+    let project_color = RwSignal::new(TASK_COLORS[0].to_string());
+    let project_rate = RwSignal::new(String::new());
+    let show_archived_projects = RwSignal::new(false);
+
+    let reset_form = move || {
+        editing_project.set(None);
+        project_name.set(String::new());
+        project_description.set(String::new());
+        project_color.set(TASK_COLORS[0].to_string());
+        project_rate.set(String::new());
+    };
+
+    view! {
+        <div class="mb-6 p-4 border rounded-lg bg-gray-50 dark:bg-gray-700 border-gray-200 dark:border-gray-600">
+            <div class="flex justify-between items-center mb-3">
+                <h4 class="text-md font-medium text-gray-800 dark:text-white">Projects</h4>
+                <label class="flex items-center space-x-2 text-xs text-gray-600 dark:text-gray-400">
+                    <input
+                        type="checkbox"
+                        class="rounded border-gray-300 text-blue-600"
+                        prop:checked=move || show_archived_projects.get()
+                        on:change=move |ev| show_archived_projects.set(event_target_checked(&ev))
+                    />
+                    <span>"Show archived"</span>
+                </label>
+            </div>
+
+            // Project form (create or edit)
+            <div class="space-y-2 mb-4">
+                <div class="flex space-x-2">
+                    <input
+                        type="text"
+                        placeholder="Project name"
+                        class="flex-grow px-3 py-2 border rounded bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 placeholder-gray-500 dark:placeholder-gray-400"
+                        prop:value=move || project_name.get()
+                        on:input=move |ev| project_name.set(event_target_value(&ev))
+                    />
+                    <select
+                        class="px-3 py-2 border rounded bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        on:change=move |ev| project_color.set(event_target_value(&ev))
+                    >
+                        {TASK_COLORS.iter().enumerate().map(|(i, color)| {
+                            let color = color.to_string();
+                            let color_selected = color.clone();
+                            view! {
+                                <option value=color selected=move || project_color.get() == color_selected>
+                                    {format!("Color {}", i + 1)}
+                                </option>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+                <input
+                    type="text"
+                    placeholder="Description (optional)"
+                    class="w-full px-3 py-2 border rounded bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 placeholder-gray-500 dark:placeholder-gray-400"
+                    prop:value=move || project_description.get()
+                    on:input=move |ev| project_description.set(event_target_value(&ev))
+                />
+                <input
+                    type="number"
+                    min="0"
+                    step="0.01"
+                    placeholder="Hourly rate (optional)"
+                    class="w-full px-3 py-2 border rounded bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 placeholder-gray-500 dark:placeholder-gray-400"
+                    prop:value=move || project_rate.get()
+                    on:input=move |ev| project_rate.set(event_target_value(&ev))
+                />
+                <div class="flex space-x-2">
+                    <button
+                        class="flex-grow px-4 py-2 bg-green-500 hover:bg-green-600 text-white rounded transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                        disabled=move || project_name.get().trim().is_empty()
+                        on:click={
+                            let task_controller = task_controller.clone();
+                            move |_| {
+                                let name = project_name.get().trim().to_string();
+                                if name.is_empty() {
+                                    return;
+                                }
+                                let description = if project_description.get().trim().is_empty() {
+                                    None
+                                } else {
+                                    Some(project_description.get().trim().to_string())
+                                };
+                                let hourly_rate = project_rate.get().trim().parse::<f64>().ok().filter(|r| *r >= 0.0);
+
+                                match editing_project.get() {
+                                    Some(mut project) => {
+                                        project.name = name;
+                                        project.description = description;
+                                        project.color = project_color.get();
+                                        project.hourly_rate = hourly_rate;
+                                        task_controller.update_project(project);
+                                    }
+                                    None => task_controller.create_project(NewProject {
+                                        name,
+                                        description,
+                                        color: project_color.get(),
+                                        hourly_rate,
+                                    }),
+                                }
+
+                                reset_form();
+                            }
+                        }
+                    >
+                        {move || if editing_project.get().is_some() { "Save Project" } else { "Create Project" }}
+                    </button>
+                    {move || {
+                        if editing_project.get().is_some() {
+                            view! {
+                                <button
+                                    class="px-4 py-2 bg-gray-500 hover:bg-gray-600 text-white rounded transition-colors"
+                                    on:click=move |_| reset_form()
+                                >
+                                    "Cancel"
+                                </button>
+                            }.into_any()
+                        } else {
+                            view! { <div></div> }.into_any()
+                        }
+                    }}
+                </div>
+            </div>
+
+            // Project list with rollups
+            {
+                let task_controller = task_controller.clone();
+                move || {
+                    let show_archived = show_archived_projects.get();
+                    let project_stats = task_controller.project_stats.get();
+                    let projects: Vec<Project> = task_controller
+                        .projects
+                        .get()
+                        .into_iter()
+                        .filter(|p| show_archived || !p.archived)
+                        .collect();
+
+                    if projects.is_empty() {
+                        return view! {
+                            <div class="text-center py-2 text-sm text-gray-500 dark:text-gray-400">
+                                "No projects yet."
+                            </div>
+                        }.into_any();
+                    }
+
+                    view! {
+                        <div class="space-y-2">
+                            {projects.into_iter().map(|project| {
+                                let stats = project_stats.iter().find(|ps| ps.project.id == project.id).cloned();
+                                let project_id = project.id.clone();
+                                let project_edit = project.clone();
+                                let controller_archive = task_controller.clone();
+                                let controller_delete = task_controller.clone();
+
+                                view! {
+                                    <div class=format!("flex items-center justify-between p-2 rounded border bg-white dark:bg-gray-800 border-gray-200 dark:border-gray-600 {}",
+                                        if project.archived { "opacity-60" } else { "" })>
+                                        <div class="flex items-center space-x-3 min-w-0">
+                                            <div
+                                                class="w-3 h-3 rounded-full flex-shrink-0"
+                                                style:background-color=project.color.clone()
+                                            ></div>
+                                            <div class="min-w-0">
+                                                <div class="text-sm font-medium text-gray-800 dark:text-white truncate">
+                                                    {project.name.clone()}
+                                                    {if project.archived { " (archived)" } else { "" }}
+                                                </div>
+                                                {project.description.clone().map(|desc| {
+                                                    view! {
+                                                        <div class="text-xs text-gray-500 dark:text-gray-400 truncate">{desc}</div>
+                                                    }
+                                                })}
+                                                {stats.map(|stats| {
+                                                    view! {
+                                                        <div class="text-xs text-gray-500 dark:text-gray-400">
+                                                            {stats.completed_tasks} "/" {stats.tasks} " tasks · "
+                                                            {format_duration_hours_minutes(stats.total_focus_time)}
+                                                        </div>
+                                                    }
+                                                })}
+                                            </div>
+                                        </div>
+
+                                        <div class="flex space-x-2 flex-shrink-0">
+                                            <button
+                                                class="text-xs px-2 py-1 bg-blue-500 hover:bg-blue-600 text-white rounded transition-colors"
+                                                on:click=move |_| {
+                                                    project_name.set(project_edit.name.clone());
+                                                    project_description.set(project_edit.description.clone().unwrap_or_default());
+                                                    project_color.set(project_edit.color.clone());
+                                                    project_rate.set(project_edit.hourly_rate.map(|r| r.to_string()).unwrap_or_default());
+                                                    editing_project.set(Some(project_edit.clone()));
+                                                }
+                                            >
+                                                "Edit"
+                                            </button>
+                                            <button
+                                                class="text-xs px-2 py-1 bg-gray-500 hover:bg-gray-600 text-white rounded transition-colors"
+                                                on:click={
+                                                    let project_id = project_id.clone();
+                                                    let archived = project.archived;
+                                                    move |_| controller_archive.set_project_archived(project_id.clone(), !archived)
+                                                }
+                                            >
+                                                {if project.archived { "Restore" } else { "Archive" }}
+                                            </button>
+                                            <button
+                                                class="text-xs px-2 py-1 bg-red-500 hover:bg-red-600 text-white rounded transition-colors"
+                                                on:click=move |_| {
+                                                    if web_sys::window()
+                                                        .and_then(|w| w.confirm_with_message("Delete this project? Its tasks are kept without a project.").ok())
+                                                        .unwrap_or(false)
+                                                    {
+                                                        controller_delete.delete_project(project_id.clone());
+                                                    }
+                                                }
+                                            >
+                                                "Delete"
+                                            </button>
+                                        </div>
+                                    </div>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </div>
+                    }.into_any()
+                }
+            }
+        </div>
+    }
+}
+
+// Select options for filtering by project; archived projects stay listed so their history can be found
+#[component]
+pub fn ProjectFilterOptions(projects: Vec<Project>, selected: String) -> impl IntoView {
+    let selected_all = selected.clone();
+    let selected_none = selected.clone();
+
+    view! {
+        <option value="all" selected=selected_all == "all">"All projects"</option>
+        <option value="none" selected=selected_none == "none">"No project"</option>
+        {projects.into_iter().map(|project| {
+            let is_selected = project.id == selected;
+            view! {
+                <option value=project.id.clone() selected=is_selected>
+                    {if project.archived { format!("{} (archived)", project.name) } else { project.name.clone() }}
+                </option>
+            }
+        }).collect::<Vec<_>>()}
+    }
+}
@@ -0,0 +1,242 @@
+use crate::console_log;
+use crate::task::TaskController;
+use crate::timer::TimerController;
+use crate::types::{get_daily_focus_from_db, get_sessions_for_day, Session, Task, HEATMAP_WEEKS};
+use crate::utils::{
+    add_days_to_date_key, day_start_hour, format_duration_hours_minutes, get_today_date_string, local_day_bounds_ms,
+    week_start_of_date_key,
+};
+use leptos::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+
+const WORK_WITHOUT_TASK_COLOR: &str = "#EF4444";
+
+// Tailwind shade for a day's focus time relative to the busiest day in the heatmap
+fn heatmap_cell_class(focus: u32, max_focus: u32) -> &'static str {
+    if focus == 0 || max_focus == 0 {
+        return "bg-gray-100 dark:bg-gray-700";
+    }
+    match focus * 4 / max_focus {
+        0 => "bg-green-200 dark:bg-green-900",
+        1 => "bg-green-300 dark:bg-green-700",
+        2 => "bg-green-500 dark:bg-green-600",
+        _ => "bg-green-700 dark:bg-green-400",
+    }
+}
+
+fn format_clock(ms: f64) -> String {
+    let date = js_sys::Date::new(&ms.into());
+    format!("{:02}:{:02}", date.get_hours(), date.get_minutes())
+}
+
+// Year-long heatmap of daily focus time; clicking a day opens its timeline below.
+// Reloads whenever session stats are refreshed or the undo history rewrites data.
+#[component]
+pub fn SessionCalendar(task_controller: TaskController, timer_controller: TimerController) -> impl IntoView {
+    let daily_focus = RwSignal::new(Vec::<(String, u32)>::new());
+    let selected_day = RwSignal::new(get_today_date_string());
+    let history = task_controller.history;
+    let session_stats = timer_controller.session_stats;
+
+    Effect::new(move |_| {
+        session_stats.track();
+        history.revision.track();
+
+        let today = get_today_date_string();
+        let Some(first_day) = week_start_of_date_key(&today)
+            .and_then(|monday| add_days_to_date_key(&monday, -7 * (HEATMAP_WEEKS - 1)))
+        else {
+            return;
+        };
+        spawn_local(async move {
+            match get_daily_focus_from_db(&first_day, &today).await {
+                Ok(days) => daily_focus.set(days),
+                Err(e) => console_log!("Error loading daily focus: {}", e),
+            }
+        });
+    });
+
+    view! {
+        <div class="mb-6 p-4 bg-white dark:bg-gray-800 rounded-lg border border-gray-200 dark:border-gray-700">
+            <div class="flex justify-between items-center mb-3">
+                <h3 class="text-lg font-semibold text-gray-800 dark:text-white">"Focus Calendar"</h3>
+                <div class="flex items-center space-x-1 text-xs text-gray-500 dark:text-gray-400">
+                    <span>"Less"</span>
+                    {[0, 1, 2, 3, 4].into_iter().map(|level| view! {
+                        <span class=format!("w-3 h-3 rounded-sm {}", heatmap_cell_class(level, 4))></span>
+                    }).collect_view()}
+                    <span>"More"</span>
+                </div>
+            </div>
+
+            <div class="overflow-x-auto">
+                {move || {
+                    let days = daily_focus.get();
+                    let max_focus = days.iter().map(|(_, focus)| *focus).max().unwrap_or(0);
+                    view! {
+                        <div class="flex space-x-1">
+                            <div class="flex flex-col space-y-1 text-[10px] text-gray-400 dark:text-gray-500 pr-1">
+                                {["Mon", "", "Wed", "", "Fri", "", ""].into_iter().map(|label| view! {
+                                    <span class="h-3 leading-3">{label}</span>
+                                }).collect_view()}
+                            </div>
+                            {days.chunks(7).map(|week| {
+                                let week = week.to_vec();
+                                view! {
+                                    <div class="flex flex-col space-y-1">
+                                        {week.into_iter().map(|(day, focus)| {
+                                            let title = format!("{}: {}", day, format_duration_hours_minutes(focus));
+                                            let is_selected = {
+                                                let day = day.clone();
+                                                move || selected_day.get() == day
+                                            };
+                                            view! {
+                                                <button
+                                                    class=move || format!(
+                                                        "w-3 h-3 rounded-sm {} {}",
+                                                        heatmap_cell_class(focus, max_focus),
+                                                        if is_selected() { "ring-2 ring-blue-500" } else { "" }
+                                                    )
+                                                    title=title
+                                                    on:click=move |_| selected_day.set(day.clone())
+                                                ></button>
+                                            }
+                                        }).collect_view()}
+                                    </div>
+                                }
+                            }).collect_view()}
+                        </div>
+                    }
+                }}
+            </div>
+
+            <DayTimeline
+                task_controller=task_controller
+                timer_controller=timer_controller
+                date_key=selected_day
+            />
+        </div>
+    }
+}
+
+// Work and break blocks of one day on a bar from the day-start hour to the next, work coloured by task
+#[component]
+fn DayTimeline(
+    task_controller: TaskController,
+    timer_controller: TimerController,
+    date_key: RwSignal<String>,
+) -> impl IntoView {
+    let sessions = RwSignal::new(Vec::<Session>::new());
+    let history = task_controller.history;
+    let session_stats = timer_controller.session_stats;
+
+    Effect::new(move |_| {
+        let day = date_key.get();
+        session_stats.track();
+        history.revision.track();
+        spawn_local(async move {
+            match get_sessions_for_day(&day).await {
+                Ok(day_sessions) => sessions.set(day_sessions),
+                Err(e) => console_log!("Error loading sessions for {}: {}", day, e),
+            }
+        });
+    });
+
+    let shift_day = move |days: i64| {
+        if let Some(day) = add_days_to_date_key(&date_key.get_untracked(), days) {
+            date_key.set(day);
+        }
+    };
+
+    view! {
+        <div class="mt-4 pt-4 border-t border-gray-200 dark:border-gray-700">
+            <div class="flex justify-between items-center mb-2">
+                <button
+                    class="px-2 py-1 text-xs text-gray-600 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700 rounded"
+                    on:click=move |_| shift_day(-1)
+                >
+                    "‹ Previous"
+                </button>
+                <span class="text-sm font-medium text-gray-700 dark:text-gray-300">{move || date_key.get()}</span>
+                <button
+                    class="px-2 py-1 text-xs text-gray-600 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700 rounded disabled:opacity-40"
+                    disabled=move || date_key.get() >= get_today_date_string()
+                    on:click=move |_| shift_day(1)
+                >
+                    "Next ›"
+                </button>
+            </div>
+
+            {move || {
+                let Some((day_start, day_end)) = local_day_bounds_ms(&date_key.get()) else {
+                    return view! { <div></div> }.into_any();
+                };
+                let day_length = day_end - day_start;
+                let tasks: Vec<Task> = task_controller.tasks.get();
+                let day_sessions = sessions.get();
+                let focus: u32 = day_sessions
+                    .iter()
+                    .filter(|s| s.session_type == "Work" && s.completed)
+                    .map(|s| s.actual_duration)
+                    .sum();
+
+                view! {
+                    <div>
+                        <div class="relative h-10 bg-gray-100 dark:bg-gray-700 rounded overflow-hidden">
+                            {day_sessions.into_iter().filter_map(|session| {
+                                let start = js_sys::Date::new(&session.start_time.as_str().into()).get_time();
+                                let end = js_sys::Date::new(&session.end_time.as_str().into()).get_time();
+                                if start.is_nan() || end.is_nan() {
+                                    return None;
+                                }
+                                let start = start.max(day_start);
+                                let end = end.min(day_end);
+                                let task = session
+                                    .task_id
+                                    .as_ref()
+                                    .and_then(|id| tasks.iter().find(|t| &t.id == id));
+
+                                let (class, color) = match session.session_type.as_str() {
+                                    "Work" => (
+                                        "top-0 bottom-0",
+                                        task.map_or(WORK_WITHOUT_TASK_COLOR.to_string(), |t| t.color.clone()),
+                                    ),
+                                    "LongBreak" => ("top-1/2 bottom-0", "#3B82F6".to_string()),
+                                    _ => ("top-1/2 bottom-0", "#22C55E".to_string()),
+                                };
+                                let title = format!(
+                                    "{}{} · {}–{}",
+                                    session.session_type,
+                                    task.map_or(String::new(), |t| format!(" · {}", t.name)),
+                                    format_clock(start),
+                                    format_clock(end)
+                                );
+                                let style = format!(
+                                    "left: {:.3}%; width: {:.3}%; background-color: {};",
+                                    (start - day_start) / day_length * 100.0,
+                                    ((end - start) / day_length * 100.0).max(0.2),
+                                    color
+                                );
+                                Some(view! {
+                                    <div
+                                        class=format!("absolute opacity-90 hover:opacity-100 {}", class)
+                                        style=style
+                                        title=title
+                                    ></div>
+                                })
+                            }).collect_view()}
+                        </div>
+                        <div class="flex justify-between text-[10px] text-gray-400 dark:text-gray-500 mt-1">
+                            {(0..=4).map(|i| view! {
+                                <span>{format!("{:02}:00", (day_start_hour() + i * 6) % 24)}</span>
+                            }).collect_view()}
+                        </div>
+                        <p class="text-xs text-gray-600 dark:text-gray-400 mt-1">
+                            "Focus: " {format_duration_hours_minutes(focus)}
+                        </p>
+                    </div>
+                }.into_any()
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,325 @@
+use crate::console_log;
+use crate::history::UndoHistory;
+use crate::task::TaskController;
+use crate::types::{
+    get_adjacent_sessions, merge_sessions_in_db, split_session_in_db, update_session_in_db, Session,
+    SessionEdit,
+};
+use crate::utils::{datetime_local_to_iso, format_iso_date, iso_to_datetime_local};
+use leptos::prelude::*;
+use std::future::Future;
+use wasm_bindgen_futures::spawn_local;
+
+fn run_session_change(
+    history: UndoHistory,
+    label: &'static str,
+    operation: impl Future<Output = Result<(), String>> + 'static,
+    saving: RwSignal<bool>,
+    error: RwSignal<Option<String>>,
+    on_changed: Callback<()>,
+) {
+    saving.set(true);
+    error.set(None);
+    spawn_local(async move {
+        match history.record(label, operation).await {
+            Ok(()) => on_changed.run(()),
+            Err(e) => {
+                console_log!("Error changing session: {}", e);
+                error.set(Some(e));
+            }
+        }
+        saving.set(false);
+    });
+}
+
+pub const INPUT_CLASS: &str = "w-full px-2 py-1 border rounded text-sm bg-white dark:bg-gray-700 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300";
+
+// Task and subtask pickers for a work session; changing the task clears the subtask
+#[component]
+pub fn SessionTaskFields(
+    task_controller: TaskController,
+    task_id: RwSignal<Option<String>>,
+    subtask_id: RwSignal<Option<String>>,
+) -> impl IntoView {
+    let task_controller_subtasks = task_controller.clone();
+
+    view! {
+        <div class="grid grid-cols-1 md:grid-cols-2 gap-3">
+            <div>
+                <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Task"</label>
+                <select
+                    class=INPUT_CLASS
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        task_id.set(if value.is_empty() { None } else { Some(value) });
+                        subtask_id.set(None);
+                    }
+                >
+                    <option value="" selected=move || task_id.get().is_none()>"No task"</option>
+                    {move || {
+                        task_controller.tasks.get().into_iter().map(|task| {
+                            let id = task.id.clone();
+                            view! {
+                                <option
+                                    value=task.id.clone()
+                                    selected=move || task_id.get().as_deref() == Some(id.as_str())
+                                >
+                                    {task.name}
+                                </option>
+                            }
+                        }).collect_view()
+                    }}
+                </select>
+            </div>
+            <div>
+                <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Subtask"</label>
+                <select
+                    class=INPUT_CLASS
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        subtask_id.set(if value.is_empty() { None } else { Some(value) });
+                    }
+                >
+                    <option value="" selected=move || subtask_id.get().is_none()>"Whole task"</option>
+                    {move || {
+                        let selected_task = task_id.get();
+                        task_controller_subtasks
+                            .subtasks
+                            .get()
+                            .into_iter()
+                            .filter(|st| selected_task.as_deref() == Some(st.task_id.as_str()))
+                            .map(|subtask| {
+                                let id = subtask.id.clone();
+                                view! {
+                                    <option
+                                        value=subtask.id.clone()
+                                        selected=move || subtask_id.get().as_deref() == Some(id.as_str())
+                                    >
+                                        {subtask.name}
+                                    </option>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                </select>
+            </div>
+        </div>
+    }
+}
+
+// Inline editor for a recorded session: task link, times and duration, plus split and merge.
+// Every change goes through the undo history; `on_changed` runs after a successful change.
+#[component]
+pub fn SessionEditor(
+    session: Session,
+    task_controller: TaskController,
+    on_changed: Callback<()>,
+    on_close: Callback<()>,
+) -> impl IntoView {
+    let history = task_controller.history;
+    let session_id = session.id.clone();
+
+    let original_start = iso_to_datetime_local(&session.start_time);
+    let original_end = iso_to_datetime_local(&session.end_time);
+    let original_minutes = session.actual_duration.div_ceil(60).to_string();
+
+    let task_id = RwSignal::new(session.task_id.clone());
+    let subtask_id = RwSignal::new(session.subtask_id.clone());
+    let start = RwSignal::new(original_start.clone());
+    let end = RwSignal::new(original_end.clone());
+    let duration_minutes = RwSignal::new(original_minutes.clone());
+    let split_at = RwSignal::new(String::new());
+    let neighbours = RwSignal::new((None::<Session>, None::<Session>));
+    let error = RwSignal::new(None::<String>);
+    let saving = RwSignal::new(false);
+
+    {
+        let session_id = session_id.clone();
+        spawn_local(async move {
+            match get_adjacent_sessions(&session_id).await {
+                Ok(adjacent) => neighbours.set(adjacent),
+                Err(e) => console_log!("Error loading adjacent sessions: {}", e),
+            }
+        });
+    }
+
+    // Unchanged fields keep their exact original values, since the inputs drop seconds
+    let save = {
+        let session = session.clone();
+        move |_| {
+            let start_time = if start.get_untracked() == original_start {
+                Some(session.start_time.clone())
+            } else {
+                datetime_local_to_iso(&start.get_untracked())
+            };
+            let end_time = if end.get_untracked() == original_end {
+                Some(session.end_time.clone())
+            } else {
+                datetime_local_to_iso(&end.get_untracked())
+            };
+            let (Some(start_time), Some(end_time)) = (start_time, end_time) else {
+                error.set(Some("Enter a valid start and end time".to_string()));
+                return;
+            };
+            let actual_duration = if duration_minutes.get_untracked() == original_minutes {
+                session.actual_duration
+            } else {
+                match duration_minutes.get_untracked().trim().parse::<u32>() {
+                    Ok(minutes) => minutes * 60,
+                    Err(_) => {
+                        error.set(Some("Duration must be a whole number of minutes".to_string()));
+                        return;
+                    }
+                }
+            };
+
+            let edit = SessionEdit {
+                task_id: task_id.get_untracked(),
+                subtask_id: subtask_id.get_untracked(),
+                start_time,
+                end_time,
+                actual_duration,
+            };
+            let operation = update_session_in_db(session.id.clone(), edit);
+            run_session_change(history, "Edit session", operation, saving, error, on_changed);
+        }
+    };
+
+    let split = {
+        let session_id = session_id.clone();
+        move |_| {
+            let Some(split_time) = datetime_local_to_iso(&split_at.get_untracked()) else {
+                error.set(Some("Choose a time to split at".to_string()));
+                return;
+            };
+            let operation = split_session_in_db(session_id.clone(), split_time);
+            let operation = async move { operation.await.map(|_| ()) };
+            run_session_change(history, "Split session", operation, saving, error, on_changed);
+        }
+    };
+
+    let merge_with = {
+        let session_id = session_id.clone();
+        move |other: Session| {
+            let operation = merge_sessions_in_db(session_id.clone(), other.id);
+            run_session_change(history, "Merge sessions", operation, saving, error, on_changed);
+        }
+    };
+
+    let is_work_session = session.session_type == "Work";
+    let session_type = session.session_type.clone();
+
+    view! {
+        <div class="mt-3 p-3 bg-white dark:bg-gray-700 rounded border border-gray-200 dark:border-gray-600 space-y-3">
+            {if is_work_session {
+                view! {
+                    <SessionTaskFields task_controller=task_controller.clone() task_id=task_id subtask_id=subtask_id />
+                }.into_any()
+            } else {
+                view! { <div></div> }.into_any()
+            }}
+
+            <div class="grid grid-cols-1 md:grid-cols-3 gap-3">
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Start"</label>
+                    <input
+                        type="datetime-local"
+                        class=INPUT_CLASS
+                        prop:value=move || start.get()
+                        on:input=move |ev| start.set(event_target_value(&ev))
+                    />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"End"</label>
+                    <input
+                        type="datetime-local"
+                        class=INPUT_CLASS
+                        prop:value=move || end.get()
+                        on:input=move |ev| end.set(event_target_value(&ev))
+                    />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Duration (minutes)"</label>
+                    <input
+                        type="number"
+                        min="0"
+                        class=INPUT_CLASS
+                        prop:value=move || duration_minutes.get()
+                        on:input=move |ev| duration_minutes.set(event_target_value(&ev))
+                    />
+                </div>
+            </div>
+
+            <div class="flex flex-wrap items-end gap-2 pt-2 border-t border-gray-200 dark:border-gray-600">
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Split at"</label>
+                    <input
+                        type="datetime-local"
+                        class=INPUT_CLASS
+                        prop:value=move || split_at.get()
+                        on:input=move |ev| split_at.set(event_target_value(&ev))
+                    />
+                </div>
+                <button
+                    class="px-3 py-1 text-xs bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-700 dark:text-gray-200 rounded transition-colors disabled:opacity-50"
+                    disabled=move || saving.get() || split_at.get().is_empty()
+                    on:click=split
+                >
+                    "✂️ Split"
+                </button>
+
+                {move || {
+                    let (previous, next) = neighbours.get();
+                    [("⤒ Merge with previous", previous), ("⤓ Merge with next", next)]
+                        .into_iter()
+                        .filter_map(|(label, neighbour)| {
+                            let neighbour = neighbour.filter(|n| n.session_type == session_type)?;
+                            let merge_with = merge_with.clone();
+                            let title = format!(
+                                "{} session from {}",
+                                neighbour.session_type,
+                                format_iso_date(&neighbour.start_time)
+                            );
+                            Some(view! {
+                                <button
+                                    class="px-3 py-1 text-xs bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-700 dark:text-gray-200 rounded transition-colors disabled:opacity-50"
+                                    title=title
+                                    disabled=move || saving.get()
+                                    on:click=move |_| merge_with(neighbour.clone())
+                                >
+                                    {label}
+                                </button>
+                            })
+                        })
+                        .collect_view()
+                }}
+            </div>
+
+            {move || {
+                if let Some(err) = error.get() {
+                    view! {
+                        <p class="text-red-500 text-xs">{err}</p>
+                    }.into_any()
+                } else {
+                    view! { <div></div> }.into_any()
+                }
+            }}
+
+            <div class="flex justify-end space-x-2">
+                <button
+                    class="px-3 py-1 text-xs bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-700 dark:text-gray-200 rounded transition-colors"
+                    on:click=move |_| on_close.run(())
+                >
+                    "Cancel"
+                </button>
+                <button
+                    class="px-3 py-1 text-xs bg-blue-600 hover:bg-blue-700 text-white rounded transition-colors disabled:opacity-50"
+                    disabled=move || saving.get()
+                    on:click=save
+                >
+                    "Save"
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -1,10 +1,17 @@
 use leptos::prelude::*;
+use std::collections::HashSet;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::spawn_local;
 
+use crate::components::session_editor::{SessionTaskFields, INPUT_CLASS};
+use crate::components::{ManualSessionForm, ProjectFilterOptions, SessionEditor};
 use crate::console_log;
+use crate::task::TaskController;
 use crate::timer::TimerController;
-use crate::types::{delete_session_from_db, get_sessions_from_db, get_task_path_by_ids, Session};
+use crate::types::{
+    bulk_update_sessions_in_db, delete_session_from_db, get_all_projects, get_all_tasks,
+    get_sessions_from_db, get_task_path_by_ids, BulkSessionAction, Project, ProjectFilter, Session,
+};
 use crate::utils::{format_duration_hours_minutes, format_iso_date};
 
 #[wasm_bindgen]
@@ -13,18 +20,164 @@ extern "C" {
     async fn invoke(cmd: &str, args: JsValue) -> JsValue;
 }
 
+const PAGE_SIZE: usize = 50;
+
 #[derive(Clone, Debug)]
 struct SessionWithTask {
     session: Session,
     task_info: Option<String>,
 }
 
+// Reassign, tag or delete every selected session in one undoable step
 #[component]
-pub fn SessionHistory(controller: TimerController) -> impl IntoView {
+fn BulkSessionActions(
+    task_controller: TaskController,
+    selected: RwSignal<HashSet<String>>,
+    on_changed: Callback<()>,
+) -> impl IntoView {
+    let history = task_controller.history;
+    let task_id = RwSignal::new(None::<String>);
+    let subtask_id = RwSignal::new(None::<String>);
+    let tag = RwSignal::new(String::new());
+    let busy = RwSignal::new(false);
+    let message = RwSignal::new(None::<String>);
+
+    let run = move |action: BulkSessionAction| {
+        let ids: Vec<String> = selected.get_untracked().into_iter().collect();
+        if ids.is_empty() {
+            return;
+        }
+        let label = action.label(ids.len());
+        busy.set(true);
+        spawn_local(async move {
+            match history.record(label, bulk_update_sessions_in_db(&ids, action)).await {
+                Ok(outcome) => {
+                    let mut text = format!("Updated {} of {} selected sessions", outcome.changed, ids.len());
+                    if outcome.skipped_breaks > 0 {
+                        text.push_str(&format!(
+                            "; skipped {} break session(s), which can't be linked to a task",
+                            outcome.skipped_breaks
+                        ));
+                    }
+                    message.set(Some(text));
+                    on_changed.run(());
+                }
+                Err(e) => {
+                    console_log!("Error updating sessions: {}", e);
+                    message.set(Some(e));
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    // "#client-a " -> "client-a"
+    let tag_value = move || {
+        let value = tag.get_untracked();
+        let value = value.trim().trim_start_matches(['#', '@']).trim();
+        (!value.is_empty()).then(|| value.to_string())
+    };
+
+    view! {
+        <div class="mb-4 p-4 border rounded-lg bg-gray-50 dark:bg-gray-700 border-gray-200 dark:border-gray-600 space-y-3">
+            <div class="text-sm font-medium text-gray-800 dark:text-white">
+                {move || format!("{} selected", selected.get().len())}
+            </div>
+
+            <div class="space-y-2">
+                <SessionTaskFields task_controller=task_controller.clone() task_id=task_id subtask_id=subtask_id />
+                <button
+                    class="px-3 py-1 text-sm bg-blue-600 hover:bg-blue-700 text-white rounded transition-colors disabled:opacity-50"
+                    disabled=move || busy.get()
+                    title="Only work sessions are reassigned"
+                    on:click=move |_| run(BulkSessionAction::Reassign {
+                        task_id: task_id.get_untracked(),
+                        subtask_id: subtask_id.get_untracked(),
+                    })
+                >
+                    "🎯 Reassign"
+                </button>
+            </div>
+
+            <div class="flex flex-wrap items-center gap-2">
+                <input
+                    type="text"
+                    placeholder="Tag"
+                    class=format!("{} w-40", INPUT_CLASS)
+                    prop:value=move || tag.get()
+                    on:input=move |ev| tag.set(event_target_value(&ev))
+                />
+                <button
+                    class="px-3 py-1 text-sm bg-teal-600 hover:bg-teal-700 text-white rounded transition-colors disabled:opacity-50"
+                    disabled=move || busy.get() || tag.get().trim().is_empty()
+                    on:click=move |_| {
+                        if let Some(value) = tag_value() {
+                            run(BulkSessionAction::AddTag(value));
+                        }
+                    }
+                >
+                    "🏷️ Add tag"
+                </button>
+                <button
+                    class="px-3 py-1 text-sm bg-gray-500 hover:bg-gray-600 text-white rounded transition-colors disabled:opacity-50"
+                    disabled=move || busy.get() || tag.get().trim().is_empty()
+                    on:click=move |_| {
+                        if let Some(value) = tag_value() {
+                            run(BulkSessionAction::RemoveTag(value));
+                        }
+                    }
+                >
+                    "Remove tag"
+                </button>
+                <button
+                    class="ml-auto px-3 py-1 text-sm bg-red-500 hover:bg-red-600 text-white rounded transition-colors disabled:opacity-50"
+                    disabled=move || busy.get()
+                    on:click=move |_| {
+                        let count = selected.get_untracked().len();
+                        if web_sys::window()
+                            .and_then(|w| w.confirm_with_message(&format!("Delete {} sessions?", count)).ok())
+                            .unwrap_or(false)
+                        {
+                            run(BulkSessionAction::Delete);
+                        }
+                    }
+                >
+                    "🗑️ Delete"
+                </button>
+            </div>
+
+            {move || {
+                if let Some(text) = message.get() {
+                    view! { <p class="text-xs text-gray-600 dark:text-gray-400">{text}</p> }.into_any()
+                } else {
+                    view! { <div></div> }.into_any()
+                }
+            }}
+        </div>
+    }
+}
+
+#[component]
+pub fn SessionHistory(controller: TimerController, task_controller: TaskController) -> impl IntoView {
+    let history = task_controller.history;
+    let editing = RwSignal::new(None::<String>); // id of the session being edited
+    let logging = RwSignal::new(false); // whether the manual entry form is open
     let sessions_with_tasks = RwSignal::new(Vec::<SessionWithTask>::new());
     let loading = RwSignal::new(false);
     let error = RwSignal::new(None::<String>);
     let filter_type = RwSignal::new(None::<String>);
+    let filter_project = RwSignal::new(ProjectFilter::All);
+    let filter_task = RwSignal::new(None::<String>);
+    let projects = RwSignal::new(Vec::<Project>::new());
+    let matching_ids = RwSignal::new(Vec::<String>::new()); // every session the filters match, beyond the page
+    let selected = RwSignal::new(HashSet::<String>::new());
+
+    spawn_local(async move {
+        match get_all_projects().await {
+            Ok(loaded) => projects.set(loaded),
+            Err(e) => console_log!("Error loading projects: {}", e),
+        }
+    });
 
     // Function to open video file
     let open_video_file = move |video_path: String| {
@@ -88,10 +241,36 @@ pub fn SessionHistory(controller: TimerController) -> impl IntoView {
                 loading.set(true);
                 error.set(None);
 
-                let query_limit = Some(50);
                 let query_session_type = filter_type.get();
+                let project_filter = filter_project.get();
+                let task_filter = filter_task.get();
+
+                // Filtering happens before the page limit so the page stays full, and every
+                // match is remembered so bulk actions can select beyond the page
+                let loaded = match (get_sessions_from_db(None, query_session_type).await, get_all_tasks().await) {
+                    (Ok(mut sessions), Ok(tasks)) => {
+                        sessions.retain(|session| {
+                            if task_filter.is_some() && session.task_id != task_filter {
+                                return false;
+                            }
+                            let project_id = session
+                                .task_id
+                                .as_ref()
+                                .and_then(|task_id| tasks.iter().find(|t| &t.id == task_id))
+                                .and_then(|t| t.project_id.as_deref());
+                            project_filter.matches(project_id)
+                        });
+                        let ids: Vec<String> = sessions.iter().map(|s| s.id.clone()).collect();
+                        let still_matching: HashSet<&String> = ids.iter().collect();
+                        selected.update(|selection| selection.retain(|id| still_matching.contains(id)));
+                        matching_ids.set(ids);
+                        sessions.truncate(PAGE_SIZE);
+                        Ok(sessions)
+                    }
+                    (Err(e), _) | (_, Err(e)) => Err(e),
+                };
 
-                match get_sessions_from_db(query_limit, query_session_type).await {
+                match loaded {
                     Ok(loaded_sessions) => {
                         console_log!("Loaded {} sessions", loaded_sessions.len());
 
@@ -157,6 +336,9 @@ pub fn SessionHistory(controller: TimerController) -> impl IntoView {
         let load_sessions = load_sessions.clone();
         move |_| {
             let _ = filter_type.get(); // Track changes
+            let _ = filter_project.get();
+            let _ = filter_task.get();
+            let _ = history.revision.get(); // Undo/redo may restore or remove sessions
             load_sessions();
         }
     });
@@ -168,7 +350,8 @@ pub fn SessionHistory(controller: TimerController) -> impl IntoView {
             let sessions_with_tasks = sessions_with_tasks.clone();
             let controller = controller.clone();
             spawn_local(async move {
-                match delete_session_from_db(session_id.clone()).await {
+                let operation = delete_session_from_db(session_id.clone());
+                match history.record("Delete session", operation).await {
                     Ok(_) => {
                         // Remove from local list
                         let current_sessions = sessions_with_tasks.get();
@@ -190,30 +373,161 @@ pub fn SessionHistory(controller: TimerController) -> impl IntoView {
         }
     };
 
+    // Edits can move focus time between tasks, so task totals reload along with the list
+    let on_session_changed = {
+        let controller = controller.clone();
+        let task_controller = task_controller.clone();
+        Callback::new(move |_| {
+            editing.set(None);
+            logging.set(false);
+            load_sessions();
+            controller.load_session_stats();
+            task_controller.load_tasks();
+            task_controller.load_task_stats();
+        })
+    };
+
     view! {
         <div class="mt-6">
             <div class="flex justify-between items-center mb-4">
                 <h3 class="text-lg font-semibold text-gray-800 dark:text-white">Session History</h3>
 
-                // Filter dropdown
-                <select
-                    class="px-3 py-1 border rounded text-sm bg-white dark:bg-gray-700 dark:border-gray-600 text-gray-700 dark:text-gray-300"
-                    on:change=move |ev| {
-                        let value = event_target_value(&ev);
-                        if value.is_empty() || value == "all" {
-                            filter_type.set(None);
+                // Filter dropdowns
+                <div class="flex space-x-2">
+                    <button
+                        class="px-3 py-1 text-sm bg-blue-600 hover:bg-blue-700 text-white rounded transition-colors"
+                        on:click=move |_| logging.update(|open| *open = !*open)
+                    >
+                        {move || if logging.get() { "Cancel" } else { "➕ Log past session" }}
+                    </button>
+                    {move || {
+                        if projects.get().is_empty() {
+                            view! { <div></div> }.into_any()
                         } else {
-                            filter_type.set(Some(value));
+                            view! {
+                                <select
+                                    class="px-3 py-1 border rounded text-sm bg-white dark:bg-gray-700 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                                    on:change=move |ev| {
+                                        selected.set(HashSet::new());
+                                        filter_project.set(ProjectFilter::from_value(&event_target_value(&ev)))
+                                    }
+                                >
+                                    <ProjectFilterOptions
+                                        projects=projects.get()
+                                        selected=filter_project.get_untracked().to_value()
+                                    />
+                                </select>
+                            }.into_any()
                         }
-                    }
-                >
-                    <option value="all">"All Sessions"</option>
-                    <option value="Work">"Work Sessions"</option>
-                    <option value="ShortBreak">"Short Breaks"</option>
-                    <option value="LongBreak">"Long Breaks"</option>
-                </select>
+                    }}
+                    <select
+                        class="px-3 py-1 border rounded text-sm bg-white dark:bg-gray-700 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        on:change=move |ev| {
+                            let value = event_target_value(&ev);
+                            selected.set(HashSet::new());
+                            filter_task.set(if value.is_empty() { None } else { Some(value) });
+                        }
+                    >
+                        <option value="">"All tasks"</option>
+                        {
+                            let tasks = task_controller.tasks;
+                            move || tasks.get().into_iter().map(|task| {
+                                let id = task.id.clone();
+                                view! {
+                                    <option value=task.id.clone() selected=move || filter_task.get_untracked().as_deref() == Some(id.as_str())>
+                                        {if task.archived { format!("{} (archived)", task.name) } else { task.name.clone() }}
+                                    </option>
+                                }
+                            }).collect_view()
+                        }
+                    </select>
+                    <select
+                        class="px-3 py-1 border rounded text-sm bg-white dark:bg-gray-700 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        on:change=move |ev| {
+                            let value = event_target_value(&ev);
+                            if value.is_empty() || value == "all" {
+                                filter_type.set(None);
+                            } else {
+                                filter_type.set(Some(value));
+                            }
+                        }
+                    >
+                        <option value="all">"All Sessions"</option>
+                        <option value="Work">"Work Sessions"</option>
+                        <option value="ShortBreak">"Short Breaks"</option>
+                        <option value="LongBreak">"Long Breaks"</option>
+                    </select>
+                </div>
             </div>
 
+            {
+                let task_controller = task_controller.clone();
+                move || {
+                    if logging.get() {
+                        view! {
+                            <ManualSessionForm
+                                task_controller=task_controller.clone()
+                                timer_controller=controller.clone()
+                                on_logged=on_session_changed
+                            />
+                        }.into_any()
+                    } else {
+                        view! { <div></div> }.into_any()
+                    }
+                }
+            }
+
+            // Selection for bulk actions
+            {move || {
+                let page: Vec<String> = sessions_with_tasks.with(|list| list.iter().map(|s| s.session.id.clone()).collect());
+                if page.is_empty() {
+                    return view! { <div></div> }.into_any();
+                }
+                let matching = matching_ids.get();
+                let matching_count = matching.len();
+                view! {
+                    <div class="flex flex-wrap items-center gap-3 mb-3 text-xs text-gray-600 dark:text-gray-400">
+                        <button
+                            class="hover:underline"
+                            on:click=move |_| selected.update(|selection| selection.extend(page.iter().cloned()))
+                        >
+                            "Select page"
+                        </button>
+                        <button
+                            class="hover:underline"
+                            on:click=move |_| selected.set(matching.iter().cloned().collect())
+                        >
+                            {format!("Select all {} matching", matching_count)}
+                        </button>
+                        <button
+                            class="hover:underline disabled:opacity-50"
+                            disabled=move || selected.with(|selection| selection.is_empty())
+                            on:click=move |_| selected.set(HashSet::new())
+                        >
+                            "Clear selection"
+                        </button>
+                        <span>{move || format!("Showing {} of {}", matching_count.min(PAGE_SIZE), matching_count)}</span>
+                    </div>
+                }.into_any()
+            }}
+
+            {
+                let task_controller = task_controller.clone();
+                move || {
+                    if selected.with(|selection| selection.is_empty()) {
+                        view! { <div></div> }.into_any()
+                    } else {
+                        view! {
+                            <BulkSessionActions
+                                task_controller=task_controller.clone()
+                                selected=selected
+                                on_changed=on_session_changed
+                            />
+                        }.into_any()
+                    }
+                }
+            }
+
             // Loading state
             {move || {
                 if loading.get() {
@@ -264,6 +578,15 @@ pub fn SessionHistory(controller: TimerController) -> impl IntoView {
                                     let video_path = session.video_path.clone();
                                     let is_break_session = session.session_type == "ShortBreak" || session.session_type == "LongBreak";
                                     let is_work_session = session.session_type == "Work";
+                                    let is_manual = session.manual;
+                                    let is_stopped = !session.completed;
+                                    let session_for_editor = session.clone();
+                                    let edit_id = session.id.clone();
+                                    let editor_id = session.id.clone();
+                                    let checked_id = session.id.clone();
+                                    let toggle_id = session.id.clone();
+                                    let tags = session.tags.clone();
+                                    let task_controller_editor = task_controller.clone();
 
                                     let session_color = match session.session_type.as_str() {
                                         "Work" => "border-l-red-500 bg-red-50 dark:bg-red-900/20",
@@ -275,6 +598,23 @@ pub fn SessionHistory(controller: TimerController) -> impl IntoView {
                                     view! {
                                         <div class=format!("border-l-4 p-4 rounded-r-lg {}", session_color)>
                                             <div class="flex justify-between items-start">
+                                                <input
+                                                    type="checkbox"
+                                                    class="mt-1 mr-3 rounded border-gray-300 text-blue-600"
+                                                    title="Select for bulk actions"
+                                                    prop:checked=move || selected.with(|selection| selection.contains(&checked_id))
+                                                    on:change=move |ev| {
+                                                        let checked = event_target_checked(&ev);
+                                                        let id = toggle_id.clone();
+                                                        selected.update(|selection| {
+                                                            if checked {
+                                                                selection.insert(id);
+                                                            } else {
+                                                                selection.remove(&id);
+                                                            }
+                                                        });
+                                                    }
+                                                />
                                                 <div class="flex-grow">
                                                     <div class="flex items-center space-x-2 mb-2">
                                                         <span class="font-medium text-gray-800 dark:text-white">
@@ -303,6 +643,24 @@ pub fn SessionHistory(controller: TimerController) -> impl IntoView {
                                                             view! { <div></div> }.into_any()
                                                         }}
 
+                                                        {tags.into_iter().map(|tag| view! {
+                                                            <span class="text-xs bg-teal-100 dark:bg-teal-800 text-teal-800 dark:text-teal-200 px-2 py-1 rounded">
+                                                                {format!("🏷️ {}", tag)}
+                                                            </span>
+                                                        }).collect::<Vec<_>>()}
+
+                                                        {is_manual.then(|| view! {
+                                                            <span class="text-xs bg-yellow-100 dark:bg-yellow-800 text-yellow-800 dark:text-yellow-200 px-2 py-1 rounded" title="Logged manually after the fact">
+                                                                "✍️ Manual"
+                                                            </span>
+                                                        })}
+
+                                                        {is_stopped.then(|| view! {
+                                                            <span class="text-xs bg-orange-100 dark:bg-orange-800 text-orange-800 dark:text-orange-200 px-2 py-1 rounded" title="Stopped before the timer ran out">
+                                                                "⏹ Stopped"
+                                                            </span>
+                                                        })}
+
                                                         // Video indicator for break sessions
                                                         {if is_break_session {
                                                             if has_video {
@@ -400,14 +758,43 @@ pub fn SessionHistory(controller: TimerController) -> impl IntoView {
                                                     }}
                                                 </div>
 
-                                                <button
-                                                    class="text-red-500 hover:text-red-700 text-sm ml-2 flex-shrink-0"
-                                                    on:click=move |_| delete_session(session_id.clone())
-                                                    title="Delete session"
-                                                >
-                                                    "×"
-                                                </button>
+                                                <div class="flex items-center ml-2 flex-shrink-0 space-x-2">
+                                                    <button
+                                                        class="text-gray-500 hover:text-gray-700 dark:text-gray-400 dark:hover:text-gray-200 text-sm"
+                                                        on:click=move |_| {
+                                                            let id = edit_id.clone();
+                                                            editing.update(|current| {
+                                                                *current = if current.as_deref() == Some(id.as_str()) { None } else { Some(id) };
+                                                            });
+                                                        }
+                                                        title="Edit, split or merge session"
+                                                    >
+                                                        "✏️"
+                                                    </button>
+                                                    <button
+                                                        class="text-red-500 hover:text-red-700 text-sm"
+                                                        on:click=move |_| delete_session(session_id.clone())
+                                                        title="Delete session"
+                                                    >
+                                                        "×"
+                                                    </button>
+                                                </div>
                                             </div>
+
+                                            {move || {
+                                                if editing.get().as_deref() == Some(editor_id.as_str()) {
+                                                    view! {
+                                                        <SessionEditor
+                                                            session=session_for_editor.clone()
+                                                            task_controller=task_controller_editor.clone()
+                                                            on_changed=on_session_changed
+                                                            on_close=Callback::new(move |_| editing.set(None))
+                                                        />
+                                                    }.into_any()
+                                                } else {
+                                                    view! { <div></div> }.into_any()
+                                                }
+                                            }}
                                         </div>
                                     }
                                 }).collect::<Vec<_>>()}
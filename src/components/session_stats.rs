@@ -1,12 +1,35 @@
 use crate::timer::TimerController;
+use crate::types::PROJECT_ROLLUP_WEEKS;
 use crate::utils::format_duration_hours_minutes;
 use leptos::prelude::*;
 
 #[component]
 pub fn SessionStats(controller: TimerController) -> impl IntoView {
+    let include_manual = controller.include_manual_sessions;
+    let reload = {
+        let controller = controller.clone();
+        move || controller.load_session_stats()
+    };
+
     view! {
         <div class="mt-6">
-            <h3 class="text-lg font-semibold text-gray-800 dark:text-white mb-4">Statistics</h3>
+            <div class="flex justify-between items-center mb-4">
+                <h3 class="text-lg font-semibold text-gray-800 dark:text-white">Statistics</h3>
+                <label
+                    class="flex items-center space-x-2 text-sm text-gray-600 dark:text-gray-400"
+                    title="Applies to these statistics and the focus charts. The heatmap, daily goal, reports and timesheets always count logged sessions."
+                >
+                    <input
+                        type="checkbox"
+                        prop:checked=move || include_manual.get()
+                        on:change=move |ev| {
+                            include_manual.set(event_target_checked(&ev));
+                            reload();
+                        }
+                    />
+                    <span>"Include manually logged sessions in statistics and charts"</span>
+                </label>
+            </div>
             
             {move || {
                 if let Some(stats) = controller.session_stats.get() {
@@ -74,6 +97,62 @@ pub fn SessionStats(controller: TimerController) -> impl IntoView {
                                 </div>
                             </div>
 
+                            // Per-project rollup
+                            {if stats.project_stats.is_empty() {
+                                view! { <div></div> }.into_any()
+                            } else {
+                                let max_week = stats
+                                    .project_stats
+                                    .iter()
+                                    .flat_map(|p| p.weekly_focus.iter().map(|(_, secs)| *secs))
+                                    .max()
+                                    .unwrap_or(0)
+                                    .max(1);
+
+                                view! {
+                                    <div class="bg-gray-50 dark:bg-gray-700 rounded-lg p-4 col-span-2 space-y-3">
+                                        <div class="flex justify-between items-center">
+                                            <div class="text-sm font-medium text-gray-700 dark:text-gray-300">"By Project"</div>
+                                            <div class="text-xs text-gray-500 dark:text-gray-400">
+                                                {format!("Focus per week, last {} weeks", PROJECT_ROLLUP_WEEKS)}
+                                            </div>
+                                        </div>
+                                        {stats.project_stats.iter().map(|project| {
+                                            view! {
+                                                <div>
+                                                    <div class="flex justify-between items-center text-sm">
+                                                        <div class="flex items-center space-x-2 min-w-0">
+                                                            <div
+                                                                class="w-3 h-3 rounded-full flex-shrink-0"
+                                                                style:background-color=project.color.clone()
+                                                            ></div>
+                                                            <span class="text-gray-800 dark:text-white truncate">{project.name.clone()}</span>
+                                                        </div>
+                                                        <span class="text-xs text-gray-600 dark:text-gray-400 flex-shrink-0">
+                                                            {project.pomodoros} " 🍅 · " {format_duration_hours_minutes(project.total_focus_time)}
+                                                        </span>
+                                                    </div>
+                                                    <div class="flex items-end space-x-1 h-8 mt-1">
+                                                        {project.weekly_focus.iter().map(|(week, secs)| {
+                                                            let height = (*secs as f64 / max_week as f64 * 100.0).max(4.0);
+                                                            view! {
+                                                                <div
+                                                                    class="flex-1 rounded-t"
+                                                                    style:height=format!("{}%", height)
+                                                                    style:background-color=project.color.clone()
+                                                                    style:opacity={if *secs > 0 { "1" } else { "0.25" }}
+                                                                    title=format!("Week of {}: {}", week, format_duration_hours_minutes(*secs))
+                                                                ></div>
+                                                            }
+                                                        }).collect::<Vec<_>>()}
+                                                    </div>
+                                                </div>
+                                            }
+                                        }).collect::<Vec<_>>()}
+                                    </div>
+                                }.into_any()
+                            }}
+
                             // Average Duration (if available)
                             {if stats.average_session_duration > 0.0 {
                                 view! {
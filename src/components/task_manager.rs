@@ -1,7 +1,44 @@
+use crate::components::{DataIntegrityPanel, MarkdownTransfer, ProjectFilterOptions, ProjectManager};
+use crate::console_log;
 use crate::task::TaskController;
-use crate::types::{NewSubTask, NewTask, TASK_COLORS};
-use crate::utils::format_duration_hours_minutes;
+use crate::types::{
+    count_sessions_for_task, NewSubTask, NewTask, ProjectFilter, RecurrenceRule,
+    SessionLinkAction, Task, TaskPriority, TASK_COLORS, WEEKDAY_NAMES,
+};
+use crate::utils::{format_duration_hours_minutes, get_today_date_string, parse_date_key};
 use leptos::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+
+// Item currently being dragged in the task list
+#[derive(Debug, Clone, PartialEq)]
+pub enum DragItem {
+    Task(String),
+    Subtask(String),
+}
+
+// Shared drag-and-drop and keyboard reordering state for the task list
+#[derive(Clone, Copy)]
+pub struct ReorderState {
+    pub dragging: RwSignal<Option<DragItem>>,
+    pub drag_over: RwSignal<Option<String>>,
+    // Task or subtask ID to refocus once the list re-renders after a keyboard move
+    pub pending_focus: RwSignal<Option<String>>,
+}
+
+impl ReorderState {
+    pub fn new() -> Self {
+        Self {
+            dragging: RwSignal::new(None),
+            drag_over: RwSignal::new(None),
+            pending_focus: RwSignal::new(None),
+        }
+    }
+
+    pub fn end_drag(&self) {
+        self.dragging.set(None);
+        self.drag_over.set(None);
+    }
+}
 
 #[component]
 pub fn TaskManager(task_controller: TaskController) -> impl IntoView {
@@ -10,18 +47,76 @@ pub fn TaskManager(task_controller: TaskController) -> impl IntoView {
     let new_task_description = RwSignal::new(String::new());
     let new_task_color = RwSignal::new(TASK_COLORS[0].to_string());
     let new_task_estimated_pomodoros = RwSignal::new(String::new());
+    let new_task_due_date = RwSignal::new(String::new());
+    let new_task_priority = RwSignal::new(TaskPriority::default());
+    let new_task_recurrence = RwSignal::new("none".to_string());
+    let new_task_recurrence_days = RwSignal::new(Vec::<u32>::new());
+    let new_task_project = RwSignal::new(String::new());
+    let show_projects = RwSignal::new(false);
+    let show_markdown = RwSignal::new(false);
+    let show_integrity = RwSignal::new(false);
 
     let show_new_subtask_form = RwSignal::new(None::<String>); // Task ID for which to show subtask form
     let new_subtask_name = RwSignal::new(String::new());
     let new_subtask_description = RwSignal::new(String::new());
     let new_subtask_estimated_pomodoros = RwSignal::new(String::new());
 
+    let delete_dialog_task = RwSignal::new(None::<Task>);
+    let reorder = ReorderState::new();
+
     view! {
         <div class="task-manager">
             <div class="flex justify-between items-center mb-4">
                 <h3 class="text-lg font-semibold text-gray-800 dark:text-white">Task Management</h3>
 
                 <div class="flex space-x-2">
+                    {
+                        let task_controller = task_controller.clone();
+                        move || {
+                            let task_controller_filter = task_controller.clone();
+                            view! {
+                                <select
+                                    class="text-sm px-2 py-1 border rounded bg-white dark:bg-gray-700 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                                    on:change=move |ev| task_controller_filter.project_filter.set(ProjectFilter::from_value(&event_target_value(&ev)))
+                                >
+                                    <ProjectFilterOptions
+                                        projects=task_controller.projects.get()
+                                        selected=task_controller.project_filter.get().to_value()
+                                    />
+                                </select>
+                            }
+                        }
+                    }
+
+                    <button
+                        class="text-sm px-3 py-1 rounded border border-gray-300 dark:border-gray-600 hover:bg-gray-50 dark:hover:bg-gray-700 text-gray-700 dark:text-gray-300 transition-colors"
+                        on:click=move |_| show_projects.set(!show_projects.get())
+                    >
+                        {move || if show_projects.get() { "Hide Projects" } else { "Projects" }}
+                    </button>
+
+                    <button
+                        class="text-sm px-3 py-1 rounded border border-gray-300 dark:border-gray-600 hover:bg-gray-50 dark:hover:bg-gray-700 text-gray-700 dark:text-gray-300 transition-colors"
+                        on:click=move |_| show_markdown.set(!show_markdown.get())
+                        title="Import or export tasks as a Markdown checklist"
+                    >
+                        "Markdown"
+                    </button>
+
+                    <button
+                        class="text-sm px-3 py-1 rounded border border-gray-300 dark:border-gray-600 hover:bg-gray-50 dark:hover:bg-gray-700 text-gray-700 dark:text-gray-300 transition-colors"
+                        on:click=move |_| show_integrity.set(!show_integrity.get())
+                        title="Check task totals against the session log and find orphaned records"
+                    >
+                        {
+                            let integrity_report = task_controller.integrity_report;
+                            move || match integrity_report.get().map(|r| r.unresolved()) {
+                                Some(unresolved) if unresolved > 0 => format!("Verify Data ({})", unresolved),
+                                _ => "Verify Data".to_string(),
+                            }
+                        }
+                    </button>
+
                     <button
                         class="text-sm px-3 py-1 rounded border border-gray-300 dark:border-gray-600 hover:bg-gray-50 dark:hover:bg-gray-700 text-gray-700 dark:text-gray-300 transition-colors"
                         on:click={
@@ -35,15 +130,80 @@ pub fn TaskManager(task_controller: TaskController) -> impl IntoView {
                         }
                     </button>
 
+                    <button
+                        class="text-sm px-3 py-1 rounded border border-gray-300 dark:border-gray-600 hover:bg-gray-50 dark:hover:bg-gray-700 text-gray-700 dark:text-gray-300 transition-colors"
+                        on:click={
+                            let task_controller = task_controller.clone();
+                            move |_| task_controller.show_archived.set(!task_controller.show_archived.get())
+                        }
+                    >
+                        {
+                            let task_controller = task_controller.clone();
+                            move || {
+                                if task_controller.show_archived.get() {
+                                    "Show Active".to_string()
+                                } else {
+                                    let archived = task_controller.tasks.get().iter().filter(|t| t.archived).count();
+                                    format!("Archived ({})", archived)
+                                }
+                            }
+                        }
+                    </button>
+
                     <button
                         class="text-sm px-3 py-1 bg-blue-500 hover:bg-blue-600 text-white rounded transition-colors"
-                        on:click=move |_| show_new_task_form.set(!show_new_task_form.get())
+                        on:click={
+                            let task_controller = task_controller.clone();
+                            move |_| {
+                                // New tasks default to the project being viewed
+                                if let ProjectFilter::Project(project_id) = task_controller.project_filter.get() {
+                                    new_task_project.set(project_id);
+                                }
+                                show_new_task_form.set(!show_new_task_form.get())
+                            }
+                        }
                     >
                         {move || if show_new_task_form.get() { "Cancel" } else { "+ New Task" }}
                     </button>
                 </div>
             </div>
 
+            // Projects
+            {
+                let task_controller = task_controller.clone();
+                move || {
+                    if show_projects.get() {
+                        view! { <ProjectManager task_controller=task_controller.clone() /> }.into_any()
+                    } else {
+                        view! { <div></div> }.into_any()
+                    }
+                }
+            }
+
+            // Markdown import/export
+            {
+                let task_controller = task_controller.clone();
+                move || {
+                    if show_markdown.get() {
+                        view! { <MarkdownTransfer task_controller=task_controller.clone() /> }.into_any()
+                    } else {
+                        view! { <div></div> }.into_any()
+                    }
+                }
+            }
+
+            // Verify & repair
+            {
+                let task_controller = task_controller.clone();
+                move || {
+                    if show_integrity.get() {
+                        view! { <DataIntegrityPanel task_controller=task_controller.clone() /> }.into_any()
+                    } else {
+                        view! { <div></div> }.into_any()
+                    }
+                }
+            }
+
             // New Task Form
             {
                 let task_controller_form = task_controller.clone();
@@ -102,6 +262,99 @@ pub fn TaskManager(task_controller: TaskController) -> impl IntoView {
                                         </div>
                                     </div>
 
+                                    <div>
+                                        <label class="block text-xs text-gray-600 dark:text-gray-400 mb-1">"Project"</label>
+                                        <select
+                                            class="w-full px-3 py-2 border rounded bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                                            on:change=move |ev| new_task_project.set(event_target_value(&ev))
+                                        >
+                                            <option value="" selected=move || new_task_project.get().is_empty()>"No project"</option>
+                                            {task_controller_form.projects.get().into_iter().filter(|p| !p.archived).map(|project| {
+                                                let project_id = project.id.clone();
+                                                view! {
+                                                    <option value=project.id.clone() selected=move || new_task_project.get() == project_id>
+                                                        {project.name.clone()}
+                                                    </option>
+                                                }
+                                            }).collect::<Vec<_>>()}
+                                        </select>
+                                    </div>
+
+                                    <div class="flex space-x-3">
+                                        <div class="flex-1">
+                                            <label class="block text-xs text-gray-600 dark:text-gray-400 mb-1">"Due date"</label>
+                                            <input
+                                                type="date"
+                                                class="w-full px-3 py-2 border rounded bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                                                prop:value=move || new_task_due_date.get()
+                                                on:input=move |ev| new_task_due_date.set(event_target_value(&ev))
+                                            />
+                                        </div>
+
+                                        <div class="flex-1">
+                                            <label class="block text-xs text-gray-600 dark:text-gray-400 mb-1">"Priority"</label>
+                                            <select
+                                                class="w-full px-3 py-2 border rounded bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                                                on:change=move |ev| new_task_priority.set(TaskPriority::from_string(&event_target_value(&ev)))
+                                            >
+                                                {TaskPriority::all().into_iter().map(|priority| {
+                                                    view! {
+                                                        <option value=priority.name() selected=move || new_task_priority.get() == priority>
+                                                            {priority.name()}
+                                                        </option>
+                                                    }
+                                                }).collect::<Vec<_>>()}
+                                            </select>
+                                        </div>
+                                    </div>
+
+                                    <div>
+                                        <label class="block text-xs text-gray-600 dark:text-gray-400 mb-1">"Repeat"</label>
+                                        <select
+                                            class="w-full px-3 py-2 border rounded bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                                            on:change=move |ev| new_task_recurrence.set(event_target_value(&ev))
+                                        >
+                                            <option value="none" selected=move || new_task_recurrence.get() == "none">"Does not repeat"</option>
+                                            <option value="daily" selected=move || new_task_recurrence.get() == "daily">"Daily"</option>
+                                            <option value="weekdays" selected=move || new_task_recurrence.get() == "weekdays">"Every weekday"</option>
+                                            <option value="weekly" selected=move || new_task_recurrence.get() == "weekly">"Weekly on..."</option>
+                                            <option value="monthly" selected=move || new_task_recurrence.get() == "monthly">"Monthly (same day as due date)"</option>
+                                        </select>
+
+                                        {move || {
+                                            if new_task_recurrence.get() == "weekly" {
+                                                view! {
+                                                    <div class="flex flex-wrap gap-2 mt-2">
+                                                        {WEEKDAY_NAMES.iter().enumerate().map(|(day, name)| {
+                                                            let day = day as u32;
+                                                            view! {
+                                                                <label class="flex items-center space-x-1 text-xs text-gray-700 dark:text-gray-300">
+                                                                    <input
+                                                                        type="checkbox"
+                                                                        class="rounded border-gray-300 text-blue-600"
+                                                                        prop:checked=move || new_task_recurrence_days.get().contains(&day)
+                                                                        on:change=move |ev| {
+                                                                            let mut days = new_task_recurrence_days.get();
+                                                                            days.retain(|d| *d != day);
+                                                                            if event_target_checked(&ev) {
+                                                                                days.push(day);
+                                                                                days.sort();
+                                                                            }
+                                                                            new_task_recurrence_days.set(days);
+                                                                        }
+                                                                    />
+                                                                    <span>{*name}</span>
+                                                                </label>
+                                                            }
+                                                        }).collect::<Vec<_>>()}
+                                                    </div>
+                                                }.into_any()
+                                            } else {
+                                                view! { <div></div> }.into_any()
+                                            }
+                                        }}
+                                    </div>
+
                                     <button
                                         class="w-full px-4 py-2 bg-green-500 hover:bg-green-600 text-white rounded transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
                                         on:click={
@@ -118,6 +371,24 @@ pub fn TaskManager(task_controller: TaskController) -> impl IntoView {
                                                     new_task_estimated_pomodoros.get().parse().ok()
                                                 };
 
+                                                let due_date = if new_task_due_date.get().is_empty() {
+                                                    None
+                                                } else {
+                                                    Some(new_task_due_date.get())
+                                                };
+
+                                                let recurrence = match new_task_recurrence.get().as_str() {
+                                                    "daily" => Some(RecurrenceRule::Daily),
+                                                    "weekdays" => Some(RecurrenceRule::Weekdays),
+                                                    "weekly" => Some(RecurrenceRule::Weekly { days: new_task_recurrence_days.get() }),
+                                                    "monthly" => {
+                                                        let anchor = due_date.clone().unwrap_or_else(get_today_date_string);
+                                                        let day_of_month = parse_date_key(&anchor).map(|(_, _, day)| day).unwrap_or(1);
+                                                        Some(RecurrenceRule::Monthly { day_of_month })
+                                                    }
+                                                    _ => None,
+                                                };
+
                                                 let new_task = NewTask {
                                                     name,
                                                     description: if new_task_description.get().trim().is_empty() {
@@ -127,6 +398,10 @@ pub fn TaskManager(task_controller: TaskController) -> impl IntoView {
                                                     },
                                                     color: new_task_color.get(),
                                                     estimated_pomodoros: estimated,
+                                                    due_date,
+                                                    priority: new_task_priority.get(),
+                                                    recurrence,
+                                                    project_id: Some(new_task_project.get()).filter(|id| !id.is_empty()),
                                                 };
 
                                                 task_controller_create.create_task(new_task);
@@ -135,6 +410,11 @@ pub fn TaskManager(task_controller: TaskController) -> impl IntoView {
                                                 new_task_name.set(String::new());
                                                 new_task_description.set(String::new());
                                                 new_task_estimated_pomodoros.set(String::new());
+                                                new_task_due_date.set(String::new());
+                                                new_task_priority.set(TaskPriority::default());
+                                                new_task_recurrence.set("none".to_string());
+                                                new_task_recurrence_days.set(Vec::new());
+                                                new_task_project.set(String::new());
                                                 show_new_task_form.set(false);
                                             }
                                         }
@@ -184,10 +464,41 @@ pub fn TaskManager(task_controller: TaskController) -> impl IntoView {
                 }
             }
 
+            // Sync conflicts (resolved in Settings → Integrations)
+            {
+                let task_controller = task_controller.clone();
+                move || {
+                    let conflicts = task_controller.sync_conflicts.get();
+                    if conflicts.is_empty() {
+                        view! { <div></div> }.into_any()
+                    } else {
+                        view! {
+                            <div class="bg-yellow-100 dark:bg-yellow-900 border border-yellow-300 dark:border-yellow-700 text-yellow-800 dark:text-yellow-200 px-4 py-3 rounded mb-4">
+                                <div class="font-medium">
+                                    {format!("{} task(s) changed both here and in a synced source", conflicts.len())}
+                                </div>
+                                <div class="text-sm">
+                                    {conflicts.iter().map(|c| c.task_name.clone()).collect::<Vec<_>>().join(", ")}
+                                    " · Resolve them in Settings → Integrations."
+                                </div>
+                            </div>
+                        }.into_any()
+                    }
+                }
+            }
+
+            // Delete confirmation
+            <DeleteTaskDialog
+                task_controller=task_controller.clone()
+                delete_dialog_task=delete_dialog_task
+            />
+
             // Tasks List
             <div class="space-y-4">
                 <TaskList
                     task_controller=task_controller
+                    delete_dialog_task=delete_dialog_task
+                    reorder=reorder
                     show_new_subtask_form=show_new_subtask_form
                     new_subtask_name=new_subtask_name
                     new_subtask_description=new_subtask_description
@@ -201,6 +512,8 @@ pub fn TaskManager(task_controller: TaskController) -> impl IntoView {
 #[component]
 pub fn TaskList(
     task_controller: TaskController,
+    delete_dialog_task: RwSignal<Option<Task>>,
+    reorder: ReorderState,
     show_new_subtask_form: RwSignal<Option<String>>,
     new_subtask_name: RwSignal<String>,
     new_subtask_description: RwSignal<String>,
@@ -208,25 +521,38 @@ pub fn TaskList(
 ) -> impl IntoView {
     move || {
         let progress_summary = task_controller.get_task_progress_summary();
+        let visible_ids: Vec<String> = progress_summary.iter().map(|(task, ..)| task.id.clone()).collect();
 
         if progress_summary.is_empty() && !task_controller.loading.get() {
             view! {
                 <div class="text-center py-8 text-gray-500 dark:text-gray-400">
-                    "No tasks found. Create your first task to get started!"
+                    {if task_controller.show_archived.get() {
+                        "No archived tasks."
+                    } else {
+                        "No tasks found. Create your first task to get started!"
+                    }}
                 </div>
             }
             .into_any()
         } else {
             view! {
                 <div class="space-y-4">
-                    {progress_summary.into_iter().map(|(task, completion_percentage, completed_subtasks, total_subtasks)| {
+                    {progress_summary.into_iter().enumerate().map(|(index, (task, completion_percentage, completed_subtasks, total_subtasks))| {
+                        // Neighbours in the visible list are the keyboard move targets
+                        let prev_task_id = index.checked_sub(1).map(|i| visible_ids[i].clone());
+                        let next_task_id = visible_ids.get(index + 1).cloned();
+
                         view! {
                             <TaskItem
                                 task=task
+                                prev_task_id=prev_task_id
+                                next_task_id=next_task_id
                                 completion_percentage=completion_percentage
                                 completed_subtasks=completed_subtasks
                                 total_subtasks=total_subtasks
                                 task_controller=task_controller.clone()
+                                delete_dialog_task=delete_dialog_task
+                                reorder=reorder
                                 show_new_subtask_form=show_new_subtask_form
                                 new_subtask_name=new_subtask_name
                                 new_subtask_description=new_subtask_description
@@ -243,19 +569,128 @@ pub fn TaskList(
 #[component]
 pub fn TaskItem(
     task: crate::types::Task,
+    prev_task_id: Option<String>,
+    next_task_id: Option<String>,
     completion_percentage: f64,
     completed_subtasks: u32,
     total_subtasks: u32,
     task_controller: TaskController,
+    delete_dialog_task: RwSignal<Option<Task>>,
+    reorder: ReorderState,
     show_new_subtask_form: RwSignal<Option<String>>,
     new_subtask_name: RwSignal<String>,
     new_subtask_description: RwSignal<String>,
     new_subtask_estimated_pomodoros: RwSignal<String>,
 ) -> impl IntoView {
     let task_id = task.id.clone();
+    let today = get_today_date_string();
+    let overdue = task.is_overdue(&today);
+    let planned_today = task.is_planned_for(&today);
+
+    let prev_task_id_subtasks = prev_task_id.clone();
+    let next_task_id_subtasks = next_task_id.clone();
+
+    let card_ref = NodeRef::<leptos::tachys::html::element::Div>::new();
+    {
+        let task_id = task_id.clone();
+        Effect::new(move |_| {
+            if let Some(card) = card_ref.get() {
+                if reorder.pending_focus.get_untracked().as_deref() == Some(task_id.as_str()) {
+                    let _ = card.focus();
+                    reorder.pending_focus.set(None);
+                }
+            }
+        });
+    }
+
+    let on_keydown = {
+        let task_id = task_id.clone();
+        let task_controller = task_controller.clone();
+        move |ev: web_sys::KeyboardEvent| {
+            // Only react when the card itself has focus, not its buttons or inputs
+            if !ev.alt_key() || ev.target() != ev.current_target() {
+                return;
+            }
+            let neighbour = match ev.key().as_str() {
+                "ArrowUp" => prev_task_id.as_ref(),
+                "ArrowDown" => next_task_id.as_ref(),
+                _ => return,
+            };
+            ev.prevent_default();
+            if let Some(position) = neighbour.and_then(|id| task_controller.get_task_position(id)) {
+                reorder.pending_focus.set(Some(task_id.clone()));
+                task_controller.move_task(task_id.clone(), position);
+            }
+        }
+    };
+
+    let on_drop = {
+        let task_id = task_id.clone();
+        let task_controller = task_controller.clone();
+        move |ev: web_sys::DragEvent| {
+            ev.prevent_default();
+            match reorder.dragging.get_untracked() {
+                Some(DragItem::Task(dragged_id)) if dragged_id != task_id => {
+                    if let Some(position) = task_controller.get_task_position(&task_id) {
+                        task_controller.move_task(dragged_id, position);
+                    }
+                }
+                // Dropping a subtask on a task card appends it to that task
+                Some(DragItem::Subtask(dragged_id)) => {
+                    task_controller.move_subtask(dragged_id, task_id.clone(), usize::MAX);
+                }
+                _ => {}
+            }
+            reorder.end_drag();
+        }
+    };
+
+    let on_dragstart = {
+        let task_id = task_id.clone();
+        move |ev: web_sys::DragEvent| {
+            if let Some(data) = ev.data_transfer() {
+                let _ = data.set_data("text/plain", &task_id);
+            }
+            reorder.dragging.set(Some(DragItem::Task(task_id.clone())));
+        }
+    };
+
+    let on_dragover = {
+        let task_id = task_id.clone();
+        move |ev: web_sys::DragEvent| {
+            if reorder.dragging.get_untracked().is_some() {
+                ev.prevent_default();
+                if reorder.drag_over.get_untracked().as_deref() != Some(task_id.as_str()) {
+                    reorder.drag_over.set(Some(task_id.clone()));
+                }
+            }
+        }
+    };
+
+    let drop_highlight = {
+        let task_id = task_id.clone();
+        move || reorder.drag_over.get().as_deref() == Some(task_id.as_str())
+    };
 
     view! {
-        <div class="border rounded-lg bg-white dark:bg-gray-800 border-gray-200 dark:border-gray-600 p-4">
+        <div
+            node_ref=card_ref
+            tabindex="0"
+            draggable="true"
+            title="Drag or use Alt+↑/↓ to reorder"
+            class=move || format!("border rounded-lg p-4 focus:outline-none focus:ring-2 focus:ring-blue-400 {} {}",
+                if overdue {
+                    "bg-red-50 dark:bg-red-900/20 border-red-300 dark:border-red-700"
+                } else {
+                    "bg-white dark:bg-gray-800 border-gray-200 dark:border-gray-600"
+                },
+                if drop_highlight() { "ring-2 ring-blue-400" } else { "" })
+            on:keydown=on_keydown
+            on:dragstart=on_dragstart
+            on:dragend=move |_| reorder.end_drag()
+            on:dragover=on_dragover
+            on:drop=on_drop
+        >
             // Task Header
             <div class="flex items-start justify-between mb-3">
                 <div class="flex items-start space-x-3 flex-grow">
@@ -280,6 +715,74 @@ pub fn TaskItem(
                             } else {
                                 view! { <div></div> }.into_any()
                             }}
+
+                            <span class=format!("text-xs px-2 py-1 rounded {}", task.priority.badge_class())>
+                                {task.priority.name()}
+                            </span>
+
+                            {task.due_date.clone().map(|due| {
+                                view! {
+                                    <span class=format!("text-xs px-2 py-1 rounded {}",
+                                        if overdue {
+                                            "bg-red-500 text-white"
+                                        } else {
+                                            "bg-gray-100 dark:bg-gray-700 text-gray-600 dark:text-gray-300"
+                                        })>
+                                        {if overdue { format!("Overdue · {}", due) } else { format!("Due {}", due) }}
+                                    </span>
+                                }
+                            })}
+
+                            {task.project_id.as_deref().and_then(|id| task_controller.get_project_by_id(id)).map(|project| {
+                                view! {
+                                    <span class="text-xs px-2 py-1 rounded border border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 flex items-center space-x-1">
+                                        <span class="w-2 h-2 rounded-full inline-block" style:background-color=project.color></span>
+                                        <span>{project.name}</span>
+                                    </span>
+                                }
+                            })}
+
+                            {task.tags.iter().map(|tag| {
+                                view! {
+                                    <span class="text-xs bg-teal-100 dark:bg-teal-800 text-teal-800 dark:text-teal-200 px-2 py-1 rounded">
+                                        {format!("@{}", tag)}
+                                    </span>
+                                }
+                            }).collect::<Vec<_>>()}
+
+                            {task.external.clone().map(|external| {
+                                let badge_class = "text-xs bg-gray-100 dark:bg-gray-700 text-gray-600 dark:text-gray-300 px-2 py-1 rounded";
+                                match external.url {
+                                    Some(url) => view! {
+                                        <a href=url target="_blank" rel="noopener" class=format!("{} hover:underline", badge_class) title=external.id.clone()>
+                                            "🔗 " {external.source.clone()}
+                                        </a>
+                                    }.into_any(),
+                                    None => view! {
+                                        <span class=badge_class title="Synced with an external source">
+                                            "🔗 " {external.source.clone()}
+                                        </span>
+                                    }.into_any(),
+                                }
+                            })}
+
+                            {task.recurrence.as_ref().map(|rule| {
+                                view! {
+                                    <span class="text-xs bg-purple-100 dark:bg-purple-800 text-purple-800 dark:text-purple-200 px-2 py-1 rounded">
+                                        "🔁 " {rule.describe()}
+                                    </span>
+                                }
+                            })}
+
+                            {if planned_today {
+                                view! {
+                                    <span class="text-xs bg-blue-100 dark:bg-blue-800 text-blue-800 dark:text-blue-200 px-2 py-1 rounded">
+                                        "☀️ Today"
+                                    </span>
+                                }.into_any()
+                            } else {
+                                view! { <div></div> }.into_any()
+                            }}
                         </div>
 
                         {task.description.as_ref().map(|desc| {
@@ -290,6 +793,61 @@ pub fn TaskItem(
                             }
                         })}
 
+                        // Project assignment
+                        {
+                            let projects = task_controller.projects.get();
+                            if projects.is_empty() {
+                                view! { <div></div> }.into_any()
+                            } else {
+                                let current_project = task.project_id.clone().unwrap_or_default();
+                                let task_for_project = task.clone();
+                                let task_controller_project = task_controller.clone();
+                                view! {
+                                    <select
+                                        class="mt-2 text-xs px-2 py-1 border rounded bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                                        title="Project"
+                                        on:change=move |ev| {
+                                            let mut updated = task_for_project.clone();
+                                            updated.project_id = Some(event_target_value(&ev)).filter(|id| !id.is_empty());
+                                            task_controller_project.update_task(updated);
+                                        }
+                                    >
+                                        <option value="" selected=current_project.is_empty()>"No project"</option>
+                                        {projects.into_iter()
+                                            .filter(|p| !p.archived || p.id == current_project)
+                                            .map(|project| {
+                                                let is_selected = project.id == current_project;
+                                                view! {
+                                                    <option value=project.id.clone() selected=is_selected>{project.name.clone()}</option>
+                                                }
+                                            }).collect::<Vec<_>>()}
+                                    </select>
+                                }.into_any()
+                            }
+                        }
+
+                        // Hourly rate for timesheets
+                        {
+                            let task_for_rate = task.clone();
+                            let task_controller_rate = task_controller.clone();
+                            view! {
+                                <input
+                                    type="number"
+                                    min="0"
+                                    step="0.01"
+                                    placeholder="Hourly rate"
+                                    title="Hourly rate on timesheets (overrides the project's rate)"
+                                    class="mt-2 ml-2 w-28 text-xs px-2 py-1 border rounded bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                                    prop:value=task.hourly_rate.map(|rate| rate.to_string()).unwrap_or_default()
+                                    on:change=move |ev| {
+                                        let mut updated = task_for_rate.clone();
+                                        updated.hourly_rate = event_target_value(&ev).trim().parse::<f64>().ok().filter(|rate| *rate >= 0.0);
+                                        task_controller_rate.update_task(updated);
+                                    }
+                                />
+                            }
+                        }
+
                         // Progress and Stats - FIXED VERSION
                         <div class="mt-2 space-y-2">
                             <div class="flex items-center space-x-4 text-sm">
@@ -344,6 +902,7 @@ pub fn TaskItem(
                 <TaskActions
                     task=task.clone()
                     task_controller=task_controller.clone()
+                    delete_dialog_task=delete_dialog_task
                     show_new_subtask_form=show_new_subtask_form
                 />
             </div>
@@ -362,6 +921,9 @@ pub fn TaskItem(
             <SubtaskList
                 task_id=task_id
                 task_controller=task_controller
+                reorder=reorder
+                prev_task_id=prev_task_id_subtasks
+                next_task_id=next_task_id_subtasks
             />
         </div>
     }
@@ -371,10 +933,39 @@ pub fn TaskItem(
 pub fn TaskActions(
     task: crate::types::Task,
     task_controller: TaskController,
+    delete_dialog_task: RwSignal<Option<Task>>,
     show_new_subtask_form: RwSignal<Option<String>>,
 ) -> impl IntoView {
     let task_id = task.id.clone();
 
+    if task.archived {
+        return view! {
+            <div class="flex space-x-2">
+                <button
+                    class="text-xs px-2 py-1 bg-blue-500 hover:bg-blue-600 text-white rounded transition-colors"
+                    on:click={
+                        let task_id_restore = task_id.clone();
+                        let task_controller_restore = task_controller.clone();
+                        move |_| task_controller_restore.set_task_archived(task_id_restore.clone(), false)
+                    }
+                >
+                    "Restore"
+                </button>
+
+                <button
+                    class="text-xs px-2 py-1 bg-red-500 hover:bg-red-600 text-white rounded transition-colors"
+                    on:click={
+                        let task_delete = task.clone();
+                        move |_| delete_dialog_task.set(Some(task_delete.clone()))
+                    }
+                >
+                    "Delete"
+                </button>
+            </div>
+        }
+        .into_any();
+    }
+
     view! {
         <div class="flex space-x-2">
             <button
@@ -387,6 +978,18 @@ pub fn TaskActions(
                 "+ Sub"
             </button>
 
+            <button
+                class="text-xs px-2 py-1 bg-gray-500 hover:bg-gray-600 text-white rounded transition-colors"
+                on:click={
+                    let task_id_plan = task_id.clone();
+                    let task_controller_plan = task_controller.clone();
+                    move |_| task_controller_plan.toggle_task_planned_today(task_id_plan.clone())
+                }
+                title="Plan this task for today"
+            >
+                {if task.is_planned_for(&get_today_date_string()) { "Unplan" } else { "☀️ Today" }}
+            </button>
+
             <button
                 class={format!("text-xs px-2 py-1 rounded transition-colors {}",
                     if task.completed {
@@ -404,25 +1007,172 @@ pub fn TaskActions(
                 {if task.completed { "Reopen" } else { "Done" }}
             </button>
 
+            <button
+                class="text-xs px-2 py-1 bg-gray-500 hover:bg-gray-600 text-white rounded transition-colors"
+                on:click={
+                    let task_id_archive = task_id.clone();
+                    let task_controller_archive = task_controller.clone();
+                    move |_| task_controller_archive.set_task_archived(task_id_archive.clone(), true)
+                }
+                title="Hide from selection but keep history"
+            >
+                "Archive"
+            </button>
+
             <button
                 class="text-xs px-2 py-1 bg-red-500 hover:bg-red-600 text-white rounded transition-colors"
                 on:click={
-                    let task_id_delete = task_id.clone();
-                    let task_controller_delete = task_controller.clone();
-                    move |_| {
-                        if web_sys::window()
-                            .and_then(|w| w.confirm_with_message("Delete this task and all its subtasks?").ok())
-                            .unwrap_or(false)
-                        {
-                            task_controller_delete.delete_task(task_id_delete.clone());
-                        }
-                    }
+                    let task_delete = task.clone();
+                    move |_| delete_dialog_task.set(Some(task_delete.clone()))
                 }
             >
                 "Delete"
             </button>
         </div>
     }
+    .into_any()
+}
+
+#[component]
+pub fn DeleteTaskDialog(
+    task_controller: TaskController,
+    delete_dialog_task: RwSignal<Option<Task>>,
+) -> impl IntoView {
+    let linked_sessions = RwSignal::new(None::<usize>);
+    let session_action = RwSignal::new("detach".to_string());
+    let reassign_target = RwSignal::new(String::new());
+
+    // Count linked sessions whenever the dialog opens for a task
+    Effect::new(move |_| {
+        linked_sessions.set(None);
+        session_action.set("detach".to_string());
+        reassign_target.set(String::new());
+
+        if let Some(task) = delete_dialog_task.get() {
+            spawn_local(async move {
+                match count_sessions_for_task(&task.id).await {
+                    Ok(count) => linked_sessions.set(Some(count)),
+                    Err(e) => console_log!("Error counting sessions for task: {}", e),
+                }
+            });
+        }
+    });
+
+    move || {
+        let Some(task) = delete_dialog_task.get() else {
+            return view! { <div></div> }.into_any();
+        };
+
+        let other_tasks: Vec<Task> = task_controller
+            .tasks
+            .get()
+            .into_iter()
+            .filter(|t| t.id != task.id)
+            .collect();
+        let task_controller_confirm = task_controller.clone();
+        let task_id = task.id.clone();
+
+        view! {
+            <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50 p-4">
+                <div class="bg-white dark:bg-gray-800 rounded-lg shadow-xl max-w-md w-full p-6 space-y-4">
+                    <h3 class="text-lg font-semibold text-gray-800 dark:text-white">
+                        "Delete \"" {task.name.clone()} "\"?"
+                    </h3>
+                    <p class="text-sm text-gray-600 dark:text-gray-400">
+                        "The task and all its subtasks will be removed permanently. Archive it instead to keep it for history."
+                    </p>
+
+                    {move || match linked_sessions.get() {
+                        None => view! {
+                            <p class="text-sm text-gray-500 dark:text-gray-400">"Checking recorded sessions..."</p>
+                        }.into_any(),
+                        Some(0) => view! {
+                            <p class="text-sm text-gray-500 dark:text-gray-400">"No sessions are linked to this task."</p>
+                        }.into_any(),
+                        Some(count) => view! {
+                            <div class="space-y-2 text-sm text-gray-700 dark:text-gray-300">
+                                <p class="font-medium">{count} " recorded session(s) are linked to this task:"</p>
+                                <label class="flex items-center space-x-2">
+                                    <input
+                                        type="radio"
+                                        name="session-action"
+                                        prop:checked=move || session_action.get() == "detach"
+                                        on:change=move |_| session_action.set("detach".to_string())
+                                    />
+                                    <span>"Keep them without a task"</span>
+                                </label>
+                                <label class="flex items-center space-x-2">
+                                    <input
+                                        type="radio"
+                                        name="session-action"
+                                        prop:checked=move || session_action.get() == "reassign"
+                                        on:change=move |_| session_action.set("reassign".to_string())
+                                    />
+                                    <span>"Reassign them to another task"</span>
+                                </label>
+                                {
+                                    let other_tasks = other_tasks.clone();
+                                    move || {
+                                        if session_action.get() == "reassign" {
+                                            view! {
+                                                <select
+                                                    class="w-full px-3 py-2 border rounded bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                                                    on:change=move |ev| reassign_target.set(event_target_value(&ev))
+                                                >
+                                                    <option value="" selected=true>"Choose a task..."</option>
+                                                    {other_tasks.iter().map(|t| {
+                                                        view! { <option value=t.id.clone()>{t.name.clone()}</option> }
+                                                    }).collect::<Vec<_>>()}
+                                                </select>
+                                            }.into_any()
+                                        } else {
+                                            view! { <div></div> }.into_any()
+                                        }
+                                    }
+                                }
+                                <label class="flex items-center space-x-2">
+                                    <input
+                                        type="radio"
+                                        name="session-action"
+                                        prop:checked=move || session_action.get() == "delete"
+                                        on:change=move |_| session_action.set("delete".to_string())
+                                    />
+                                    <span class="text-red-600 dark:text-red-400">"Delete them too"</span>
+                                </label>
+                            </div>
+                        }.into_any(),
+                    }}
+
+                    <div class="flex justify-end space-x-2 pt-2">
+                        <button
+                            class="px-3 py-1 bg-gray-500 hover:bg-gray-600 text-white text-sm rounded transition-colors"
+                            on:click=move |_| delete_dialog_task.set(None)
+                        >
+                            "Cancel"
+                        </button>
+                        <button
+                            class="px-3 py-1 bg-red-500 hover:bg-red-600 text-white text-sm rounded transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                            disabled=move || {
+                                linked_sessions.get().is_none()
+                                    || (session_action.get() == "reassign" && reassign_target.get().is_empty())
+                            }
+                            on:click=move |_| {
+                                let action = match session_action.get().as_str() {
+                                    "reassign" => SessionLinkAction::Reassign { task_id: reassign_target.get() },
+                                    "delete" => SessionLinkAction::Delete,
+                                    _ => SessionLinkAction::Detach,
+                                };
+                                task_controller_confirm.delete_task(task_id.clone(), action);
+                                delete_dialog_task.set(None);
+                            }
+                        >
+                            "Delete Task"
+                        </button>
+                    </div>
+                </div>
+            </div>
+        }.into_any()
+    }
 }
 
 #[component]
@@ -525,17 +1275,131 @@ pub fn SubtaskForm(
 }
 
 #[component]
-pub fn SubtaskList(task_id: String, task_controller: TaskController) -> impl IntoView {
+pub fn SubtaskList(
+    task_id: String,
+    task_controller: TaskController,
+    reorder: ReorderState,
+    prev_task_id: Option<String>,
+    next_task_id: Option<String>,
+) -> impl IntoView {
     move || {
         let subtasks = task_controller.get_subtasks_for_task(&task_id);
+        // Completed subtasks may be hidden, so moves target a visible neighbour's position among all siblings
+        let visible_ids: Vec<String> = subtasks.iter().map(|st| st.id.clone()).collect();
         if !subtasks.is_empty() {
             view! {
                 <div class="mt-3 space-y-2">
-                    {subtasks.into_iter().map(|subtask| {
+                    {subtasks.into_iter().enumerate().map(|(index, subtask)| {
                         let subtask_id = subtask.id.clone();
 
+                        let row_ref = NodeRef::<leptos::tachys::html::element::Div>::new();
+                        {
+                            let subtask_id = subtask_id.clone();
+                            Effect::new(move |_| {
+                                if let Some(row) = row_ref.get() {
+                                    if reorder.pending_focus.get_untracked().as_deref() == Some(subtask_id.as_str()) {
+                                        let _ = row.focus();
+                                        reorder.pending_focus.set(None);
+                                    }
+                                }
+                            });
+                        }
+
+                        // Alt+↑/↓ reorders within the task, Alt+Shift+↑/↓ moves to the neighbouring task
+                        let on_keydown = {
+                            let subtask_id = subtask_id.clone();
+                            let task_id = task_id.clone();
+                            let prev_task_id = prev_task_id.clone();
+                            let next_task_id = next_task_id.clone();
+                            let prev_subtask_id = index.checked_sub(1).map(|i| visible_ids[i].clone());
+                            let next_subtask_id = visible_ids.get(index + 1).cloned();
+                            let task_controller = task_controller.clone();
+                            move |ev: web_sys::KeyboardEvent| {
+                                if !ev.alt_key() || ev.target() != ev.current_target() {
+                                    return;
+                                }
+                                let sibling_target = |neighbour: &Option<String>| {
+                                    neighbour
+                                        .as_deref()
+                                        .and_then(|id| task_controller.get_subtask_position(id))
+                                        .map(|position| (task_id.clone(), position))
+                                };
+                                let target = match (ev.key().as_str(), ev.shift_key()) {
+                                    ("ArrowUp", false) => sibling_target(&prev_subtask_id),
+                                    ("ArrowDown", false) => sibling_target(&next_subtask_id),
+                                    ("ArrowUp", true) => prev_task_id.clone().map(|id| (id, usize::MAX)),
+                                    ("ArrowDown", true) => next_task_id.clone().map(|id| (id, usize::MAX)),
+                                    _ => return,
+                                };
+                                ev.prevent_default();
+                                ev.stop_propagation();
+                                if let Some((target_task_id, target_index)) = target {
+                                    reorder.pending_focus.set(Some(subtask_id.clone()));
+                                    task_controller.move_subtask(subtask_id.clone(), target_task_id, target_index);
+                                }
+                            }
+                        };
+
+                        let on_drop = {
+                            let subtask_id = subtask_id.clone();
+                            let task_id = task_id.clone();
+                            let task_controller = task_controller.clone();
+                            move |ev: web_sys::DragEvent| {
+                                // Subtasks dropped on a subtask take its place; tasks fall through to the card
+                                if let Some(DragItem::Subtask(dragged_id)) = reorder.dragging.get_untracked() {
+                                    ev.prevent_default();
+                                    ev.stop_propagation();
+                                    if dragged_id != subtask_id {
+                                        if let Some(position) = task_controller.get_subtask_position(&subtask_id) {
+                                            task_controller.move_subtask(dragged_id, task_id.clone(), position);
+                                        }
+                                    }
+                                    reorder.end_drag();
+                                }
+                            }
+                        };
+
+                        let drop_highlight = {
+                            let subtask_id = subtask_id.clone();
+                            move || reorder.drag_over.get().as_deref() == Some(subtask_id.as_str())
+                        };
+
                         view! {
-                            <div class="flex items-center justify-between p-2 bg-gray-50 dark:bg-gray-700 rounded">
+                            <div
+                                node_ref=row_ref
+                                tabindex="0"
+                                draggable="true"
+                                class=move || format!("flex items-center justify-between p-2 bg-gray-50 dark:bg-gray-700 rounded focus:outline-none focus:ring-2 focus:ring-blue-400 {}",
+                                    if drop_highlight() { "ring-2 ring-blue-400" } else { "" })
+                                on:keydown=on_keydown
+                                on:dragstart={
+                                    let subtask_id = subtask_id.clone();
+                                    move |ev: web_sys::DragEvent| {
+                                        ev.stop_propagation();
+                                        if let Some(data) = ev.data_transfer() {
+                                            let _ = data.set_data("text/plain", &subtask_id);
+                                        }
+                                        reorder.dragging.set(Some(DragItem::Subtask(subtask_id.clone())));
+                                    }
+                                }
+                                on:dragend=move |ev: web_sys::DragEvent| {
+                                    ev.stop_propagation();
+                                    reorder.end_drag();
+                                }
+                                on:dragover={
+                                    let subtask_id = subtask_id.clone();
+                                    move |ev: web_sys::DragEvent| {
+                                        if matches!(reorder.dragging.get_untracked(), Some(DragItem::Subtask(_))) {
+                                            ev.prevent_default();
+                                            ev.stop_propagation();
+                                            if reorder.drag_over.get_untracked().as_deref() != Some(subtask_id.as_str()) {
+                                                reorder.drag_over.set(Some(subtask_id.clone()));
+                                            }
+                                        }
+                                    }
+                                }
+                                on:drop=on_drop
+                            >
                                 <div class="flex items-center space-x-2 flex-grow">
                                     <div class="w-2 h-2 rounded-full bg-gray-400 dark:bg-gray-500 flex-shrink-0"></div>
                                     <span class=format!("text-sm text-gray-800 dark:text-gray-200 {}",
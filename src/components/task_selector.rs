@@ -1,9 +1,12 @@
+use crate::components::ProjectFilterOptions;
 use crate::task::TaskController;
+use crate::types::ProjectFilter;
 use leptos::prelude::*;
 
 #[component]
 pub fn TaskSelector(task_controller: TaskController) -> impl IntoView {
     let show_task_selection = RwSignal::new(false);
+    let project_filter = RwSignal::new(ProjectFilter::All);
 
     view! {
         <div class="task-selector mb-6">
@@ -72,6 +75,79 @@ pub fn TaskSelector(task_controller: TaskController) -> impl IntoView {
                 }
             </div>
 
+            // Outcome of the last work session on a subtask
+            {
+                let task_controller_outcome = task_controller.clone();
+                move || {
+                    if let Some(subtask) = task_controller_outcome.pending_subtask_outcome.get() {
+                        let task_controller_done = task_controller_outcome.clone();
+                        let task_controller_continue = task_controller_outcome.clone();
+                        view! {
+                            <div class="p-3 mb-3 rounded-lg bg-blue-50 dark:bg-blue-900/20 border border-blue-200 dark:border-blue-800">
+                                <p class="text-sm text-blue-800 dark:text-blue-200 mb-2">
+                                    {format!("Session finished. Is \"{}\" done?", subtask.name)}
+                                </p>
+                                <div class="flex space-x-2">
+                                    <button
+                                        class="px-3 py-1 text-xs bg-blue-600 hover:bg-blue-700 text-white rounded transition-colors"
+                                        on:click=move |_| task_controller_done.resolve_subtask_outcome(true)
+                                    >
+                                        "✓ Done, next subtask"
+                                    </button>
+                                    <button
+                                        class="px-3 py-1 text-xs bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-700 dark:text-gray-200 rounded transition-colors"
+                                        on:click=move |_| task_controller_continue.resolve_subtask_outcome(false)
+                                    >
+                                        "Keep working on it"
+                                    </button>
+                                </div>
+                            </div>
+                        }.into_any()
+                    } else {
+                        view! { <div></div> }.into_any()
+                    }
+                }
+            }
+
+            // All subtasks done: offer to complete the parent task
+            {
+                let task_controller_parent = task_controller.clone();
+                move || {
+                    if let Some(task) = task_controller_parent.parent_completion_prompt.get() {
+                        let task_controller_complete = task_controller_parent.clone();
+                        let task_controller_dismiss = task_controller_parent.clone();
+                        let task_id = task.id.clone();
+                        view! {
+                            <div class="p-3 mb-3 rounded-lg bg-green-50 dark:bg-green-900/20 border border-green-200 dark:border-green-800">
+                                <p class="text-sm text-green-800 dark:text-green-200 mb-2">
+                                    {format!("All subtasks of \"{}\" are done. Complete the task?", task.name)}
+                                </p>
+                                <div class="flex space-x-2">
+                                    <button
+                                        class="px-3 py-1 text-xs bg-green-600 hover:bg-green-700 text-white rounded transition-colors"
+                                        on:click=move |_| {
+                                            task_controller_complete.parent_completion_prompt.set(None);
+                                            task_controller_complete.toggle_task_completion(task_id.clone());
+                                            task_controller_complete.select_task(None);
+                                        }
+                                    >
+                                        "✓ Complete task"
+                                    </button>
+                                    <button
+                                        class="px-3 py-1 text-xs bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-700 dark:text-gray-200 rounded transition-colors"
+                                        on:click=move |_| task_controller_dismiss.parent_completion_prompt.set(None)
+                                    >
+                                        "Not yet"
+                                    </button>
+                                </div>
+                            </div>
+                        }.into_any()
+                    } else {
+                        view! { <div></div> }.into_any()
+                    }
+                }
+            }
+
             // Task selection dropdown
             {
                 let task_controller_dropdown = task_controller.clone();
@@ -79,10 +155,40 @@ pub fn TaskSelector(task_controller: TaskController) -> impl IntoView {
                     if show_task_selection.get() {
                         view! {
                             <div class="border rounded-lg bg-white dark:bg-gray-800 border-gray-200 dark:border-gray-600 max-h-64 overflow-y-auto">
+                                // Project filter
+                                {
+                                    let task_controller_projects = task_controller_dropdown.clone();
+                                    move || {
+                                        let projects = task_controller_projects.projects.get();
+                                        if projects.is_empty() {
+                                            view! { <div></div> }.into_any()
+                                        } else {
+                                            view! {
+                                                <div class="p-2 border-b border-gray-200 dark:border-gray-600">
+                                                    <select
+                                                        class="w-full px-2 py-1 border rounded text-sm bg-white dark:bg-gray-700 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                                                        on:change=move |ev| project_filter.set(ProjectFilter::from_value(&event_target_value(&ev)))
+                                                    >
+                                                        <ProjectFilterOptions
+                                                            projects=projects
+                                                            selected=project_filter.get_untracked().to_value()
+                                                        />
+                                                    </select>
+                                                </div>
+                                            }.into_any()
+                                        }
+                                    }
+                                }
+
                                 {
                                     let task_controller_list = task_controller_dropdown.clone();
                                     move || {
-                                        let filtered_tasks = task_controller_list.get_filtered_tasks();
+                                        let filter = project_filter.get();
+                                        let filtered_tasks: Vec<_> = task_controller_list
+                                            .get_filtered_tasks()
+                                            .into_iter()
+                                            .filter(|t| filter.matches(t.project_id.as_deref()))
+                                            .collect();
                                         
                                         if filtered_tasks.is_empty() {
                                             view! {
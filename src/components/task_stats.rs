@@ -1,4 +1,5 @@
 use crate::task::TaskController;
+use crate::types::FORECAST_WINDOW_DAYS;
 use crate::utils::format_duration_hours_minutes;
 use leptos::prelude::*;
 
@@ -70,6 +71,144 @@ pub fn TaskStats(task_controller: TaskController) -> impl IntoView {
                                     }
                                 }
 
+                                // Project rollup
+                                {
+                                    let project_stats = task_controller_stats.project_stats.get();
+                                    if project_stats.is_empty() {
+                                        view! { <div></div> }.into_any()
+                                    } else {
+                                        view! {
+                                            <div class="space-y-2">
+                                                <h4 class="text-md font-semibold text-gray-700 dark:text-gray-300">Projects</h4>
+                                                {project_stats.into_iter().map(|stats| {
+                                                    view! {
+                                                        <div class="flex items-center justify-between p-3 border rounded-lg bg-white dark:bg-gray-800 border-gray-200 dark:border-gray-600 text-sm">
+                                                            <div class="flex items-center space-x-3 min-w-0">
+                                                                <div
+                                                                    class="w-3 h-3 rounded-full flex-shrink-0"
+                                                                    style:background-color=stats.project.color.clone()
+                                                                ></div>
+                                                                <div class="font-medium text-gray-800 dark:text-white truncate">
+                                                                    {stats.project.name.clone()}
+                                                                    {if stats.project.archived { " (archived)" } else { "" }}
+                                                                </div>
+                                                            </div>
+                                                            <div class="flex items-center space-x-4 text-xs text-gray-600 dark:text-gray-400 flex-shrink-0">
+                                                                <span>{stats.completed_tasks} "/" {stats.tasks} " tasks"</span>
+                                                                <span>{stats.total_pomodoros} " min 🍅"</span>
+                                                                <span class="font-medium text-gray-800 dark:text-white">
+                                                                    {format_duration_hours_minutes(stats.total_focus_time)}
+                                                                </span>
+                                                            </div>
+                                                        </div>
+                                                    }
+                                                }).collect::<Vec<_>>()}
+                                            </div>
+                                        }.into_any()
+                                    }
+                                }
+
+                                // Recurring series rollup
+                                {
+                                    let series_stats = task_controller_stats.series_stats.get();
+                                    if series_stats.is_empty() {
+                                        view! { <div></div> }.into_any()
+                                    } else {
+                                        view! {
+                                            <div class="space-y-2">
+                                                <h4 class="text-md font-semibold text-gray-700 dark:text-gray-300">Recurring Series</h4>
+                                                {series_stats.into_iter().map(|series| {
+                                                    view! {
+                                                        <div class="flex items-center justify-between p-3 border rounded-lg bg-white dark:bg-gray-800 border-gray-200 dark:border-gray-600 text-sm">
+                                                            <div class="flex items-center space-x-3 min-w-0">
+                                                                <div
+                                                                    class="w-3 h-3 rounded-full flex-shrink-0"
+                                                                    style:background-color=series.color.clone()
+                                                                ></div>
+                                                                <div class="min-w-0">
+                                                                    <div class="font-medium text-gray-800 dark:text-white truncate">{series.name.clone()}</div>
+                                                                    <div class="text-xs text-gray-500 dark:text-gray-400">"🔁 " {series.recurrence.describe()}</div>
+                                                                </div>
+                                                            </div>
+                                                            <div class="flex items-center space-x-4 text-xs text-gray-600 dark:text-gray-400 flex-shrink-0">
+                                                                <span>{series.completed_instances} "/" {series.instances} " done"</span>
+                                                                <span>{series.total_pomodoros} " min 🍅"</span>
+                                                                <span class="font-medium text-gray-800 dark:text-white">
+                                                                    {format_duration_hours_minutes(series.total_focus_time)}
+                                                                </span>
+                                                            </div>
+                                                        </div>
+                                                    }
+                                                }).collect::<Vec<_>>()}
+                                            </div>
+                                        }.into_any()
+                                    }
+                                }
+
+                                // Remaining effort forecasts for open tasks
+                                {
+                                    match task_controller_stats.forecasts.get() {
+                                        Some((accuracy, forecasts)) if !forecasts.is_empty() => view! {
+                                            <div class="space-y-2">
+                                                <div class="flex items-baseline justify-between">
+                                                    <h4 class="text-md font-semibold text-gray-700 dark:text-gray-300">Forecast</h4>
+                                                    <span class="text-xs text-gray-500 dark:text-gray-400">
+                                                        {if accuracy.samples > 0 {
+                                                            format!(
+                                                                "Estimates usually take {:.1}× (range {:.1}–{:.1}×, {} finished items)",
+                                                                accuracy.typical, accuracy.low, accuracy.high, accuracy.samples
+                                                            )
+                                                        } else {
+                                                            "Not enough finished estimates yet, assuming estimates hold".to_string()
+                                                        }}
+                                                    </span>
+                                                </div>
+                                                {forecasts.into_iter().map(|forecast| {
+                                                    let completion = match &forecast.completion_date {
+                                                        Some(date) => format!("~{}", date),
+                                                        None => "no recent work".to_string(),
+                                                    };
+                                                    view! {
+                                                        <div class="flex items-center justify-between p-3 border rounded-lg bg-white dark:bg-gray-800 border-gray-200 dark:border-gray-600 text-sm">
+                                                            <div class="flex items-center space-x-3 min-w-0">
+                                                                <div
+                                                                    class="w-3 h-3 rounded-full flex-shrink-0"
+                                                                    style:background-color=forecast.task.color.clone()
+                                                                ></div>
+                                                                <div class="font-medium text-gray-800 dark:text-white truncate">
+                                                                    {forecast.task.name.clone()}
+                                                                </div>
+                                                                {forecast.over_budget.then(|| view! {
+                                                                    <span class="text-xs bg-red-100 dark:bg-red-900/40 text-red-700 dark:text-red-300 px-2 py-0.5 rounded flex-shrink-0">
+                                                                        "Over budget"
+                                                                    </span>
+                                                                })}
+                                                            </div>
+                                                            <div class="flex items-center space-x-4 text-xs text-gray-600 dark:text-gray-400 flex-shrink-0">
+                                                                <span title="Completed sessions so far">
+                                                                    {forecast.spent}
+                                                                    {forecast.budget.map(|budget| format!(" / {}", budget))}
+                                                                    " done"
+                                                                </span>
+                                                                <span class="font-medium text-gray-800 dark:text-white" title="Sessions still needed">
+                                                                    {format!(
+                                                                        "~{} left ({}–{})",
+                                                                        forecast.remaining, forecast.remaining_low, forecast.remaining_high
+                                                                    )}
+                                                                </span>
+                                                                <span title=format!("{:.1} sessions/day over the last {} days", forecast.daily_rate, FORECAST_WINDOW_DAYS)>
+                                                                    "📅 " {completion}
+                                                                </span>
+                                                            </div>
+                                                        </div>
+                                                    }
+                                                }).collect::<Vec<_>>()}
+                                            </div>
+                                        }.into_any(),
+                                        _ => view! { <div></div> }.into_any(),
+                                    }
+                                }
+
                                 // Individual Task Stats
                                 <div class="space-y-4">
                                     <h4 class="text-md font-semibold text-gray-700 dark:text-gray-300">Individual Tasks</h4>
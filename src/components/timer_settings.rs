@@ -11,6 +11,7 @@ pub fn TimerSettings(controller: TimerController) -> impl IntoView {
     let short_break_error = RwSignal::new(None::<String>);
     let long_break_error = RwSignal::new(None::<String>);
     let sessions_error = RwSignal::new(None::<String>);
+    let workday_error = RwSignal::new(None::<String>);
 
     // Function to save settings with validation
     let save_settings = {
@@ -21,6 +22,7 @@ pub fn TimerSettings(controller: TimerController) -> impl IntoView {
             short_break_error.set(None);
             long_break_error.set(None);
             sessions_error.set(None);
+            workday_error.set(None);
 
             // Validate settings
             let mut is_valid = true;
@@ -50,6 +52,16 @@ pub fn TimerSettings(controller: TimerController) -> impl IntoView {
                 is_valid = false;
             }
 
+            if new_settings.workday_end_hour < 1 || new_settings.workday_end_hour > 24 {
+                workday_error.set(Some("Workday end must be between 1-24".to_string()));
+                is_valid = false;
+            }
+
+            if new_settings.day_start_hour > 23 {
+                workday_error.set(Some("Day start must be between 0-23".to_string()));
+                is_valid = false;
+            }
+
             if new_settings.sessions_before_long_break <= new_settings.sessions_before_short_break {
                 sessions_error.set(Some("Long break interval must be greater than short break interval".to_string()));
                 is_valid = false;
@@ -322,6 +334,100 @@ pub fn TimerSettings(controller: TimerController) -> impl IntoView {
                             }
                         />
                     </div>
+
+                    // Auto-advance subtasks
+                    <div class="flex items-center justify-between">
+                        <div>
+                            <span class="text-sm font-medium text-gray-600 dark:text-gray-400">
+                                "Auto-advance subtasks"
+                            </span>
+                            <p class="text-xs text-gray-500 dark:text-gray-400">
+                                "Select the next open subtask once the current one is done"
+                            </p>
+                        </div>
+                        <input
+                            type="checkbox"
+                            class="rounded border-gray-300 text-blue-600 focus:ring-blue-500"
+                            checked=move || settings.get().auto_advance_subtasks
+                            on:change={
+                                let save_settings = save_settings.clone();
+                                move |ev| {
+                                    let mut current_settings = settings.get();
+                                    current_settings.auto_advance_subtasks = event_target_checked(&ev);
+                                    save_settings(current_settings);
+                                }
+                            }
+                        />
+                    </div>
+                </div>
+            </div>
+
+            // Daily Planning
+            <div class="space-y-4 p-4 bg-gray-50 dark:bg-gray-700 rounded-lg">
+                <h5 class="text-md font-medium text-gray-700 dark:text-gray-300">
+                    "Daily Planning"
+                </h5>
+
+                <div>
+                    <label class="block text-sm font-medium text-gray-600 dark:text-gray-400 mb-1">
+                        "Workday ends at (hour)"
+                    </label>
+                    <input
+                        type="number"
+                        min="1"
+                        max="24"
+                        class="w-full md:w-1/3 px-3 py-2 border rounded-md bg-white dark:bg-gray-800 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        class:border-red-500=move || workday_error.get().is_some()
+                        value=move || settings.get().workday_end_hour
+                        on:input={
+                            let save_settings = save_settings.clone();
+                            move |ev| {
+                                if let Ok(value) = event_target_value(&ev).parse::<u32>() {
+                                    let mut current_settings = settings.get();
+                                    current_settings.workday_end_hour = value;
+                                    save_settings(current_settings);
+                                }
+                            }
+                        }
+                    />
+                    {move || {
+                        if let Some(error) = workday_error.get() {
+                            view! {
+                                <p class="text-red-500 text-xs mt-1">{error}</p>
+                            }.into_any()
+                        } else {
+                            view! { <div></div> }.into_any()
+                        }
+                    }}
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                        "Used by the Today view to estimate how many pomodoros still fit in the day"
+                    </p>
+                </div>
+
+                <div>
+                    <label class="block text-sm font-medium text-gray-600 dark:text-gray-400 mb-1">
+                        "Day starts at (hour)"
+                    </label>
+                    <input
+                        type="number"
+                        min="0"
+                        max="23"
+                        class="w-full md:w-1/3 px-3 py-2 border rounded-md bg-white dark:bg-gray-800 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                        value=move || settings.get().day_start_hour
+                        on:input={
+                            let save_settings = save_settings.clone();
+                            move |ev| {
+                                if let Ok(value) = event_target_value(&ev).parse::<u32>() {
+                                    let mut current_settings = settings.get();
+                                    current_settings.day_start_hour = value;
+                                    save_settings(current_settings);
+                                }
+                            }
+                        }
+                    />
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                        "Work before this hour counts toward the previous day in stats, goals and the calendar (local time)"
+                    </p>
                 </div>
             </div>
 
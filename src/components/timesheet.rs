@@ -0,0 +1,259 @@
+use crate::console_log;
+use crate::reports::{export_timesheet, open_report, TimesheetFiles};
+use crate::task::TaskController;
+use crate::timer::TimerController;
+use crate::types::{build_timesheet, RoundingMode, Timesheet, TimesheetSettings, TIMESHEET_ROUNDING_OPTIONS};
+use crate::utils::{format_duration_hours_minutes, get_today_date_string};
+use leptos::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+
+const FIELD_CLASS: &str = "px-2 py-1 border rounded text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300";
+
+fn update_timesheet_settings(settings: RwSignal<TimesheetSettings>, change: impl FnOnce(&mut TimesheetSettings)) {
+    let mut updated = settings.get_untracked();
+    change(&mut updated);
+    updated.save_to_storage();
+    settings.set(updated);
+}
+
+fn format_amount(amount: Option<f64>, currency: &str) -> String {
+    match amount {
+        Some(amount) => format!("{:.2} {}", amount, currency),
+        None => "–".to_string(),
+    }
+}
+
+fn open_file(path: String) {
+    spawn_local(async move {
+        if let Err(e) = open_report(&path).await {
+            console_log!("Failed to open timesheet {}: {}", path, e);
+        }
+    });
+}
+
+// Billable time per task and day for a date range, exported as CSV or an HTML invoice
+#[component]
+pub fn TimesheetPanel(task_controller: TaskController, timer_controller: TimerController) -> impl IntoView {
+    let today = get_today_date_string();
+    let from = RwSignal::new(format!("{}01", today.get(..8).unwrap_or_default()));
+    let to = RwSignal::new(today);
+    let settings = RwSignal::new(TimesheetSettings::load_from_storage());
+    let timesheet = RwSignal::new(None::<Timesheet>);
+    let exporting = RwSignal::new(false);
+    let files = RwSignal::new(None::<TimesheetFiles>);
+    let error = RwSignal::new(None::<String>);
+
+    let session_stats = timer_controller.session_stats;
+    let tasks = task_controller.tasks;
+    let projects = task_controller.projects;
+    let history = task_controller.history;
+
+    Effect::new(move |_| {
+        let from = from.get();
+        let to = to.get();
+        let settings = settings.get();
+        session_stats.track();
+        tasks.track();
+        projects.track();
+        history.revision.track();
+        spawn_local(async move {
+            match build_timesheet(&from, &to, &settings).await {
+                Ok(data) => {
+                    error.set(None);
+                    timesheet.set(Some(data));
+                }
+                Err(e) => {
+                    error.set(Some(e));
+                    timesheet.set(None);
+                }
+            }
+        });
+    });
+
+    let export = move |_| {
+        let Some(data) = timesheet.get_untracked() else {
+            return;
+        };
+        exporting.set(true);
+        spawn_local(async move {
+            match export_timesheet(&data).await {
+                Ok(written) => {
+                    console_log!("🧾 Timesheet written to {}", written.csv_path);
+                    files.set(Some(written));
+                }
+                Err(e) => error.set(Some(e)),
+            }
+            exporting.set(false);
+        });
+    };
+
+    view! {
+        <div class="mt-8 p-4 bg-gray-50 dark:bg-gray-700 rounded-lg">
+            <h3 class="text-lg font-semibold text-gray-800 dark:text-white mb-3">"Timesheet"</h3>
+
+            <div class="flex flex-wrap items-end gap-3">
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"From"</label>
+                    <input
+                        type="date"
+                        class=FIELD_CLASS
+                        prop:value=move || from.get()
+                        on:change=move |ev| from.set(event_target_value(&ev))
+                    />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"To"</label>
+                    <input
+                        type="date"
+                        class=FIELD_CLASS
+                        prop:value=move || to.get()
+                        on:change=move |ev| to.set(event_target_value(&ev))
+                    />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Rounding"</label>
+                    <select
+                        class=FIELD_CLASS
+                        on:change=move |ev| {
+                            if let Ok(minutes) = event_target_value(&ev).parse() {
+                                update_timesheet_settings(settings, |s| s.rounding_minutes = minutes);
+                            }
+                        }
+                    >
+                        {TIMESHEET_ROUNDING_OPTIONS.into_iter().map(|minutes| view! {
+                            <option value=minutes.to_string() selected=move || settings.get().rounding_minutes == minutes>
+                                {if minutes == 0 { "Exact".to_string() } else { format!("{} min", minutes) }}
+                            </option>
+                        }).collect_view()}
+                    </select>
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Round"</label>
+                    <select
+                        class=FIELD_CLASS
+                        on:change=move |ev| {
+                            let mode = if event_target_value(&ev) == "Nearest" { RoundingMode::Nearest } else { RoundingMode::Up };
+                            update_timesheet_settings(settings, |s| s.rounding_mode = mode);
+                        }
+                    >
+                        <option value="Up" selected=move || settings.get().rounding_mode == RoundingMode::Up>"Up"</option>
+                        <option value="Nearest" selected=move || settings.get().rounding_mode == RoundingMode::Nearest>"To nearest"</option>
+                    </select>
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Currency"</label>
+                    <input
+                        type="text"
+                        class=format!("{} w-20", FIELD_CLASS)
+                        prop:value=move || settings.get().currency
+                        on:change=move |ev| {
+                            let currency = event_target_value(&ev).trim().to_string();
+                            update_timesheet_settings(settings, |s| s.currency = currency);
+                        }
+                    />
+                </div>
+                <button
+                    class="px-3 py-1 text-sm bg-blue-600 hover:bg-blue-700 text-white rounded transition-colors disabled:opacity-50"
+                    disabled=move || exporting.get() || timesheet.get().is_none_or(|t| t.entries.is_empty())
+                    on:click=export
+                >
+                    {move || if exporting.get() { "Exporting..." } else { "🧾 Export CSV & HTML" }}
+                </button>
+            </div>
+
+            {move || {
+                if let Some(err) = error.get() {
+                    view! { <p class="text-red-500 text-xs mt-2">{err}</p> }.into_any()
+                } else {
+                    view! { <div></div> }.into_any()
+                }
+            }}
+
+            {move || {
+                if let Some(written) = files.get() {
+                    let csv_path = written.csv_path.clone();
+                    let html_path = written.html_path.clone();
+                    view! {
+                        <div class="flex items-center gap-2 mt-3 text-sm">
+                            <span class="text-gray-600 dark:text-gray-400">"Saved:"</span>
+                            <button
+                                class="px-2 py-1 text-xs bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-700 dark:text-gray-200 rounded transition-colors"
+                                title=written.csv_path
+                                on:click=move |_| open_file(csv_path.clone())
+                            >
+                                "Open CSV"
+                            </button>
+                            <button
+                                class="px-2 py-1 text-xs bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-700 dark:text-gray-200 rounded transition-colors"
+                                title=written.html_path
+                                on:click=move |_| open_file(html_path.clone())
+                            >
+                                "Open invoice"
+                            </button>
+                        </div>
+                    }.into_any()
+                } else {
+                    view! { <div></div> }.into_any()
+                }
+            }}
+
+            {move || {
+                let Some(data) = timesheet.get() else {
+                    return view! { <div></div> }.into_any();
+                };
+                if data.entries.is_empty() {
+                    return view! {
+                        <p class="text-sm text-gray-500 dark:text-gray-400 mt-4">"No task work in this period."</p>
+                    }.into_any();
+                }
+                let currency = data.currency.clone();
+                view! {
+                    <div class="mt-4 overflow-x-auto">
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mb-2">{data.rounding.clone()}</p>
+                        <table class="w-full text-sm text-gray-700 dark:text-gray-300">
+                            <thead>
+                                <tr class="text-left text-xs text-gray-500 dark:text-gray-400 border-b border-gray-200 dark:border-gray-600">
+                                    <th class="py-1">"Date"</th>
+                                    <th class="py-1">"Task"</th>
+                                    <th class="py-1 text-right">"Tracked"</th>
+                                    <th class="py-1 text-right">"Billed"</th>
+                                    <th class="py-1 text-right">"Amount"</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {data.tasks.iter().map(|task| {
+                                    let rows = data.entries.iter().filter(|e| e.task_id == task.task_id).map(|entry| view! {
+                                        <tr class="border-b border-gray-100 dark:border-gray-600">
+                                            <td class="py-1">{entry.date.clone()}</td>
+                                            <td class="py-1">{entry.name.clone()}</td>
+                                            <td class="py-1 text-right">{format_duration_hours_minutes(entry.focus_time)}</td>
+                                            <td class="py-1 text-right">{format!("{:.2} h", entry.billed_time as f64 / 3600.0)}</td>
+                                            <td class="py-1 text-right">{format_amount(entry.amount, &currency)}</td>
+                                        </tr>
+                                    }).collect_view();
+                                    view! {
+                                        {rows}
+                                        <tr class="font-medium border-b border-gray-200 dark:border-gray-600">
+                                            <td class="py-1"></td>
+                                            <td class="py-1">{format!("{} total", task.name)}</td>
+                                            <td class="py-1 text-right">{format_duration_hours_minutes(task.focus_time)}</td>
+                                            <td class="py-1 text-right">{format!("{:.2} h", task.billed_time as f64 / 3600.0)}</td>
+                                            <td class="py-1 text-right">{format_amount(Some(task.amount), &currency)}</td>
+                                        </tr>
+                                    }
+                                }).collect_view()}
+                                <tr class="font-semibold text-gray-800 dark:text-white">
+                                    <td class="py-2"></td>
+                                    <td class="py-2">"Total"</td>
+                                    <td class="py-2 text-right">{format_duration_hours_minutes(data.total_focus_time)}</td>
+                                    <td class="py-2 text-right">{format!("{:.2} h", data.total_billed_time as f64 / 3600.0)}</td>
+                                    <td class="py-2 text-right">{format_amount(Some(data.total_amount), &currency)}</td>
+                                </tr>
+                            </tbody>
+                        </table>
+                    </div>
+                }.into_any()
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,230 @@
+use crate::task::TaskController;
+use crate::timer::TimerController;
+use crate::utils::{get_today_date_string, minutes_until_hour};
+use leptos::prelude::*;
+
+#[component]
+pub fn TodayPlanner(task_controller: TaskController, timer_controller: TimerController) -> impl IntoView {
+    view! {
+        <div class="today-planner">
+            <div class="flex justify-between items-center mb-4">
+                <h3 class="text-lg font-semibold text-gray-800 dark:text-white">"Today"</h3>
+                <span class="text-sm text-gray-500 dark:text-gray-400">{get_today_date_string()}</span>
+            </div>
+
+            // Capacity summary
+            {
+                let task_controller_capacity = task_controller.clone();
+                let timer_controller_capacity = timer_controller.clone();
+                move || {
+                    let settings = timer_controller_capacity.timer_settings.get();
+                    let planned: u32 = task_controller_capacity
+                        .get_today_tasks()
+                        .iter()
+                        .map(|task| task_controller_capacity.get_remaining_pomodoros(task, &settings))
+                        .sum();
+                    let capacity = settings.pomodoro_capacity(minutes_until_hour(settings.workday_end_hour));
+                    let over_capacity = planned > capacity;
+                    let fill_percentage = if capacity > 0 {
+                        (planned as f64 / capacity as f64 * 100.0).min(100.0)
+                    } else if planned > 0 {
+                        100.0
+                    } else {
+                        0.0
+                    };
+
+                    view! {
+                        <div class=format!("mb-6 p-4 rounded-lg border {}",
+                            if over_capacity {
+                                "bg-red-50 dark:bg-red-900/20 border-red-200 dark:border-red-800"
+                            } else {
+                                "bg-blue-50 dark:bg-blue-900/20 border-blue-200 dark:border-blue-800"
+                            })>
+                            <div class="flex justify-between items-center text-sm mb-2">
+                                <span class="text-gray-700 dark:text-gray-300">
+                                    "Planned: " <span class="font-bold">{planned}</span> " 🍅"
+                                </span>
+                                <span class="text-gray-700 dark:text-gray-300">
+                                    "Capacity left: " <span class="font-bold">{capacity}</span> " 🍅"
+                                    <span class="text-xs text-gray-500 dark:text-gray-400">
+                                        {format!(" (until {:02}:00)", settings.workday_end_hour)}
+                                    </span>
+                                </span>
+                            </div>
+                            <div class="bg-gray-200 dark:bg-gray-600 rounded-full h-2">
+                                <div
+                                    class=format!("h-2 rounded-full transition-all duration-300 {}",
+                                        if over_capacity { "bg-red-500" } else { "bg-blue-500" })
+                                    style:width=format!("{}%", fill_percentage)
+                                ></div>
+                            </div>
+                            {if over_capacity {
+                                view! {
+                                    <p class="text-xs text-red-600 dark:text-red-400 mt-2">
+                                        {format!("⚠️ {} pomodoro(s) more than fit in the rest of the day", planned - capacity)}
+                                    </p>
+                                }.into_any()
+                            } else {
+                                view! { <div></div> }.into_any()
+                            }}
+                        </div>
+                    }
+                }
+            }
+
+            // Today's tasks
+            {
+                let task_controller_list = task_controller.clone();
+                let timer_controller_list = timer_controller.clone();
+                move || {
+                    let today = get_today_date_string();
+                    let settings = timer_controller_list.timer_settings.get();
+                    let today_tasks = task_controller_list.get_today_tasks();
+
+                    if today_tasks.is_empty() {
+                        view! {
+                            <div class="text-center py-8 text-gray-500 dark:text-gray-400">
+                                "Nothing planned for today. Plan a task below or give tasks a due date."
+                            </div>
+                        }.into_any()
+                    } else {
+                        view! {
+                            <div class="space-y-2 mb-6">
+                                {today_tasks.into_iter().map(|task| {
+                                    let remaining = task_controller_list.get_remaining_pomodoros(&task, &settings);
+                                    let overdue = task.is_overdue(&today);
+                                    let planned = task.is_planned_for(&today);
+                                    let task_id = task.id.clone();
+                                    let task_for_select = task.clone();
+                                    let controller_select = task_controller_list.clone();
+                                    let controller_plan = task_controller_list.clone();
+                                    let controller_done = task_controller_list.clone();
+
+                                    view! {
+                                        <div class=format!("flex items-center justify-between p-3 rounded-lg border {}",
+                                            if overdue {
+                                                "border-red-300 dark:border-red-700 bg-red-50 dark:bg-red-900/20"
+                                            } else {
+                                                "border-gray-200 dark:border-gray-600 bg-white dark:bg-gray-800"
+                                            })>
+                                            <div class="flex items-center space-x-3 min-w-0">
+                                                <div
+                                                    class="w-3 h-3 rounded-full flex-shrink-0"
+                                                    style:background-color=task.color.clone()
+                                                ></div>
+                                                <div class="min-w-0">
+                                                    <div class="text-sm font-medium text-gray-800 dark:text-white truncate">
+                                                        {task.name.clone()}
+                                                    </div>
+                                                    <div class="flex items-center space-x-2 text-xs mt-1">
+                                                        <span class=format!("px-2 py-0.5 rounded {}", task.priority.badge_class())>
+                                                            {task.priority.name()}
+                                                        </span>
+                                                        {task.due_date.clone().map(|due| {
+                                                            view! {
+                                                                <span class=if overdue {
+                                                                    "text-red-600 dark:text-red-400 font-medium"
+                                                                } else {
+                                                                    "text-gray-500 dark:text-gray-400"
+                                                                }>
+                                                                    {if overdue { format!("Overdue · due {}", due) } else { format!("Due {}", due) }}
+                                                                </span>
+                                                            }
+                                                        })}
+                                                        <span class="text-gray-500 dark:text-gray-400">
+                                                            {remaining} " 🍅 left"
+                                                        </span>
+                                                    </div>
+                                                </div>
+                                            </div>
+
+                                            <div class="flex space-x-2 flex-shrink-0">
+                                                <button
+                                                    class="text-xs px-2 py-1 bg-blue-500 hover:bg-blue-600 text-white rounded transition-colors"
+                                                    on:click=move |_| controller_select.select_task(Some(task_for_select.clone()))
+                                                    title="Select as active task"
+                                                >
+                                                    "Focus"
+                                                </button>
+                                                {if planned {
+                                                    let task_id_plan = task_id.clone();
+                                                    view! {
+                                                        <button
+                                                            class="text-xs px-2 py-1 bg-gray-500 hover:bg-gray-600 text-white rounded transition-colors"
+                                                            on:click=move |_| controller_plan.toggle_task_planned_today(task_id_plan.clone())
+                                                            title="Remove from today's plan"
+                                                        >
+                                                            "Unplan"
+                                                        </button>
+                                                    }.into_any()
+                                                } else {
+                                                    view! { <div></div> }.into_any()
+                                                }}
+                                                <button
+                                                    class="text-xs px-2 py-1 bg-green-500 hover:bg-green-600 text-white rounded transition-colors"
+                                                    on:click={
+                                                        let task_id_done = task_id.clone();
+                                                        move |_| controller_done.toggle_task_completion(task_id_done.clone())
+                                                    }
+                                                >
+                                                    "Done"
+                                                </button>
+                                            </div>
+                                        </div>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </div>
+                        }.into_any()
+                    }
+                }
+            }
+
+            // Plan another open task for today
+            {
+                let task_controller_add = task_controller.clone();
+                move || {
+                    let today = get_today_date_string();
+                    let today_ids: Vec<String> = task_controller_add
+                        .get_today_tasks()
+                        .into_iter()
+                        .map(|t| t.id)
+                        .collect();
+                    let candidates: Vec<_> = task_controller_add
+                        .tasks
+                        .get()
+                        .into_iter()
+                        .filter(|t| !t.completed && !t.archived && !t.is_planned_for(&today) && !today_ids.contains(&t.id))
+                        .collect();
+
+                    if candidates.is_empty() {
+                        view! { <div></div> }.into_any()
+                    } else {
+                        let controller_plan = task_controller_add.clone();
+                        view! {
+                            <div class="flex items-center space-x-2">
+                                <select
+                                    class="flex-grow px-3 py-2 border rounded text-sm bg-white dark:bg-gray-800 border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                                    on:change=move |ev| {
+                                        let task_id = event_target_value(&ev);
+                                        if !task_id.is_empty() {
+                                            controller_plan.toggle_task_planned_today(task_id);
+                                        }
+                                    }
+                                >
+                                    <option value="" selected=true>"＋ Plan a task for today..."</option>
+                                    {candidates.into_iter().map(|task| {
+                                        view! {
+                                            <option value=task.id.clone()>
+                                                {format!("{} ({})", task.name, task.priority.name())}
+                                            </option>
+                                        }
+                                    }).collect::<Vec<_>>()}
+                                </select>
+                            </div>
+                        }.into_any()
+                    }
+                }
+            }
+        </div>
+    }
+}
@@ -0,0 +1,122 @@
+use crate::console_log;
+use crate::history::ToastAction;
+use crate::task::TaskController;
+use crate::timer::TimerController;
+use leptos::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::KeyboardEvent;
+
+const TOAST_SECONDS: u64 = 6;
+
+// Toast with an Undo/Redo action after each recorded change, plus Ctrl+Z / Ctrl+Shift+Z / Ctrl+Y
+#[component]
+pub fn UndoToast(task_controller: TaskController, timer_controller: TimerController) -> impl IntoView {
+    let history = task_controller.history;
+
+    let run = move |action: ToastAction| {
+        let result = match action {
+            ToastAction::Undo => history.undo(),
+            ToastAction::Redo => history.redo(),
+        };
+        match result {
+            Ok(Some(entry)) => {
+                console_log!("↩️ {:?}: {}", action, entry.label);
+                task_controller.load_tasks();
+                task_controller.load_task_stats();
+                timer_controller.load_session_stats();
+                task_controller.write_back_task_completions(entry.tasks_with_changed_field("completed"));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                console_log!("Error applying {:?}: {}", action, e);
+                task_controller.error.set(Some(e));
+            }
+        }
+    };
+
+    let _ = window_event_listener(leptos::ev::keydown, {
+        let run = run.clone();
+        move |event: KeyboardEvent| {
+            if !(event.ctrl_key() || event.meta_key()) {
+                return;
+            }
+
+            // Text fields keep their own undo
+            if let Some(element) = event
+                .target()
+                .and_then(|t| t.dyn_into::<web_sys::HtmlElement>().ok())
+            {
+                let tag_name = element.tag_name().to_lowercase();
+                if tag_name == "input" || tag_name == "textarea" || element.is_content_editable() {
+                    return;
+                }
+            }
+
+            match event.key().to_lowercase().as_str() {
+                "z" if event.shift_key() => {
+                    event.prevent_default();
+                    run(ToastAction::Redo);
+                }
+                "z" => {
+                    event.prevent_default();
+                    run(ToastAction::Undo);
+                }
+                "y" => {
+                    event.prevent_default();
+                    run(ToastAction::Redo);
+                }
+                _ => {}
+            }
+        }
+    });
+
+    // Hide each toast after a few seconds unless a newer one replaced it
+    Effect::new(move |_| {
+        if let Some(toast) = history.toast.get() {
+            spawn_local(async move {
+                gloo_timers::future::sleep(std::time::Duration::from_secs(TOAST_SECONDS)).await;
+                if history.toast.get_untracked().is_some_and(|t| t.id == toast.id) {
+                    history.toast.set(None);
+                }
+            });
+        }
+    });
+
+    view! {
+        {move || {
+            if let Some(toast) = history.toast.get() {
+                let run = run.clone();
+                let action = toast.action;
+                let available = match action {
+                    ToastAction::Undo => history.can_undo(),
+                    ToastAction::Redo => history.can_redo(),
+                };
+                view! {
+                    <div class="fixed bottom-4 left-1/2 -translate-x-1/2 z-50 flex items-center space-x-4 px-4 py-3 bg-gray-800 dark:bg-gray-700 text-white text-sm rounded-lg shadow-lg">
+                        <span>{toast.message}</span>
+                        {available.then(|| view! {
+                            <button
+                                class="font-medium text-blue-300 hover:text-blue-200 transition-colors"
+                                on:click=move |_| run(action)
+                            >
+                                {match action {
+                                    ToastAction::Undo => "Undo",
+                                    ToastAction::Redo => "Redo",
+                                }}
+                            </button>
+                        })}
+                        <button
+                            class="text-gray-400 hover:text-gray-200 transition-colors"
+                            on:click=move |_| history.toast.set(None)
+                        >
+                            "✕"
+                        </button>
+                    </div>
+                }.into_any()
+            } else {
+                view! { <div></div> }.into_any()
+            }
+        }}
+    }
+}
@@ -0,0 +1,108 @@
+use crate::console_log;
+use crate::reports::{open_report, ReportScheduler};
+use crate::utils::{add_days_to_date_key, get_today_date_string, week_start_of_date_key};
+use leptos::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+
+const WEEKS_TO_OFFER: i64 = 8;
+
+fn open_file(path: String) {
+    spawn_local(async move {
+        match open_report(&path).await {
+            Ok(message) => console_log!("Report opened: {}", message),
+            Err(e) => console_log!("Failed to open report {}: {}", path, e),
+        }
+    });
+}
+
+// Generates the Markdown/HTML weekly report on demand and toggles the Monday schedule
+#[component]
+pub fn WeeklyReportPanel(scheduler: ReportScheduler) -> impl IntoView {
+    let this_week = week_start_of_date_key(&get_today_date_string()).unwrap_or_default();
+    let weeks: Vec<String> = (0..WEEKS_TO_OFFER)
+        .filter_map(|i| add_days_to_date_key(&this_week, -7 * i))
+        .collect();
+    let selected_week = RwSignal::new(weeks.get(1).cloned().unwrap_or_default());
+
+    view! {
+        <div class="mt-8 p-4 bg-gray-50 dark:bg-gray-700 rounded-lg">
+            <h3 class="text-lg font-semibold text-gray-800 dark:text-white mb-3">"Weekly Report"</h3>
+
+            <div class="flex flex-wrap items-center gap-3">
+                <select
+                    class="px-3 py-1 border rounded text-sm bg-white dark:bg-gray-800 dark:border-gray-600 text-gray-700 dark:text-gray-300"
+                    on:change=move |ev| selected_week.set(event_target_value(&ev))
+                >
+                    {weeks.into_iter().enumerate().map(|(i, week)| {
+                        let label = match i {
+                            0 => format!("This week ({})", week),
+                            1 => format!("Last week ({})", week),
+                            _ => format!("Week of {}", week),
+                        };
+                        let value = week.clone();
+                        view! {
+                            <option value=week selected=move || selected_week.get() == value>{label}</option>
+                        }
+                    }).collect_view()}
+                </select>
+                <button
+                    class="px-3 py-1 text-sm bg-blue-600 hover:bg-blue-700 text-white rounded transition-colors disabled:opacity-50"
+                    disabled=move || scheduler.generating.get()
+                    on:click=move |_| scheduler.generate_now(selected_week.get_untracked())
+                >
+                    {move || if scheduler.generating.get() { "Generating..." } else { "📊 Generate report" }}
+                </button>
+                <label class="flex items-center space-x-2 text-sm text-gray-600 dark:text-gray-400">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || scheduler.settings.get().auto_generate
+                        on:change=move |ev| {
+                            let mut settings = scheduler.settings.get_untracked();
+                            settings.auto_generate = event_target_checked(&ev);
+                            scheduler.update_settings(settings);
+                        }
+                    />
+                    <span>"Generate last week's report automatically every Monday"</span>
+                </label>
+            </div>
+
+            {move || {
+                if let Some(err) = scheduler.error.get() {
+                    view! {
+                        <p class="text-red-500 text-xs mt-2">{err}</p>
+                    }.into_any()
+                } else {
+                    view! { <div></div> }.into_any()
+                }
+            }}
+
+            {move || {
+                if let Some(files) = scheduler.last_files.get() {
+                    let markdown_path = files.markdown_path.clone();
+                    let html_path = files.html_path.clone();
+                    view! {
+                        <div class="flex items-center gap-2 mt-3 text-sm">
+                            <span class="text-gray-600 dark:text-gray-400">"Saved:"</span>
+                            <button
+                                class="px-2 py-1 text-xs bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-700 dark:text-gray-200 rounded transition-colors"
+                                title=files.markdown_path
+                                on:click=move |_| open_file(markdown_path.clone())
+                            >
+                                "Open Markdown"
+                            </button>
+                            <button
+                                class="px-2 py-1 text-xs bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-700 dark:text-gray-200 rounded transition-colors"
+                                title=files.html_path
+                                on:click=move |_| open_file(html_path.clone())
+                            >
+                                "Open HTML"
+                            </button>
+                        </div>
+                    }.into_any()
+                } else {
+                    view! { <div></div> }.into_any()
+                }
+            }}
+        </div>
+    }
+}
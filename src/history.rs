@@ -0,0 +1,449 @@
+// Undo/redo for changes to tasks, subtasks and sessions.
+// An entry keeps every record an operation touched, keyed by id. Cascades such as a task delete
+// that also removes subtasks and detaches sessions undo in one step. For records that exist on
+// both sides only the changed fields are kept, so undo leaves later edits to other fields alone.
+
+use leptos::prelude::*;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use web_sys::{window, Storage};
+
+const TRACKED_KEYS: [&str; 3] = ["pomodoro_tasks", "pomodoro_subtasks", "pomodoro_sessions"];
+const HISTORY_LIMIT: usize = 50;
+
+// Field name, value before and value after (None when the field was absent)
+type FieldChange = (String, Option<Value>, Option<Value>);
+
+#[derive(Debug, Clone, PartialEq)]
+enum RecordChange {
+    // An edited record
+    Fields { id: String, fields: Vec<FieldChange> },
+    // A created or removed record with its position in the stored list
+    Record {
+        id: String,
+        before: Option<(usize, Value)>,
+        after: Option<(usize, Value)>,
+    },
+}
+
+impl RecordChange {
+    fn id(&self) -> &str {
+        match self {
+            RecordChange::Fields { id, .. } | RecordChange::Record { id, .. } => id,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub label: String,
+    changes: Vec<(&'static str, Vec<RecordChange>)>,
+}
+
+impl HistoryEntry {
+    // Ids of existing tasks whose `field` this entry changed
+    pub fn tasks_with_changed_field(&self, field: &str) -> Vec<String> {
+        self.changes
+            .iter()
+            .filter(|(key, _)| *key == "pomodoro_tasks")
+            .flat_map(|(_, changes)| changes)
+            .filter_map(|change| match change {
+                RecordChange::Fields { id, fields } if fields.iter().any(|(name, _, _)| name == field) => {
+                    Some(id.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+// Raw contents of the tracked collections before an operation
+pub struct Snapshot(Vec<(&'static str, Vec<Value>)>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToastAction {
+    Undo,
+    Redo,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryToast {
+    pub message: String,
+    pub action: ToastAction,
+    pub id: u64,
+}
+
+fn get_local_storage() -> Option<Storage> {
+    window()?.local_storage().ok()?
+}
+
+fn read_collection(storage: &Storage, key: &str) -> Vec<Value> {
+    storage
+        .get_item(key)
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn write_collection(storage: &Storage, key: &str, records: &[Value]) -> Result<(), String> {
+    let json = serde_json::to_string(records).map_err(|e| e.to_string())?;
+    storage.set_item(key, &json).map_err(|e| format!("{:?}", e))
+}
+
+fn record_id(record: &Value) -> Option<&str> {
+    record.get("id")?.as_str()
+}
+
+pub fn take_snapshot() -> Snapshot {
+    let Some(storage) = get_local_storage() else {
+        return Snapshot(Vec::new());
+    };
+    Snapshot(
+        TRACKED_KEYS
+            .iter()
+            .map(|key| (*key, read_collection(&storage, key)))
+            .collect(),
+    )
+}
+
+fn indexed_by_id(records: &[Value]) -> Vec<(&str, usize, &Value)> {
+    records
+        .iter()
+        .enumerate()
+        .filter_map(|(index, record)| Some((record_id(record)?, index, record)))
+        .collect()
+}
+
+// First position of each id, so bulk edits on thousands of records diff in linear time
+fn lookup_by_id<'a>(indexed: &[(&'a str, usize, &'a Value)]) -> HashMap<&'a str, (usize, &'a Value)> {
+    let mut lookup = HashMap::new();
+    for (id, index, record) in indexed {
+        lookup.entry(*id).or_insert((*index, *record));
+    }
+    lookup
+}
+
+// Fields whose values differ between two versions of a record; None if either is not an object
+fn changed_fields(old: &Value, new: &Value) -> Option<Vec<FieldChange>> {
+    let (old, new) = (old.as_object()?, new.as_object()?);
+    let mut fields: Vec<FieldChange> = old
+        .iter()
+        .filter(|(name, value)| new.get(*name) != Some(*value))
+        .map(|(name, value)| (name.clone(), Some(value.clone()), new.get(name).cloned()))
+        .collect();
+    fields.extend(
+        new.iter()
+            .filter(|(name, _)| !old.contains_key(*name))
+            .map(|(name, value)| (name.clone(), None, Some(value.clone()))),
+    );
+    Some(fields)
+}
+
+fn diff_records(old_records: &[Value], new_records: &[Value]) -> Vec<RecordChange> {
+    let old = indexed_by_id(old_records);
+    let new = indexed_by_id(new_records);
+    let old_lookup = lookup_by_id(&old);
+    let new_lookup = lookup_by_id(&new);
+
+    let mut changes: Vec<RecordChange> = old
+        .iter()
+        .filter_map(|(id, old_index, old_record)| match new_lookup.get(id) {
+            Some((_, new_record)) if new_record == old_record => None,
+            Some((new_index, new_record)) => Some(match changed_fields(old_record, new_record) {
+                Some(fields) => RecordChange::Fields {
+                    id: id.to_string(),
+                    fields,
+                },
+                None => RecordChange::Record {
+                    id: id.to_string(),
+                    before: Some((*old_index, (*old_record).clone())),
+                    after: Some((*new_index, (*new_record).clone())),
+                },
+            }),
+            None => Some(RecordChange::Record {
+                id: id.to_string(),
+                before: Some((*old_index, (*old_record).clone())),
+                after: None,
+            }),
+        })
+        .collect();
+    changes.extend(
+        new.iter()
+            .filter(|(id, _, _)| !old_lookup.contains_key(id))
+            .map(|(id, index, record)| RecordChange::Record {
+                id: id.to_string(),
+                before: None,
+                after: Some((*index, (*record).clone())),
+            }),
+    );
+    changes
+}
+
+// Records that differ between the snapshot and what is stored now
+fn diff_snapshot(label: String, before: Snapshot) -> Option<HistoryEntry> {
+    let storage = get_local_storage()?;
+    let changes: Vec<(&'static str, Vec<RecordChange>)> = before
+        .0
+        .into_iter()
+        .map(|(key, old_records)| (key, diff_records(&old_records, &read_collection(&storage, key))))
+        .filter(|(_, changes)| !changes.is_empty())
+        .collect();
+
+    if changes.is_empty() {
+        None
+    } else {
+        Some(HistoryEntry { label, changes })
+    }
+}
+
+// Puts the changed fields and records into the state on one side of the entry. Fields of a
+// record that was deleted since are dropped; everything else on the record stays as it is now.
+fn apply_changes(records: &mut Vec<Value>, changes: &[RecordChange], restore_before: bool) {
+    for change in changes {
+        let RecordChange::Fields { id, fields } = change else {
+            continue;
+        };
+        let Some(record) = records
+            .iter_mut()
+            .find(|r| record_id(r) == Some(id.as_str()))
+            .and_then(|r| r.as_object_mut())
+        else {
+            continue;
+        };
+        for (name, before, after) in fields {
+            let value = if restore_before { before } else { after };
+            match value {
+                Some(value) => {
+                    record.insert(name.clone(), value.clone());
+                }
+                None => {
+                    record.remove(name);
+                }
+            }
+        }
+    }
+
+    let replaced_ids: HashSet<&str> = changes
+        .iter()
+        .filter(|c| matches!(c, RecordChange::Record { .. }))
+        .map(|c| c.id())
+        .collect();
+    records.retain(|record| record_id(record).is_none_or(|id| !replaced_ids.contains(id)));
+
+    let mut targets: Vec<&(usize, Value)> = changes
+        .iter()
+        .filter_map(|c| match c {
+            RecordChange::Record { before, after, .. } => {
+                if restore_before { before.as_ref() } else { after.as_ref() }
+            }
+            RecordChange::Fields { .. } => None,
+        })
+        .collect();
+    targets.sort_by_key(|(index, _)| *index);
+    for (index, record) in targets {
+        records.insert((*index).min(records.len()), record.clone());
+    }
+}
+
+fn apply_entry(entry: &HistoryEntry, restore_before: bool) -> Result<(), String> {
+    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
+
+    for (key, changes) in &entry.changes {
+        let mut records = read_collection(&storage, key);
+        apply_changes(&mut records, changes, restore_before);
+        write_collection(&storage, key, &records)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+pub struct UndoHistory {
+    undo_stack: RwSignal<Vec<HistoryEntry>>,
+    redo_stack: RwSignal<Vec<HistoryEntry>>,
+    pub toast: RwSignal<Option<HistoryToast>>,
+    pub revision: RwSignal<u32>, // bumped whenever undo/redo rewrites stored data
+}
+
+impl UndoHistory {
+    pub fn new() -> Self {
+        Self {
+            undo_stack: RwSignal::new(Vec::new()),
+            redo_stack: RwSignal::new(Vec::new()),
+            toast: RwSignal::new(None),
+            revision: RwSignal::new(0),
+        }
+    }
+
+    // Runs a data-layer operation and, if it succeeds, records what it changed
+    pub async fn record<T>(
+        &self,
+        label: impl Into<String>,
+        operation: impl Future<Output = Result<T, String>>,
+    ) -> Result<T, String> {
+        let snapshot = take_snapshot();
+        let result = operation.await;
+        if result.is_ok() {
+            self.commit(label, snapshot);
+        }
+        result
+    }
+
+    // For operations made of several data-layer calls: snapshot first, commit when done
+    pub fn commit(&self, label: impl Into<String>, snapshot: Snapshot) {
+        let Some(entry) = diff_snapshot(label.into(), snapshot) else {
+            return;
+        };
+        self.show_toast(entry.label.clone(), ToastAction::Undo);
+        self.undo_stack.update(|stack| {
+            stack.push(entry);
+            if stack.len() > HISTORY_LIMIT {
+                stack.remove(0);
+            }
+        });
+        self.redo_stack.set(Vec::new());
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.undo_stack.with(|stack| !stack.is_empty())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.redo_stack.with(|stack| !stack.is_empty())
+    }
+
+    // Returns the undone entry, or None when there was nothing to undo
+    pub fn undo(&self) -> Result<Option<HistoryEntry>, String> {
+        let Some(entry) = self.undo_stack.try_update(|stack| stack.pop()).flatten() else {
+            return Ok(None);
+        };
+        if let Err(e) = apply_entry(&entry, true) {
+            self.undo_stack.update(|stack| stack.push(entry));
+            return Err(e);
+        }
+
+        self.redo_stack.update(|stack| stack.push(entry.clone()));
+        self.revision.update(|r| *r += 1);
+        self.show_toast(format!("Undone: {}", entry.label), ToastAction::Redo);
+        Ok(Some(entry))
+    }
+
+    pub fn redo(&self) -> Result<Option<HistoryEntry>, String> {
+        let Some(entry) = self.redo_stack.try_update(|stack| stack.pop()).flatten() else {
+            return Ok(None);
+        };
+        if let Err(e) = apply_entry(&entry, false) {
+            self.redo_stack.update(|stack| stack.push(entry));
+            return Err(e);
+        }
+
+        self.undo_stack.update(|stack| stack.push(entry.clone()));
+        self.revision.update(|r| *r += 1);
+        self.show_toast(format!("Redone: {}", entry.label), ToastAction::Undo);
+        Ok(Some(entry))
+    }
+
+    fn show_toast(&self, message: String, action: ToastAction) {
+        self.toast.set(Some(HistoryToast {
+            message,
+            action,
+            id: js_sys::Date::now() as u64,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_only_changed_fields_of_edited_records() {
+        let before = vec![json!({ "id": "a", "completed": false, "focus_time": 0 })];
+        let after = vec![json!({ "id": "a", "completed": true, "focus_time": 0 })];
+
+        assert_eq!(
+            diff_records(&before, &after),
+            vec![RecordChange::Fields {
+                id: "a".to_string(),
+                fields: vec![("completed".to_string(), Some(json!(false)), Some(json!(true)))],
+            }]
+        );
+    }
+
+    #[test]
+    fn undo_keeps_later_edits_to_other_fields() {
+        let before = vec![json!({ "id": "a", "completed": false, "focus_time": 0 })];
+        let after = vec![json!({ "id": "a", "completed": true, "focus_time": 0 })];
+        let changes = diff_records(&before, &after);
+
+        // A session finished after the toggle was recorded
+        let mut stored = vec![json!({ "id": "a", "completed": true, "focus_time": 1500 })];
+        apply_changes(&mut stored, &changes, true);
+        assert_eq!(stored, vec![json!({ "id": "a", "completed": false, "focus_time": 1500 })]);
+
+        apply_changes(&mut stored, &changes, false);
+        assert_eq!(stored, vec![json!({ "id": "a", "completed": true, "focus_time": 1500 })]);
+    }
+
+    #[test]
+    fn restores_added_and_removed_fields() {
+        let before = vec![json!({ "id": "a", "due_date": "2024-01-15" })];
+        let after = vec![json!({ "id": "a", "external": { "source": "caldav" } })];
+        let changes = diff_records(&before, &after);
+
+        let mut stored = after.clone();
+        apply_changes(&mut stored, &changes, true);
+        assert_eq!(stored, before);
+    }
+
+    #[test]
+    fn undoes_created_and_deleted_records_in_place() {
+        let before = vec![json!({ "id": "a" }), json!({ "id": "b" }), json!({ "id": "c" })];
+        let after = vec![json!({ "id": "a" }), json!({ "id": "c" }), json!({ "id": "d" })];
+        let changes = diff_records(&before, &after);
+
+        let mut stored = after.clone();
+        apply_changes(&mut stored, &changes, true);
+        assert_eq!(stored, before);
+
+        apply_changes(&mut stored, &changes, false);
+        assert_eq!(stored, after);
+    }
+
+    #[test]
+    fn skips_fields_of_records_deleted_since() {
+        let changes = diff_records(
+            &[json!({ "id": "a", "completed": false })],
+            &[json!({ "id": "a", "completed": true })],
+        );
+
+        let mut stored = vec![json!({ "id": "b" })];
+        apply_changes(&mut stored, &changes, true);
+        assert_eq!(stored, vec![json!({ "id": "b" })]);
+    }
+
+    #[test]
+    fn lists_tasks_whose_completion_changed() {
+        let entry = HistoryEntry {
+            label: "Complete task".to_string(),
+            changes: vec![
+                (
+                    "pomodoro_tasks",
+                    diff_records(
+                        &[json!({ "id": "a", "completed": false }), json!({ "id": "b", "name": "x" })],
+                        &[json!({ "id": "a", "completed": true }), json!({ "id": "b", "name": "y" }), json!({ "id": "c", "completed": false })],
+                    ),
+                ),
+                (
+                    "pomodoro_subtasks",
+                    diff_records(&[json!({ "id": "s", "completed": false })], &[json!({ "id": "s", "completed": true })]),
+                ),
+            ],
+        };
+
+        assert_eq!(entry.tasks_with_changed_field("completed"), vec!["a".to_string()]);
+    }
+}
@@ -0,0 +1,402 @@
+use crate::console_log;
+use crate::task::TaskController;
+use crate::types::{
+    find_or_create_projects, get_all_subtasks, get_all_tasks, save_all_tasks, save_task_to_db,
+    ExternalRef, NewTask, Task, TaskPriority,
+};
+use crate::utils::format_duration_hours_minutes;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{window, Storage};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
+    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueProvider {
+    GitHub,
+    GitLab,
+}
+
+impl IssueProvider {
+    // Also used as the ExternalRef source
+    pub fn source(&self) -> &'static str {
+        match self {
+            IssueProvider::GitHub => "github",
+            IssueProvider::GitLab => "gitlab",
+        }
+    }
+
+    pub fn from_source(source: &str) -> Option<Self> {
+        match source {
+            "github" => Some(IssueProvider::GitHub),
+            "gitlab" => Some(IssueProvider::GitLab),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            IssueProvider::GitHub => "GitHub",
+            IssueProvider::GitLab => "GitLab",
+        }
+    }
+
+    pub fn default_base_url(&self) -> &'static str {
+        match self {
+            IssueProvider::GitHub => "https://api.github.com",
+            IssueProvider::GitLab => "https://gitlab.com/api/v4",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueImportSettings {
+    pub enabled: bool,
+    pub provider: IssueProvider,
+    pub base_url: String, // API root; any server with the same issue API shape works
+    #[serde(default)]
+    pub token_ref: Option<String>, // the token itself is kept by the backend under this reference
+    pub post_focus_comments: bool,
+    // Plaintext token saved by older versions, moved to the backend on startup
+    #[serde(default, rename = "token", skip_serializing)]
+    pub legacy_token: Option<String>,
+}
+
+impl Default for IssueImportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: IssueProvider::GitHub,
+            base_url: IssueProvider::GitHub.default_base_url().to_string(),
+            token_ref: None,
+            post_focus_comments: false,
+            legacy_token: None,
+        }
+    }
+}
+
+impl IssueImportSettings {
+    pub fn save_to_storage(&self) {
+        if let Ok(settings_json) = serde_json::to_string(&self) {
+            if let Some(storage) = get_local_storage() {
+                let _ = storage.set_item("pomodoro_issue_import_settings", &settings_json);
+            }
+        }
+    }
+
+    pub fn load_from_storage() -> Self {
+        if let Some(storage) = get_local_storage() {
+            if let Ok(Some(settings_json)) = storage.get_item("pomodoro_issue_import_settings") {
+                if let Ok(settings) = serde_json::from_str::<IssueImportSettings>(&settings_json) {
+                    return settings;
+                }
+            }
+        }
+        Self::default()
+    }
+}
+
+fn get_local_storage() -> Option<Storage> {
+    window()?.local_storage().ok()?
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteIssue {
+    pub id: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub url: String,
+    pub open: bool,
+    pub labels: Vec<String>,
+    pub updated_at: String,
+    pub repository: Option<String>,
+    pub due_date: Option<String>,
+}
+
+fn apply_issue(task: &mut Task, issue: &RemoteIssue, project_id: Option<String>) {
+    task.name = issue.title.clone();
+    task.description = issue.body.clone();
+    task.completed = !issue.open;
+    task.due_date = issue.due_date.clone();
+    task.tags = issue.labels.clone();
+    if project_id.is_some() {
+        task.project_id = project_id;
+    }
+    if let Some(external) = task.external.as_mut() {
+        external.url = Some(issue.url.clone());
+        external.etag = Some(issue.updated_at.clone());
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IssueImportSummary {
+    pub created: u32,
+    pub updated: u32,
+}
+
+// Creates tasks for newly assigned open issues and updates tasks whose issue changed.
+// Each repository becomes a project of the same name.
+pub async fn import_remote_issues(
+    provider: IssueProvider,
+    issues: &[RemoteIssue],
+) -> Result<IssueImportSummary, String> {
+    let source = provider.source();
+
+    let mut repositories: Vec<String> = Vec::new();
+    for name in issues.iter().filter_map(|i| i.repository.as_ref()) {
+        if !repositories.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            repositories.push(name.clone());
+        }
+    }
+    let project_ids = find_or_create_projects(&repositories).await?;
+    let project_for = |issue: &RemoteIssue| {
+        issue.repository.as_ref().and_then(|name| {
+            project_ids
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, id)| id.clone())
+        })
+    };
+
+    let mut summary = IssueImportSummary::default();
+    let mut tasks = get_all_tasks().await?;
+    let mut new_issues = Vec::new();
+
+    for issue in issues {
+        let existing = tasks.iter_mut().find(|t| {
+            t.external
+                .as_ref()
+                .is_some_and(|e| e.source == source && e.id == issue.id)
+        });
+
+        match existing {
+            Some(task) => {
+                let unchanged = task.external.as_ref().and_then(|e| e.etag.as_deref()) == Some(issue.updated_at.as_str());
+                if !unchanged {
+                    apply_issue(task, issue, project_for(issue));
+                    summary.updated += 1;
+                }
+            }
+            None if issue.open => new_issues.push(issue),
+            None => {}
+        }
+    }
+
+    save_all_tasks(&tasks)?;
+
+    if new_issues.is_empty() {
+        return Ok(summary);
+    }
+
+    let mut created_ids = Vec::new();
+    for issue in &new_issues {
+        let task_id = save_task_to_db(NewTask {
+            name: issue.title.clone(),
+            description: issue.body.clone(),
+            color: Task::get_random_color(),
+            estimated_pomodoros: None,
+            due_date: issue.due_date.clone(),
+            priority: TaskPriority::default(),
+            recurrence: None,
+            project_id: project_for(issue),
+        })
+        .await?;
+        created_ids.push(task_id);
+    }
+
+    // Link the new tasks to their issues in one write
+    let mut tasks = get_all_tasks().await?;
+    for (task_id, issue) in created_ids.iter().zip(&new_issues) {
+        if let Some(task) = tasks.iter_mut().find(|t| &t.id == task_id) {
+            task.external = Some(ExternalRef {
+                source: source.to_string(),
+                id: issue.id.clone(),
+                url: None,
+                etag: None,
+            });
+            apply_issue(task, issue, None);
+        }
+    }
+    save_all_tasks(&tasks)?;
+    summary.created = created_ids.len() as u32;
+
+    Ok(summary)
+}
+
+// Hands the token to the backend, which keeps it with the server it is for
+async fn save_issue_token(
+    settings: &IssueImportSettings,
+    token: &str,
+    replaces: Option<&str>,
+) -> Result<String, String> {
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({
+        "provider": settings.provider,
+        "baseUrl": settings.base_url,
+        "token": token,
+        "replaces": replaces,
+    }))
+    .map_err(|e| e.to_string())?;
+    let result = invoke("save_issue_token", args).await;
+    serde_wasm_bindgen::from_value::<Result<String, String>>(result)
+        .map_err(|_| "Unexpected response from save_issue_token".to_string())?
+}
+
+async fn fetch_assigned_issues(settings: &IssueImportSettings) -> Result<Vec<RemoteIssue>, String> {
+    let token_ref = settings.token_ref.as_deref().ok_or("No access token saved")?;
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({ "tokenRef": token_ref }))
+        .map_err(|e| e.to_string())?;
+    let result = invoke("fetch_assigned_issues", args).await;
+    serde_wasm_bindgen::from_value::<Result<Vec<RemoteIssue>, String>>(result)
+        .map_err(|_| "Unexpected response from fetch_assigned_issues".to_string())?
+}
+
+// Posts the finished pomodoro as a comment on the task's issue, if enabled
+pub async fn post_issue_focus_comment(task_id: &str, focus_time_seconds: u32) -> Result<(), String> {
+    let settings = IssueImportSettings::load_from_storage();
+    if !settings.enabled || !settings.post_focus_comments {
+        return Ok(());
+    }
+
+    let tasks = get_all_tasks().await?;
+    let Some(task) = tasks.iter().find(|t| t.id == task_id) else {
+        return Ok(());
+    };
+    let Some(token_ref) = settings.token_ref.as_deref() else {
+        return Ok(());
+    };
+    let Some(external) = task
+        .external
+        .as_ref()
+        .filter(|e| IssueProvider::from_source(&e.source) == Some(settings.provider))
+    else {
+        return Ok(());
+    };
+
+    let subtask_focus: u32 = get_all_subtasks()
+        .await?
+        .iter()
+        .filter(|st| st.task_id == task.id)
+        .map(|st| st.total_focus_time)
+        .sum();
+    let body = format!(
+        "🍅 Focused on this issue for {} (total {}).",
+        format_duration_hours_minutes(focus_time_seconds),
+        format_duration_hours_minutes(task.total_focus_time + subtask_focus)
+    );
+
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({
+        "tokenRef": token_ref,
+        "issueId": external.id,
+        "body": body,
+    }))
+    .map_err(|e| e.to_string())?;
+    let result = invoke("post_issue_comment", args).await;
+    serde_wasm_bindgen::from_value::<Result<String, String>>(result)
+        .map_err(|_| "Unexpected response from post_issue_comment".to_string())??;
+
+    console_log!("Posted focus comment on {}", external.id);
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct IssueSync {
+    pub settings: RwSignal<IssueImportSettings>,
+    pub status: RwSignal<Option<String>>,
+    pub syncing: RwSignal<bool>,
+    task_controller: TaskController,
+}
+
+impl IssueSync {
+    pub fn new(task_controller: TaskController) -> Self {
+        let sync = Self {
+            settings: RwSignal::new(IssueImportSettings::load_from_storage()),
+            status: RwSignal::new(None),
+            syncing: RwSignal::new(false),
+            task_controller,
+        };
+
+        let startup = sync.clone();
+        spawn_local(async move {
+            let settings = startup.settings.get_untracked();
+            if let Some(token) = settings.legacy_token.clone().filter(|t| !t.is_empty()) {
+                match startup.store_settings(settings, &token).await {
+                    Ok(()) => console_log!("Moved the issue access token to the backend"),
+                    Err(e) => console_log!("Error moving the issue access token: {}", e),
+                }
+            }
+            if startup.settings.get_untracked().enabled {
+                startup.import_now();
+            }
+        });
+
+        sync
+    }
+
+    // Saves the settings; a non-empty `token` replaces the stored one
+    pub fn update_settings(&self, settings: IssueImportSettings, token: String) {
+        let sync = self.clone();
+        spawn_local(async move {
+            if let Err(e) = sync.store_settings(settings, token.trim()).await {
+                sync.status.set(Some(format!("Error: {}", e)));
+            }
+        });
+    }
+
+    async fn store_settings(&self, mut settings: IssueImportSettings, token: &str) -> Result<(), String> {
+        let current = self.settings.get_untracked();
+        if !token.is_empty() {
+            settings.token_ref = Some(save_issue_token(&settings, token, current.token_ref.as_deref()).await?);
+        } else if settings.provider != current.provider || settings.base_url != current.base_url {
+            // A saved token is only ever sent to the server it was entered for
+            if current.token_ref.is_some() {
+                return Err("Enter the access token again to use it with a different server".to_string());
+            }
+        } else {
+            settings.token_ref = current.token_ref;
+        }
+        settings.legacy_token = None;
+        settings.save_to_storage();
+        self.settings.set(settings);
+        Ok(())
+    }
+
+    pub fn import_now(&self) {
+        let sync = self.clone();
+        spawn_local(async move {
+            sync.syncing.set(true);
+            let settings = sync.settings.get_untracked();
+
+            let result = match fetch_assigned_issues(&settings).await {
+                Ok(issues) => import_remote_issues(settings.provider, &issues).await,
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(summary) => {
+                    sync.status.set(Some(format!(
+                        "Imported at {}: {} new, {} updated",
+                        js_sys::Date::new_0().to_locale_time_string("en-US"),
+                        summary.created,
+                        summary.updated
+                    )));
+                    sync.task_controller.load_projects();
+                    sync.task_controller.load_tasks();
+                    sync.task_controller.load_task_stats();
+                }
+                Err(e) => {
+                    console_log!("Error importing issues: {}", e);
+                    sync.status.set(Some(format!("Error: {}", e)));
+                }
+            }
+            sync.syncing.set(false);
+        });
+    }
+}
@@ -163,6 +163,8 @@ impl KeyboardShortcuts {
                             console_log!("  Ctrl+R: Reset work sessions");
                             console_log!("  Ctrl++/-: Adjust volume");
                             console_log!("  Alt+F: Toggle fullscreen");
+                            console_log!("  Ctrl+K: Command palette");
+                            console_log!("  Ctrl+Z / Ctrl+Shift+Z: Undo / redo");
                             console_log!("  Shift+?: Show this help");
                         }
                     }
@@ -263,6 +265,17 @@ pub fn KeyboardShortcutsHelp() -> impl IntoView {
                                                 <span class="text-gray-600 dark:text-gray-400">"Fullscreen"</span>
                                                 <kbd class="px-2 py-1 bg-gray-100 dark:bg-gray-700 rounded text-xs">"Alt+F"</kbd>
                                             </div>
+                                            <div class="flex justify-between items-center">
+                                                <span class="text-gray-600 dark:text-gray-400">"Search Tasks & Actions"</span>
+                                                <kbd class="px-2 py-1 bg-gray-100 dark:bg-gray-700 rounded text-xs">"Ctrl+K"</kbd>
+                                            </div>
+                                            <div class="flex justify-between items-center">
+                                                <span class="text-gray-600 dark:text-gray-400">"Undo/Redo"</span>
+                                                <div class="space-x-1">
+                                                    <kbd class="px-2 py-1 bg-gray-100 dark:bg-gray-700 rounded text-xs">"Ctrl+Z"</kbd>
+                                                    <kbd class="px-2 py-1 bg-gray-100 dark:bg-gray-700 rounded text-xs">"Ctrl+Shift+Z"</kbd>
+                                                </div>
+                                            </div>
                                         </div>
                                     </div>
 
@@ -7,6 +7,15 @@ mod task;
 mod theme;
 mod keyboard_shortcuts;
 mod cleanup_scheduler;
+mod history;
+mod markdown;
+mod caldav;
+mod issues;
+mod reports;
+mod taskwarrior;
+mod todotxt;
+#[cfg(test)]
+mod test_support;
 
 use app::*;
 use leptos::prelude::*;
@@ -0,0 +1,357 @@
+// Markdown checklist import/export for tasks and subtasks
+//
+//   - [ ] Write report (~3🍅)
+//     > Optional description
+//     - [x] Gather data (~1🍅) _(25m focused)_
+//     - [ ] Draft
+//
+// Top-level items become tasks, anything indented below them becomes a subtask of the
+// closest task above. The focus suffix is written on export and ignored on import.
+
+use crate::types::{
+    get_all_subtasks, get_all_tasks, save_all_subtasks, save_all_tasks, save_subtask_to_db,
+    save_task_to_db, NewSubTask, NewTask, SubTask, Task, TaskPriority,
+};
+use crate::utils::format_duration_hours_minutes;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChecklistItem {
+    pub name: String,
+    pub completed: bool,
+    pub estimated_pomodoros: Option<u32>,
+    pub description: Option<String>,
+    pub subtasks: Vec<ChecklistItem>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ImportSummary {
+    pub tasks: u32,
+    pub subtasks: u32,
+}
+
+// Splits "- [x] rest" into (completed, rest)
+fn parse_checkbox(line: &str) -> Option<(bool, &str)> {
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))?;
+    let completed = match rest.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    Some((completed, rest[3..].trim()))
+}
+
+// Strips the trailing "_(… focused)_" and "(~N🍅)" annotations from an item's text
+fn parse_item_text(text: &str) -> (String, Option<u32>) {
+    let mut text = text.trim();
+
+    if let Some(start) = text.rfind(" _(") {
+        if text.ends_with(" focused)_") {
+            text = text[..start].trim_end();
+        }
+    }
+
+    let mut estimate = None;
+    if let Some(start) = text.rfind("(~") {
+        if text.ends_with(')') {
+            let inner = text[start + 2..text.len() - 1].trim_end_matches('🍅').trim();
+            if let Ok(value) = inner.parse::<u32>() {
+                estimate = Some(value);
+                text = text[..start].trim_end();
+            }
+        }
+    }
+
+    (text.to_string(), estimate)
+}
+
+pub fn parse_markdown_checklist(markdown: &str) -> Result<Vec<ChecklistItem>, String> {
+    let mut items: Vec<ChecklistItem> = Vec::new();
+    let mut task_indent: Option<usize> = None;
+
+    for (line_number, raw_line) in markdown.lines().enumerate() {
+        let expanded = raw_line.replace('\t', "    ");
+        let trimmed = expanded.trim_start();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indent = expanded.len() - trimmed.len();
+
+        // A quoted line right below an item is its description
+        if let Some(quote) = trimmed.strip_prefix('>') {
+            let target = match items.last_mut() {
+                Some(task) if !task.subtasks.is_empty() => task.subtasks.last_mut(),
+                other => other,
+            };
+            if let Some(item) = target {
+                let quote = quote.trim();
+                item.description = Some(match item.description.take() {
+                    Some(existing) => format!("{}\n{}", existing, quote),
+                    None => quote.to_string(),
+                });
+            }
+            continue;
+        }
+
+        let Some((completed, text)) = parse_checkbox(trimmed) else {
+            continue;
+        };
+        let (name, estimated_pomodoros) = parse_item_text(text);
+        if name.is_empty() {
+            return Err(format!("Line {}: checklist item has no text", line_number + 1));
+        }
+
+        let item = ChecklistItem {
+            name,
+            completed,
+            estimated_pomodoros,
+            description: None,
+            subtasks: Vec::new(),
+        };
+
+        match task_indent {
+            Some(base) if indent > base => match items.last_mut() {
+                Some(task) => task.subtasks.push(item),
+                None => items.push(item),
+            },
+            _ => {
+                task_indent = Some(indent);
+                items.push(item);
+            }
+        }
+    }
+
+    if items.is_empty() {
+        return Err("No checklist items found. Use lines like \"- [ ] Task\".".to_string());
+    }
+
+    Ok(items)
+}
+
+fn format_item_line(
+    indent: &str,
+    name: &str,
+    completed: bool,
+    estimated_pomodoros: Option<u32>,
+    total_focus_time: u32,
+) -> String {
+    let mut line = format!("{}- [{}] {}", indent, if completed { "x" } else { " " }, name);
+    if let Some(estimate) = estimated_pomodoros {
+        line.push_str(&format!(" (~{}🍅)", estimate));
+    }
+    if total_focus_time > 0 {
+        line.push_str(&format!(" _({} focused)_", format_duration_hours_minutes(total_focus_time)));
+    }
+    line
+}
+
+pub fn export_tasks_to_markdown(tasks: &[Task], subtasks: &[SubTask]) -> String {
+    let mut lines = Vec::new();
+
+    for task in tasks {
+        let task_subtasks: Vec<&SubTask> = subtasks.iter().filter(|st| st.task_id == task.id).collect();
+
+        // The task line carries the focus time of the whole tree, subtasks included
+        let tree_focus_time = task.total_focus_time
+            + task_subtasks.iter().map(|st| st.total_focus_time).sum::<u32>();
+        lines.push(format_item_line(
+            "",
+            &task.name,
+            task.completed,
+            task.estimated_pomodoros,
+            tree_focus_time,
+        ));
+        if let Some(description) = &task.description {
+            lines.extend(description.lines().map(|l| format!("  > {}", l)));
+        }
+
+        for subtask in task_subtasks {
+            lines.push(format_item_line(
+                "  ",
+                &subtask.name,
+                subtask.completed,
+                subtask.estimated_pomodoros,
+                subtask.total_focus_time,
+            ));
+            if let Some(description) = &subtask.description {
+                lines.extend(description.lines().map(|l| format!("    > {}", l)));
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+// Creates tasks and subtasks for every checklist item, keeping their checked state
+pub async fn import_markdown_checklist(
+    markdown: &str,
+    project_id: Option<String>,
+) -> Result<ImportSummary, String> {
+    let items = parse_markdown_checklist(markdown)?;
+    let mut summary = ImportSummary::default();
+    let mut completed_task_ids = Vec::new();
+    let mut completed_subtask_ids = Vec::new();
+
+    for item in items {
+        let task_id = save_task_to_db(NewTask {
+            name: item.name,
+            description: item.description,
+            color: Task::get_random_color(),
+            estimated_pomodoros: item.estimated_pomodoros,
+            due_date: None,
+            priority: TaskPriority::default(),
+            recurrence: None,
+            project_id: project_id.clone(),
+        })
+        .await?;
+        summary.tasks += 1;
+        if item.completed {
+            completed_task_ids.push(task_id.clone());
+        }
+
+        for subtask in item.subtasks {
+            let subtask_id = save_subtask_to_db(NewSubTask {
+                task_id: task_id.clone(),
+                name: subtask.name,
+                description: subtask.description,
+                estimated_pomodoros: subtask.estimated_pomodoros,
+            })
+            .await?;
+            summary.subtasks += 1;
+            if subtask.completed {
+                completed_subtask_ids.push(subtask_id);
+            }
+        }
+    }
+
+    // New records always start open, so checked items are marked in one pass afterwards
+    if !completed_task_ids.is_empty() {
+        let mut tasks = get_all_tasks().await?;
+        for task in tasks.iter_mut().filter(|t| completed_task_ids.contains(&t.id)) {
+            task.completed = true;
+        }
+        save_all_tasks(&tasks)?;
+    }
+    if !completed_subtask_ids.is_empty() {
+        let mut subtasks = get_all_subtasks().await?;
+        for subtask in subtasks.iter_mut().filter(|st| completed_subtask_ids.contains(&st.id)) {
+            subtask.completed = true;
+        }
+        save_all_subtasks(&subtasks)?;
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{subtask, task};
+
+    #[test]
+    fn parses_nested_checklist_with_estimates_and_descriptions() {
+        let markdown = "\
+- [ ] Write report (~3🍅)
+  > Quarterly numbers
+  - [x] Gather data (~1🍅)
+  - [ ] Draft
+* [X] Ship it
+";
+        let items = parse_markdown_checklist(markdown).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].name, "Write report");
+        assert_eq!(items[0].estimated_pomodoros, Some(3));
+        assert_eq!(items[0].description.as_deref(), Some("Quarterly numbers"));
+        assert_eq!(items[0].subtasks.len(), 2);
+        assert_eq!(items[0].subtasks[0].name, "Gather data");
+        assert!(items[0].subtasks[0].completed);
+        assert_eq!(items[0].subtasks[0].estimated_pomodoros, Some(1));
+        assert_eq!(items[0].subtasks[1].estimated_pomodoros, None);
+        assert_eq!(items[1].name, "Ship it");
+        assert!(items[1].completed);
+    }
+
+    #[test]
+    fn quoted_line_after_subtask_describes_the_subtask() {
+        let items = parse_markdown_checklist("- [ ] Task\n\t- [ ] Sub\n\t  > Details\n").unwrap();
+
+        assert_eq!(items[0].description, None);
+        assert_eq!(items[0].subtasks[0].description.as_deref(), Some("Details"));
+    }
+
+    #[test]
+    fn ignores_prose_and_keeps_unparsable_estimates_in_the_name() {
+        let items = parse_markdown_checklist("# Plan\n\nSome notes\n- [ ] Call Bob (~soon)\n- not a checkbox\n").unwrap();
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "Call Bob (~soon)");
+        assert_eq!(items[0].estimated_pomodoros, None);
+    }
+
+    #[test]
+    fn rejects_empty_items_and_input_without_checklist() {
+        assert!(parse_markdown_checklist("- [ ]   \n").unwrap_err().starts_with("Line 1"));
+        assert!(parse_markdown_checklist("just text").is_err());
+    }
+
+    #[test]
+    fn export_includes_subtask_focus_in_task_total() {
+        let mut parent = task("t1", "Write report");
+        parent.total_focus_time = 1500;
+        let mut child = subtask("s1", "t1", "Gather data");
+        child.total_focus_time = 3000;
+
+        let markdown = export_tasks_to_markdown(&[parent], &[child]);
+
+        assert_eq!(
+            markdown,
+            "- [ ] Write report _(1h 15m focused)_\n  - [ ] Gather data _(50m focused)_"
+        );
+    }
+
+    #[test]
+    fn export_round_trips_through_the_parser() {
+        let mut parent = task("t1", "Write report");
+        parent.estimated_pomodoros = Some(3);
+        parent.description = Some("Line one\nLine two".to_string());
+        parent.total_focus_time = 600;
+        let mut done = subtask("s1", "t1", "Gather data");
+        done.completed = true;
+        done.estimated_pomodoros = Some(1);
+        done.total_focus_time = 1500;
+        let draft = subtask("s2", "t1", "Draft");
+        let other = subtask("s3", "t2", "Elsewhere");
+
+        let markdown = export_tasks_to_markdown(&[parent], &[done, draft, other]);
+        let items = parse_markdown_checklist(&markdown).unwrap();
+
+        assert_eq!(
+            items,
+            vec![ChecklistItem {
+                name: "Write report".to_string(),
+                completed: false,
+                estimated_pomodoros: Some(3),
+                description: Some("Line one\nLine two".to_string()),
+                subtasks: vec![
+                    ChecklistItem {
+                        name: "Gather data".to_string(),
+                        completed: true,
+                        estimated_pomodoros: Some(1),
+                        description: None,
+                        subtasks: Vec::new(),
+                    },
+                    ChecklistItem {
+                        name: "Draft".to_string(),
+                        completed: false,
+                        estimated_pomodoros: None,
+                        description: None,
+                        subtasks: Vec::new(),
+                    },
+                ],
+            }]
+        );
+    }
+}
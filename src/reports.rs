@@ -0,0 +1,174 @@
+// Weekly productivity reports: the data is gathered here and rendered to Markdown and HTML
+// files by the backend. With auto-generation on, last week's report is written once the
+// week is over.
+
+use crate::console_log;
+use crate::types::{build_weekly_report, Timesheet};
+use crate::utils::{add_days_to_date_key, get_today_date_string, week_start_of_date_key};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{window, Storage};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
+    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+}
+
+const CHECK_INTERVAL_MS: u64 = 3_600_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeeklyReportFiles {
+    pub markdown_path: String,
+    pub html_path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimesheetFiles {
+    pub csv_path: String,
+    pub html_path: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportSettings {
+    pub auto_generate: bool,
+    pub last_generated_week: Option<String>, // Monday of the last week generated on schedule
+}
+
+impl ReportSettings {
+    pub fn save_to_storage(&self) {
+        if let Ok(settings_json) = serde_json::to_string(&self) {
+            if let Some(storage) = get_local_storage() {
+                let _ = storage.set_item("pomodoro_report_settings", &settings_json);
+            }
+        }
+    }
+
+    pub fn load_from_storage() -> Self {
+        if let Some(storage) = get_local_storage() {
+            if let Ok(Some(settings_json)) = storage.get_item("pomodoro_report_settings") {
+                if let Ok(settings) = serde_json::from_str::<ReportSettings>(&settings_json) {
+                    return settings;
+                }
+            }
+        }
+        Self::default()
+    }
+}
+
+fn get_local_storage() -> Option<Storage> {
+    window()?.local_storage().ok()?
+}
+
+// Monday of the week before the current one
+pub fn previous_week_start() -> Option<String> {
+    week_start_of_date_key(&get_today_date_string()).and_then(|monday| add_days_to_date_key(&monday, -7))
+}
+
+async fn write_report(week_start: &str) -> Result<WeeklyReportFiles, String> {
+    let report = build_weekly_report(week_start).await?;
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({ "report": report }))
+        .map_err(|e| format!("Failed to serialize report: {}", e))?;
+    let result = invoke("generate_weekly_report", args).await;
+    serde_wasm_bindgen::from_value::<Result<WeeklyReportFiles, String>>(result)
+        .map_err(|e| format!("Unexpected response from generate_weekly_report: {}", e))?
+}
+
+// Writes the timesheet as CSV and as a printable HTML invoice
+pub async fn export_timesheet(timesheet: &Timesheet) -> Result<TimesheetFiles, String> {
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({ "timesheet": timesheet }))
+        .map_err(|e| format!("Failed to serialize timesheet: {}", e))?;
+    let result = invoke("export_timesheet", args).await;
+    serde_wasm_bindgen::from_value::<Result<TimesheetFiles, String>>(result)
+        .map_err(|e| format!("Unexpected response from export_timesheet: {}", e))?
+}
+
+// Opens a report with the system's default application
+pub async fn open_report(path: &str) -> Result<String, String> {
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({ "path": path }))
+        .map_err(|e| format!("Failed to serialize args: {}", e))?;
+    let result = invoke("open_video_file", args).await;
+    serde_wasm_bindgen::from_value::<Result<String, String>>(result)
+        .map_err(|e| format!("Unexpected response from open_video_file: {}", e))?
+}
+
+#[derive(Clone, Copy)]
+pub struct ReportScheduler {
+    pub settings: RwSignal<ReportSettings>,
+    pub generating: RwSignal<bool>,
+    pub last_files: RwSignal<Option<WeeklyReportFiles>>,
+    pub error: RwSignal<Option<String>>,
+}
+
+impl ReportScheduler {
+    pub fn new() -> Self {
+        let scheduler = Self {
+            settings: RwSignal::new(ReportSettings::load_from_storage()),
+            generating: RwSignal::new(false),
+            last_files: RwSignal::new(None),
+            error: RwSignal::new(None),
+        };
+
+        spawn_local(async move {
+            loop {
+                scheduler.generate_due_report().await;
+                gloo_timers::future::sleep(std::time::Duration::from_millis(CHECK_INTERVAL_MS)).await;
+            }
+        });
+
+        scheduler
+    }
+
+    pub fn update_settings(&self, new_settings: ReportSettings) {
+        new_settings.save_to_storage();
+        self.settings.set(new_settings);
+        let scheduler = *self;
+        spawn_local(async move { scheduler.generate_due_report().await });
+    }
+
+    // Writes last week's report if auto-generation is on and it hasn't been written yet
+    async fn generate_due_report(&self) {
+        let settings = self.settings.get_untracked();
+        let Some(week) = previous_week_start() else {
+            return;
+        };
+        if !settings.auto_generate || settings.last_generated_week.as_ref() >= Some(&week) {
+            return;
+        }
+
+        console_log!("📊 Generating scheduled weekly report for {}", week);
+        if self.generate(week.clone()).await.is_ok() {
+            let mut updated = self.settings.get_untracked();
+            updated.last_generated_week = Some(week);
+            updated.save_to_storage();
+            self.settings.set(updated);
+        }
+    }
+
+    pub async fn generate(&self, week_start: String) -> Result<WeeklyReportFiles, String> {
+        self.generating.set(true);
+        self.error.set(None);
+        let result = write_report(&week_start).await;
+        match &result {
+            Ok(files) => {
+                console_log!("📊 Weekly report written to {}", files.markdown_path);
+                self.last_files.set(Some(files.clone()));
+            }
+            Err(e) => {
+                console_log!("Error generating weekly report: {}", e);
+                self.error.set(Some(e.clone()));
+            }
+        }
+        self.generating.set(false);
+        result
+    }
+
+    pub fn generate_now(&self, week_start: String) {
+        let scheduler = *self;
+        spawn_local(async move {
+            let _ = scheduler.generate(week_start).await;
+        });
+    }
+}
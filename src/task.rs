@@ -1,11 +1,22 @@
 // Fixed src/task.rs
 
 use crate::console_log;
+use crate::history::{take_snapshot, UndoHistory};
+use crate::utils::get_today_date_string;
 use crate::types::{
-    delete_subtask_from_db, delete_task_from_db, get_all_subtasks, get_all_tasks,
-    get_task_stats, save_subtask_to_db, save_task_to_db,
-    update_subtask_in_db, update_task_in_db, NewSubTask, NewTask, SubTask, Task, TaskStats,
+    aggregate_project_stats, aggregate_series_stats, create_next_recurrence,
+    delete_project_from_db, delete_subtask_from_db, delete_task_from_db, get_all_projects,
+    get_all_subtasks, get_all_tasks, get_task_forecasts_from_db, get_task_stats,
+    load_recent_selections, move_subtask_in_db,
+    move_task_in_db, roll_over_recurring_tasks, save_project_to_db, save_recent_selections,
+    save_subtask_to_db, save_task_to_db, update_project_in_db, update_subtask_in_db,
+    update_task_in_db, verify_data_integrity, EstimationAccuracy, IntegrityRepair, IntegrityReport, NewProject, NewSubTask, NewTask, Project, ProjectFilter, ProjectStats,
+    SeriesStats, SessionLinkAction, SubTask, SyncConflict, Task, TaskForecast, TaskStats,
+    TimerSettings,
+    RECENT_SELECTION_LIMIT,
 };
+use crate::taskwarrior::{write_back_taskwarrior_completion, TASKWARRIOR_SOURCE};
+use crate::todotxt::{write_back_completion, TODOTXT_SOURCE};
 use leptos::prelude::*;
 use wasm_bindgen_futures::spawn_local;
 
@@ -14,11 +25,23 @@ pub struct TaskController {
     pub tasks: RwSignal<Vec<Task>>,
     pub subtasks: RwSignal<Vec<SubTask>>,
     pub task_stats: RwSignal<Vec<TaskStats>>,
+    pub series_stats: RwSignal<Vec<SeriesStats>>,
+    pub projects: RwSignal<Vec<Project>>,
+    pub project_stats: RwSignal<Vec<ProjectStats>>,
+    pub forecasts: RwSignal<Option<(EstimationAccuracy, Vec<TaskForecast>)>>,
+    pub project_filter: RwSignal<ProjectFilter>,
     pub selected_task: RwSignal<Option<Task>>,
     pub selected_subtask: RwSignal<Option<SubTask>>,
     pub loading: RwSignal<bool>,
     pub error: RwSignal<Option<String>>,
     pub show_completed: RwSignal<bool>,
+    pub show_archived: RwSignal<bool>,
+    pub sync_conflicts: RwSignal<Vec<SyncConflict>>,
+    pub recent_selections: RwSignal<Vec<String>>, // task/subtask ids, most recent first
+    pub pending_subtask_outcome: RwSignal<Option<SubTask>>, // subtask just worked on, awaiting done/not done
+    pub parent_completion_prompt: RwSignal<Option<Task>>, // task whose subtasks are now all done
+    pub history: UndoHistory,
+    pub integrity_report: RwSignal<Option<IntegrityReport>>, // latest verify & repair result
 }
 
 impl TaskController {
@@ -27,14 +50,31 @@ impl TaskController {
             tasks: RwSignal::new(Vec::new()),
             subtasks: RwSignal::new(Vec::new()),
             task_stats: RwSignal::new(Vec::new()),
+            series_stats: RwSignal::new(Vec::new()),
+            projects: RwSignal::new(Vec::new()),
+            project_stats: RwSignal::new(Vec::new()),
+            forecasts: RwSignal::new(None),
+            project_filter: RwSignal::new(ProjectFilter::All),
             selected_task: RwSignal::new(None),
             selected_subtask: RwSignal::new(None),
             loading: RwSignal::new(false),
             error: RwSignal::new(None),
             show_completed: RwSignal::new(false),
+            show_archived: RwSignal::new(false),
+            sync_conflicts: RwSignal::new(Vec::new()),
+            recent_selections: RwSignal::new(load_recent_selections()),
+            pending_subtask_outcome: RwSignal::new(None),
+            parent_completion_prompt: RwSignal::new(None),
+            history: UndoHistory::new(),
+            integrity_report: RwSignal::new(None),
         };
 
+        // Totals only derive from the session log, so they are fixed on every start. The fix
+        // goes through the undo history and the report lists what changed.
+        controller.verify_integrity(IntegrityRepair::Totals);
+
         // Load initial data
+        controller.load_projects();
         controller.load_tasks();
         controller.load_task_stats();
 
@@ -47,6 +87,16 @@ impl TaskController {
             controller.loading.set(true);
             controller.error.set(None);
 
+            // Recurring tasks whose period has passed get their next instance before display
+            match roll_over_recurring_tasks().await {
+                Ok(created) if created > 0 => {
+                    console_log!("Generated {} recurring task instance(s)", created);
+                    controller.load_task_stats();
+                }
+                Ok(_) => {}
+                Err(e) => console_log!("Error rolling over recurring tasks: {}", e),
+            }
+
             match get_all_tasks().await {
                 Ok(tasks) => {
                     controller.tasks.set(tasks);
@@ -73,12 +123,50 @@ impl TaskController {
         });
     }
 
+    pub fn verify_integrity(&self, repair: IntegrityRepair) {
+        let controller = self.clone();
+        spawn_local(async move {
+            let label = match repair {
+                IntegrityRepair::Totals => "Recompute task totals",
+                IntegrityRepair::None | IntegrityRepair::All => "Repair data",
+            };
+            match controller.history.record(label, verify_data_integrity(repair)).await {
+                Ok(report) => {
+                    if !report.issues.is_empty() {
+                        console_log!(
+                            "🩺 Integrity check found {} issue(s), repaired {}",
+                            report.issues.len(),
+                            report.repaired()
+                        );
+                    }
+                    if report.repaired() > 0 {
+                        controller.load_tasks();
+                        controller.load_task_stats();
+                        controller.history.revision.update(|r| *r += 1);
+                    }
+                    controller.integrity_report.set(Some(report));
+                }
+                Err(e) => {
+                    console_log!("Error verifying data integrity: {}", e);
+                    controller.error.set(Some(e));
+                }
+            }
+        });
+    }
+
     pub fn load_task_stats(&self) {
         let controller = self.clone();
         spawn_local(async move {
             match get_task_stats().await {
                 Ok(stats) => {
+                    let projects = get_all_projects().await.unwrap_or_default();
+                    controller.project_stats.set(aggregate_project_stats(&stats, &projects));
+                    controller.series_stats.set(aggregate_series_stats(&stats));
                     controller.task_stats.set(stats);
+                    match get_task_forecasts_from_db().await {
+                        Ok(forecasts) => controller.forecasts.set(Some(forecasts)),
+                        Err(e) => console_log!("Error loading task forecasts: {}", e),
+                    }
                     console_log!("Task stats loaded successfully");
                 }
                 Err(e) => {
@@ -89,13 +177,93 @@ impl TaskController {
         });
     }
 
+    pub fn load_projects(&self) {
+        let controller = self.clone();
+        spawn_local(async move {
+            match get_all_projects().await {
+                Ok(projects) => {
+                    controller.projects.set(projects);
+                    console_log!("Projects loaded successfully");
+                }
+                Err(e) => {
+                    console_log!("Error loading projects: {}", e);
+                    controller.error.set(Some(e));
+                }
+            }
+        });
+    }
+
+    pub fn create_project(&self, new_project: NewProject) {
+        let controller = self.clone();
+        spawn_local(async move {
+            match save_project_to_db(new_project).await {
+                Ok(project_id) => {
+                    console_log!("Project created with ID: {}", project_id);
+                    controller.load_projects();
+                    controller.load_task_stats();
+                }
+                Err(e) => {
+                    console_log!("Error creating project: {}", e);
+                    controller.error.set(Some(e));
+                }
+            }
+        });
+    }
+
+    pub fn update_project(&self, updated_project: Project) {
+        let controller = self.clone();
+        spawn_local(async move {
+            match update_project_in_db(updated_project.clone()).await {
+                Ok(_) => {
+                    console_log!("Project updated: {}", updated_project.id);
+                    controller.load_projects();
+                    controller.load_task_stats();
+                }
+                Err(e) => {
+                    console_log!("Error updating project: {}", e);
+                    controller.error.set(Some(e));
+                }
+            }
+        });
+    }
+
+    pub fn set_project_archived(&self, project_id: String, archived: bool) {
+        if let Some(mut project) = self.get_project_by_id(&project_id) {
+            project.archived = archived;
+            self.update_project(project);
+        }
+    }
+
+    pub fn delete_project(&self, project_id: String) {
+        let controller = self.clone();
+        spawn_local(async move {
+            match delete_project_from_db(project_id.clone()).await {
+                Ok(true) => {
+                    console_log!("Project deleted: {}", project_id);
+                    if controller.project_filter.get_untracked() == ProjectFilter::Project(project_id) {
+                        controller.project_filter.set(ProjectFilter::All);
+                    }
+                    controller.load_projects();
+                    controller.load_tasks();
+                    controller.load_task_stats();
+                }
+                Ok(false) => console_log!("Project not found for deletion: {}", project_id),
+                Err(e) => {
+                    console_log!("Error deleting project: {}", e);
+                    controller.error.set(Some(e));
+                }
+            }
+        });
+    }
+
     pub fn create_task(&self, new_task: NewTask) {
         let controller = self.clone();
         spawn_local(async move {
             controller.loading.set(true);
             controller.error.set(None);
 
-            match save_task_to_db(new_task).await {
+            let label = format!("Create task \"{}\"", new_task.name);
+            match controller.history.record(label, save_task_to_db(new_task)).await {
                 Ok(task_id) => {
                     console_log!("Task created with ID: {}", task_id);
                     controller.load_tasks();
@@ -117,7 +285,8 @@ impl TaskController {
             controller.loading.set(true);
             controller.error.set(None);
 
-            match save_subtask_to_db(new_subtask).await {
+            let label = format!("Create subtask \"{}\"", new_subtask.name);
+            match controller.history.record(label, save_subtask_to_db(new_subtask)).await {
                 Ok(subtask_id) => {
                     console_log!("Subtask created with ID: {}", subtask_id);
                     controller.load_tasks();
@@ -136,7 +305,8 @@ impl TaskController {
     pub fn update_task(&self, updated_task: Task) {
         let controller = self.clone();
         spawn_local(async move {
-            match update_task_in_db(updated_task.clone()).await {
+            let label = format!("Edit task \"{}\"", updated_task.name);
+            match controller.history.record(label, update_task_in_db(updated_task.clone())).await {
                 Ok(_) => {
                     console_log!("Task updated: {}", updated_task.id);
                     controller.load_tasks();
@@ -153,7 +323,18 @@ impl TaskController {
     pub fn update_subtask(&self, updated_subtask: SubTask) {
         let controller = self.clone();
         spawn_local(async move {
-            match update_subtask_in_db(updated_subtask.clone()).await {
+            let was_completed = controller
+                .subtasks
+                .get_untracked()
+                .iter()
+                .find(|st| st.id == updated_subtask.id)
+                .map(|st| st.completed);
+            let label = match (was_completed, updated_subtask.completed) {
+                (Some(false), true) => format!("Complete subtask \"{}\"", updated_subtask.name),
+                (Some(true), false) => format!("Reopen subtask \"{}\"", updated_subtask.name),
+                _ => format!("Edit subtask \"{}\"", updated_subtask.name),
+            };
+            match controller.history.record(label, update_subtask_in_db(updated_subtask.clone())).await {
                 Ok(_) => {
                     console_log!("Subtask updated: {}", updated_subtask.id);
                     controller.load_tasks();
@@ -167,17 +348,158 @@ impl TaskController {
         });
     }
 
+    pub fn move_task(&self, task_id: String, target_index: usize) {
+        let controller = self.clone();
+        spawn_local(async move {
+            match controller.history.record("Move task", move_task_in_db(&task_id, target_index)).await {
+                Ok(_) => {
+                    console_log!("Task {} moved to position {}", task_id, target_index);
+                    controller.load_tasks();
+                }
+                Err(e) => {
+                    console_log!("Error moving task: {}", e);
+                    controller.error.set(Some(e));
+                }
+            }
+        });
+    }
+
+    pub fn move_subtask(&self, subtask_id: String, target_task_id: String, target_index: usize) {
+        let controller = self.clone();
+        spawn_local(async move {
+            let operation = move_subtask_in_db(&subtask_id, &target_task_id, target_index);
+            match controller.history.record("Move subtask", operation).await {
+                Ok(_) => {
+                    console_log!("Subtask {} moved to task {} at position {}", subtask_id, target_task_id, target_index);
+
+                    // Keep the active selection pointing at the subtask's new parent
+                    if let Some(mut selected) = controller.selected_subtask.get() {
+                        if selected.id == subtask_id && selected.task_id != target_task_id {
+                            selected.task_id = target_task_id.clone();
+                            controller.select_task(controller.get_task_by_id(&target_task_id));
+                            controller.select_subtask(Some(selected));
+                        }
+                    }
+
+                    controller.load_tasks();
+                    controller.load_task_stats();
+                }
+                Err(e) => {
+                    console_log!("Error moving subtask: {}", e);
+                    controller.error.set(Some(e));
+                }
+            }
+        });
+    }
+
+    // Position of a task in the full ordering, used as a move target
+    pub fn get_task_position(&self, task_id: &str) -> Option<usize> {
+        self.tasks.get().iter().position(|t| t.id == task_id)
+    }
+
+    // Position of a subtask among all its siblings, completed ones included. Moving another sibling
+    // to this position puts it exactly where this subtask is shown, from above or below.
+    pub fn get_subtask_position(&self, subtask_id: &str) -> Option<usize> {
+        let subtasks = self.subtasks.get();
+        let task_id = &subtasks.iter().find(|st| st.id == subtask_id)?.task_id;
+        subtasks
+            .iter()
+            .filter(|st| &st.task_id == task_id)
+            .position(|st| st.id == subtask_id)
+    }
+
     pub fn toggle_task_completion(&self, task_id: String) {
         let controller = self.clone();
         spawn_local(async move {
             let mut tasks = controller.tasks.get();
             if let Some(task) = tasks.iter_mut().find(|t| t.id == task_id) {
                 task.completed = !task.completed;
-                controller.update_task(task.clone());
+
+                // The toggle and the recurring instance it may create undo together. Both only
+                // touch local storage, so the entry is committed before any external call.
+                let snapshot = take_snapshot();
+                let label = if task.completed {
+                    format!("Complete task \"{}\"", task.name)
+                } else {
+                    format!("Reopen task \"{}\"", task.name)
+                };
+
+                if let Err(e) = update_task_in_db(task.clone()).await {
+                    console_log!("Error updating task: {}", e);
+                    controller.error.set(Some(e));
+                    return;
+                }
+
+                // Completing a recurring task schedules its next instance
+                if task.completed && task.recurrence.is_some() {
+                    match create_next_recurrence(task).await {
+                        Ok(Some(next_id)) => console_log!("Next recurring instance created: {}", next_id),
+                        Ok(None) => {}
+                        Err(e) => {
+                            console_log!("Error creating next recurring instance: {}", e);
+                            controller.error.set(Some(e));
+                        }
+                    }
+                }
+
+                controller.history.commit(label, snapshot);
+                controller.load_tasks();
+                controller.load_task_stats();
+
+                controller.write_back_task_completion(task).await;
+                controller.load_tasks();
             }
         });
     }
 
+    // Linked tasks get their completion written back to where they came from
+    async fn write_back_task_completion(&self, task: &Task) {
+        match task.external_source() {
+            Some(TODOTXT_SOURCE) => match write_back_completion(task).await {
+                Ok(Some(conflict)) => {
+                    console_log!("todo.txt conflict for task {}", task.id);
+                    self.sync_conflicts.update(|list| {
+                        list.retain(|c| !(c.source == conflict.source && c.task_id == conflict.task_id));
+                        list.push(conflict);
+                    });
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    console_log!("Error writing todo.txt: {}", e);
+                    self.error.set(Some(e));
+                }
+            },
+            Some(TASKWARRIOR_SOURCE) => {
+                if let Err(e) = write_back_taskwarrior_completion(task).await {
+                    console_log!("Error updating Taskwarrior: {}", e);
+                    self.error.set(Some(e));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // After undo/redo changed the completion of linked tasks, writes their current state back
+    pub fn write_back_task_completions(&self, task_ids: Vec<String>) {
+        if task_ids.is_empty() {
+            return;
+        }
+        let controller = self.clone();
+        spawn_local(async move {
+            let tasks = match get_all_tasks().await {
+                Ok(tasks) => tasks,
+                Err(e) => {
+                    controller.error.set(Some(e));
+                    return;
+                }
+            };
+            for task in tasks.iter().filter(|t| task_ids.contains(&t.id)) {
+                controller.write_back_task_completion(task).await;
+            }
+            controller.load_tasks();
+        });
+    }
+
     pub fn toggle_subtask_completion(&self, subtask_id: String) {
         let controller = self.clone();
         spawn_local(async move {
@@ -185,16 +507,107 @@ impl TaskController {
             if let Some(subtask) = subtasks.iter_mut().find(|st| st.id == subtask_id) {
                 subtask.completed = !subtask.completed;
                 controller.update_subtask(subtask.clone());
+
+                let was_selected = controller
+                    .selected_subtask
+                    .get_untracked()
+                    .is_some_and(|st| st.id == subtask_id);
+                if subtask.completed && was_selected {
+                    controller.advance_subtask_selection(&subtasks, &subtask_id);
+                }
             }
         });
     }
 
-    pub fn delete_task(&self, task_id: String) {
+    // Records the outcome of a work session on a subtask; "done" completes it
+    pub fn resolve_subtask_outcome(&self, done: bool) {
+        if let Some(subtask) = self.pending_subtask_outcome.get_untracked() {
+            self.pending_subtask_outcome.set(None);
+            if done && !subtask.completed {
+                self.toggle_subtask_completion(subtask.id);
+            }
+        }
+    }
+
+    // Asks for the session outcome when auto-advance is enabled and a subtask was tracked
+    pub fn request_subtask_outcome(&self, subtask_id: &str) {
+        if !TimerSettings::load_from_storage().auto_advance_subtasks {
+            return;
+        }
+        let subtask = self
+            .subtasks
+            .get_untracked()
+            .into_iter()
+            .find(|st| st.id == subtask_id && !st.completed);
+        self.pending_subtask_outcome.set(subtask);
+    }
+
+    // Selects the next incomplete subtask by order_index, wrapping around to earlier ones.
+    // When none are left the user is asked whether to complete the parent task.
+    fn advance_subtask_selection(&self, subtasks: &[SubTask], completed_id: &str) {
+        if !TimerSettings::load_from_storage().auto_advance_subtasks {
+            return;
+        }
+        let Some(completed) = subtasks.iter().find(|st| st.id == completed_id) else {
+            return;
+        };
+
+        let mut siblings: Vec<&SubTask> = subtasks
+            .iter()
+            .filter(|st| st.task_id == completed.task_id && !st.completed)
+            .collect();
+        siblings.sort_by_key(|st| st.order_index);
+        let next = siblings
+            .iter()
+            .find(|st| st.order_index > completed.order_index)
+            .or_else(|| siblings.first());
+
+        match next {
+            Some(next) => {
+                console_log!("Advancing to next subtask: {}", next.name);
+                self.select_subtask(Some((*next).clone()));
+            }
+            None => {
+                self.select_subtask(None);
+                let parent = self.get_task_by_id(&completed.task_id).filter(|t| !t.completed);
+                self.parent_completion_prompt.set(parent);
+            }
+        }
+    }
+
+    pub fn toggle_task_planned_today(&self, task_id: String) {
+        let today = get_today_date_string();
+        if let Some(mut task) = self.get_task_by_id(&task_id) {
+            task.planned_date = if task.is_planned_for(&today) {
+                None
+            } else {
+                Some(today)
+            };
+            self.update_task(task);
+        }
+    }
+
+    pub fn set_task_archived(&self, task_id: String, archived: bool) {
+        if let Some(mut task) = self.get_task_by_id(&task_id) {
+            task.archived = archived;
+
+            // Archived tasks can no longer be tracked against
+            if archived && self.selected_task.get().is_some_and(|t| t.id == task_id) {
+                self.select_task(None);
+            }
+
+            self.update_task(task);
+        }
+    }
+
+    pub fn delete_task(&self, task_id: String, session_action: SessionLinkAction) {
         let controller = self.clone();
         spawn_local(async move {
             controller.loading.set(true);
 
-            match delete_task_from_db(task_id.clone()).await {
+            let name = controller.get_task_by_id(&task_id).map(|t| t.name).unwrap_or_default();
+            let label = format!("Delete task \"{}\"", name);
+            match controller.history.record(label, delete_task_from_db(task_id.clone(), session_action)).await {
                 Ok(deleted) => {
                     if deleted {
                         console_log!("Task deleted: {}", task_id);
@@ -228,7 +641,15 @@ impl TaskController {
         spawn_local(async move {
             controller.loading.set(true);
 
-            match delete_subtask_from_db(subtask_id.clone()).await {
+            let name = controller
+                .subtasks
+                .get_untracked()
+                .into_iter()
+                .find(|st| st.id == subtask_id)
+                .map(|st| st.name)
+                .unwrap_or_default();
+            let label = format!("Delete subtask \"{}\"", name);
+            match controller.history.record(label, delete_subtask_from_db(subtask_id.clone())).await {
                 Ok(deleted) => {
                     if deleted {
                         console_log!("Subtask deleted: {}", subtask_id);
@@ -257,6 +678,9 @@ impl TaskController {
     }
 
     pub fn select_task(&self, task: Option<Task>) {
+        if let Some(task) = &task {
+            self.remember_selection(&task.id);
+        }
         self.selected_task.set(task);
         // Clear subtask selection when task changes
         self.selected_subtask.set(None);
@@ -264,19 +688,64 @@ impl TaskController {
     }
 
     pub fn select_subtask(&self, subtask: Option<SubTask>) {
+        if let Some(subtask) = &subtask {
+            self.remember_selection(&subtask.id);
+        }
         self.selected_subtask.set(subtask);
         console_log!("Subtask selection changed");
     }
 
+    // Recently used tasks rank first in the command palette
+    fn remember_selection(&self, id: &str) {
+        let mut recent = self.recent_selections.get_untracked();
+        recent.retain(|r| r != id);
+        recent.insert(0, id.to_string());
+        recent.truncate(RECENT_SELECTION_LIMIT);
+        if let Err(e) = save_recent_selections(&recent) {
+            console_log!("Error saving recent selections: {}", e);
+        }
+        self.recent_selections.set(recent);
+    }
+
     pub fn get_filtered_tasks(&self) -> Vec<Task> {
         let tasks = self.tasks.get();
         let show_completed = self.show_completed.get();
 
-        if show_completed {
-            tasks
-        } else {
-            tasks.into_iter().filter(|t| !t.completed).collect()
-        }
+        tasks
+            .into_iter()
+            .filter(|t| !t.archived)
+            .filter(|t| show_completed || !t.completed)
+            .collect()
+    }
+
+    // Open tasks planned for today, due today or overdue, most urgent first
+    pub fn get_today_tasks(&self) -> Vec<Task> {
+        let today = get_today_date_string();
+
+        let mut tasks: Vec<Task> = self
+            .tasks
+            .get()
+            .into_iter()
+            .filter(|t| !t.completed && !t.archived)
+            .filter(|t| {
+                t.is_planned_for(&today)
+                    || t.due_date.as_deref().is_some_and(|due| due <= today.as_str())
+            })
+            .collect();
+
+        tasks.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then_with(|| match (&a.due_date, &b.due_date) {
+                    (Some(a_due), Some(b_due)) => a_due.cmp(b_due),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                })
+                .then_with(|| a.order_index.cmp(&b.order_index))
+        });
+
+        tasks
     }
 
     pub fn get_subtasks_for_task(&self, task_id: &str) -> Vec<SubTask> {
@@ -295,6 +764,17 @@ impl TaskController {
         }
     }
 
+    // Pomodoros left on a task, counting every subtask whether or not completed ones are shown
+    pub fn get_remaining_pomodoros(&self, task: &Task, settings: &TimerSettings) -> u32 {
+        let subtasks: Vec<SubTask> = self
+            .subtasks
+            .get()
+            .into_iter()
+            .filter(|st| st.task_id == task.id)
+            .collect();
+        task.remaining_pomodoros(&subtasks, settings)
+    }
+
     pub fn get_active_task_info(&self) -> Option<String> {
         if let Some(subtask) = self.selected_subtask.get() {
             if let Some(task) = self.tasks.get().iter().find(|t| t.id == subtask.task_id) {
@@ -326,6 +806,10 @@ impl TaskController {
         self.tasks.get().into_iter().find(|t| t.id == task_id)
     }
 
+    pub fn get_project_by_id(&self, project_id: &str) -> Option<Project> {
+        self.projects.get().into_iter().find(|p| p.id == project_id)
+    }
+
     // Helper method to get subtask by ID
     pub fn get_subtask_by_id(&self, subtask_id: &str) -> Option<SubTask> {
         self.subtasks.get().into_iter().find(|st| st.id == subtask_id)
@@ -335,9 +819,13 @@ impl TaskController {
     pub fn get_task_progress_summary(&self) -> Vec<(Task, f64, u32, u32)> {
         let tasks = self.tasks.get();
         let all_subtasks = self.subtasks.get();
+        let show_archived = self.show_archived.get();
+        let project_filter = self.project_filter.get();
 
         tasks
             .into_iter()
+            .filter(|task| task.archived == show_archived)
+            .filter(|task| project_filter.matches(task.project_id.as_deref()))
             .map(|task| {
                 let subtasks: Vec<_> = all_subtasks
                     .iter()
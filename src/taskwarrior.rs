@@ -0,0 +1,399 @@
+use crate::console_log;
+use crate::task::TaskController;
+use crate::types::{
+    find_or_create_projects, get_all_subtasks, get_all_tasks, local_start, save_all_tasks,
+    save_task_to_db, ExternalRef, LocalStart, NewTask, Task, TaskPriority,
+};
+use crate::utils::{format_duration_hours_minutes, iso_from_basic_date_time};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{window, Storage};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
+    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+}
+
+pub const TASKWARRIOR_SOURCE: &str = "taskwarrior";
+
+// The binary that gets run is configured on the backend only, see `taskwarrior_binary`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskwarriorSettings {
+    pub enabled: bool,
+    pub focus_uda: String, // numeric UDA that receives total focus minutes
+}
+
+impl Default for TaskwarriorSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            focus_uda: "pomodoro_focus".to_string(),
+        }
+    }
+}
+
+impl TaskwarriorSettings {
+    pub fn save_to_storage(&self) {
+        if let Ok(settings_json) = serde_json::to_string(&self) {
+            if let Some(storage) = get_local_storage() {
+                let _ = storage.set_item("pomodoro_taskwarrior_settings", &settings_json);
+            }
+        }
+    }
+
+    pub fn load_from_storage() -> Self {
+        if let Some(storage) = get_local_storage() {
+            if let Ok(Some(settings_json)) = storage.get_item("pomodoro_taskwarrior_settings") {
+                if let Ok(settings) = serde_json::from_str::<TaskwarriorSettings>(&settings_json) {
+                    return settings;
+                }
+            }
+        }
+        Self::default()
+    }
+}
+
+fn get_local_storage() -> Option<Storage> {
+    window()?.local_storage().ok()?
+}
+
+// One entry of `task export`; fields we don't map are ignored
+#[derive(Debug, Clone, Deserialize)]
+pub struct TaskwarriorTask {
+    pub uuid: String,
+    pub description: String,
+    pub status: String,
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[serde(default)]
+    pub due: Option<String>, // e.g. "20240115T050000Z"
+    #[serde(default)]
+    pub modified: Option<String>,
+}
+
+impl TaskwarriorTask {
+    fn task_priority(&self) -> TaskPriority {
+        match self.priority.as_deref() {
+            Some("H") => TaskPriority::High,
+            Some("L") => TaskPriority::Low,
+            _ => TaskPriority::Medium,
+        }
+    }
+
+    // `due` is a UTC timestamp, so the local day it falls on can differ from the UTC one
+    fn due_date(&self, local_start: impl Fn(&str) -> Option<LocalStart>) -> Option<String> {
+        let due = iso_from_basic_date_time(self.due.as_deref()?)?;
+        local_start(&due).map(|start| start.day)
+    }
+
+    fn is_completed(&self) -> bool {
+        self.status == "completed"
+    }
+}
+
+fn apply_taskwarrior_task(task: &mut Task, tw_task: &TaskwarriorTask, project_id: Option<String>) {
+    task.name = tw_task.description.clone();
+    task.completed = tw_task.is_completed();
+    task.priority = tw_task.task_priority();
+    task.due_date = tw_task.due_date(local_start);
+    task.tags = tw_task.tags.clone();
+    if project_id.is_some() {
+        task.project_id = project_id;
+    }
+    if let Some(external) = task.external.as_mut() {
+        external.etag = tw_task.modified.clone();
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TaskwarriorImportSummary {
+    pub created: u32,
+    pub updated: u32,
+}
+
+// Imports `task export` output. Tasks are keyed by UUID, so importing again updates them
+// instead of creating duplicates. Deleted tasks and recurrence templates are skipped, and
+// tasks already completed in Taskwarrior are only imported if they were imported before.
+pub async fn import_taskwarrior_export(json: &str) -> Result<TaskwarriorImportSummary, String> {
+    let exported: Vec<TaskwarriorTask> =
+        serde_json::from_str(json).map_err(|e| format!("Invalid task export output: {}", e))?;
+    let exported: Vec<TaskwarriorTask> = exported
+        .into_iter()
+        .filter(|t| t.status != "deleted" && t.status != "recurring")
+        .collect();
+
+    let mut project_names: Vec<String> = Vec::new();
+    for name in exported.iter().filter_map(|t| t.project.as_ref()) {
+        if !project_names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            project_names.push(name.clone());
+        }
+    }
+    let project_ids = find_or_create_projects(&project_names).await?;
+    let project_for = |tw_task: &TaskwarriorTask| {
+        tw_task.project.as_ref().and_then(|name| {
+            project_ids
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, id)| id.clone())
+        })
+    };
+
+    let mut summary = TaskwarriorImportSummary::default();
+    let mut tasks = get_all_tasks().await?;
+    let mut new_tasks = Vec::new();
+
+    for tw_task in &exported {
+        let existing = tasks.iter_mut().find(|t| {
+            t.external
+                .as_ref()
+                .is_some_and(|e| e.source == TASKWARRIOR_SOURCE && e.id == tw_task.uuid)
+        });
+
+        match existing {
+            Some(task) => {
+                let unchanged = task.external.as_ref().and_then(|e| e.etag.as_ref()) == tw_task.modified.as_ref();
+                if !unchanged {
+                    apply_taskwarrior_task(task, tw_task, project_for(tw_task));
+                    summary.updated += 1;
+                }
+            }
+            None if !tw_task.is_completed() => new_tasks.push(tw_task),
+            None => {}
+        }
+    }
+
+    save_all_tasks(&tasks)?;
+
+    if new_tasks.is_empty() {
+        return Ok(summary);
+    }
+
+    let mut created_ids = Vec::new();
+    for tw_task in &new_tasks {
+        let task_id = save_task_to_db(NewTask {
+            name: tw_task.description.clone(),
+            description: None,
+            color: Task::get_random_color(),
+            estimated_pomodoros: None,
+            due_date: tw_task.due_date(local_start),
+            priority: tw_task.task_priority(),
+            recurrence: None,
+            project_id: project_for(tw_task),
+        })
+        .await?;
+        created_ids.push(task_id);
+    }
+
+    // Link the new tasks to their Taskwarrior UUIDs in one write
+    let mut tasks = get_all_tasks().await?;
+    for (task_id, tw_task) in created_ids.iter().zip(&new_tasks) {
+        if let Some(task) = tasks.iter_mut().find(|t| &t.id == task_id) {
+            task.external = Some(ExternalRef {
+                source: TASKWARRIOR_SOURCE.to_string(),
+                id: tw_task.uuid.clone(),
+                url: None,
+                etag: None,
+            });
+            apply_taskwarrior_task(task, tw_task, None);
+        }
+    }
+    save_all_tasks(&tasks)?;
+    summary.created = created_ids.len() as u32;
+
+    Ok(summary)
+}
+
+async fn run_taskwarrior(cmd: &str, args: serde_json::Value) -> Result<String, String> {
+    let args = serde_wasm_bindgen::to_value(&args).map_err(|e| e.to_string())?;
+    let result = invoke(cmd, args).await;
+    serde_wasm_bindgen::from_value::<Result<String, String>>(result)
+        .map_err(|_| format!("Unexpected response from {}", cmd))?
+}
+
+// Path of the `task` binary the backend will run, or why none can be used
+pub async fn get_taskwarrior_binary() -> Result<String, String> {
+    run_taskwarrior("taskwarrior_binary", serde_json::json!({})).await
+}
+
+fn taskwarrior_uuid(task: &Task) -> Option<&str> {
+    task.external
+        .as_ref()
+        .filter(|e| e.source == TASKWARRIOR_SOURCE)
+        .map(|e| e.id.as_str())
+}
+
+// Annotates the linked Taskwarrior task with a finished pomodoro and updates its focus UDA
+pub async fn record_taskwarrior_focus(task_id: &str, focus_time_seconds: u32) -> Result<(), String> {
+    let settings = TaskwarriorSettings::load_from_storage();
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let tasks = get_all_tasks().await?;
+    let Some(task) = tasks.iter().find(|t| t.id == task_id) else {
+        return Ok(());
+    };
+    let Some(uuid) = taskwarrior_uuid(task) else {
+        return Ok(());
+    };
+
+    run_taskwarrior(
+        "taskwarrior_annotate",
+        serde_json::json!({
+            "uuid": uuid,
+            "text": format!("Pomodoro: {} focused", format_duration_hours_minutes(focus_time_seconds)),
+        }),
+    )
+    .await?;
+
+    // Focus on subtasks counts towards the Taskwarrior task as well
+    let subtask_focus: u32 = get_all_subtasks()
+        .await?
+        .iter()
+        .filter(|st| st.task_id == task.id)
+        .map(|st| st.total_focus_time)
+        .sum();
+    let total_minutes = (task.total_focus_time + subtask_focus) / 60;
+
+    run_taskwarrior(
+        "taskwarrior_set_uda",
+        serde_json::json!({
+            "uuid": uuid,
+            "uda": settings.focus_uda,
+            "value": total_minutes,
+        }),
+    )
+    .await?;
+
+    console_log!("Recorded {}s of focus on Taskwarrior task {}", focus_time_seconds, uuid);
+    Ok(())
+}
+
+// Runs `task <uuid> done` (or reopens the task) to match the task's completed state
+pub async fn write_back_taskwarrior_completion(task: &Task) -> Result<(), String> {
+    let settings = TaskwarriorSettings::load_from_storage();
+    let Some(uuid) = taskwarrior_uuid(task).filter(|_| settings.enabled) else {
+        return Ok(());
+    };
+
+    run_taskwarrior(
+        "taskwarrior_set_done",
+        serde_json::json!({
+            "uuid": uuid,
+            "done": task.completed,
+        }),
+    )
+    .await
+    .map(|_| ())
+}
+
+#[derive(Clone)]
+pub struct TaskwarriorSync {
+    pub settings: RwSignal<TaskwarriorSettings>,
+    pub status: RwSignal<Option<String>>,
+    pub syncing: RwSignal<bool>,
+    task_controller: TaskController,
+}
+
+impl TaskwarriorSync {
+    pub fn new(task_controller: TaskController) -> Self {
+        let sync = Self {
+            settings: RwSignal::new(TaskwarriorSettings::load_from_storage()),
+            status: RwSignal::new(None),
+            syncing: RwSignal::new(false),
+            task_controller,
+        };
+
+        if sync.settings.get_untracked().enabled {
+            sync.import_now();
+        }
+
+        sync
+    }
+
+    pub fn update_settings(&self, settings: TaskwarriorSettings) {
+        settings.save_to_storage();
+        let enabled = settings.enabled;
+        self.settings.set(settings);
+        if enabled {
+            self.import_now();
+        }
+    }
+
+    pub fn import_now(&self) {
+        let sync = self.clone();
+        spawn_local(async move {
+            sync.syncing.set(true);
+            let result = match run_taskwarrior("taskwarrior_export", serde_json::json!({})).await {
+                Ok(json) => import_taskwarrior_export(&json).await,
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(summary) => {
+                    sync.status.set(Some(format!(
+                        "Imported at {}: {} new, {} updated",
+                        js_sys::Date::new_0().to_locale_time_string("en-US"),
+                        summary.created,
+                        summary.updated
+                    )));
+                    sync.task_controller.load_projects();
+                    sync.task_controller.load_tasks();
+                    sync.task_controller.load_task_stats();
+                }
+                Err(e) => {
+                    console_log!("Error importing from Taskwarrior: {}", e);
+                    sync.status.set(Some(format!("Error: {}", e)));
+                }
+            }
+            sync.syncing.set(false);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::offset_start;
+
+    fn due(value: &str) -> TaskwarriorTask {
+        TaskwarriorTask {
+            uuid: "uuid".to_string(),
+            description: "Task".to_string(),
+            status: "pending".to_string(),
+            project: None,
+            tags: Vec::new(),
+            priority: None,
+            due: Some(value.to_string()),
+            modified: None,
+        }
+    }
+
+    #[test]
+    fn due_dates_fall_on_the_local_day() {
+        // Local midnight on the 16th, five hours behind UTC
+        assert_eq!(due("20240116T050000Z").due_date(offset_start(-5, 0)).as_deref(), Some("2024-01-16"));
+        // Late on the 15th in UTC is already the 16th an hour ahead of it
+        assert_eq!(due("20240115T230000Z").due_date(offset_start(1, 0)).as_deref(), Some("2024-01-16"));
+        assert_eq!(due("20240115T230000Z").due_date(offset_start(0, 0)).as_deref(), Some("2024-01-15"));
+    }
+
+    #[test]
+    fn due_dates_before_the_day_start_hour_belong_to_the_previous_day() {
+        assert_eq!(due("20240116T020000Z").due_date(offset_start(0, 4)).as_deref(), Some("2024-01-15"));
+        assert_eq!(due("20240116T040000Z").due_date(offset_start(0, 4)).as_deref(), Some("2024-01-16"));
+    }
+
+    #[test]
+    fn malformed_due_dates_are_ignored() {
+        assert_eq!(due("2024-01-16").due_date(offset_start(0, 0)), None);
+        assert_eq!(due("20240116").due_date(offset_start(0, 0)), None);
+    }
+}
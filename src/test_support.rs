@@ -0,0 +1,98 @@
+// Record builders shared by the unit tests
+
+use crate::types::{LocalStart, Session, SubTask, Task, TaskPriority};
+use crate::utils::{date_key_from_days, days_from_civil};
+
+pub fn task(id: &str, name: &str) -> Task {
+    Task {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: None,
+        color: "#3B82F6".to_string(),
+        created_at: "2024-01-01T00:00:00.000Z".to_string(),
+        completed: false,
+        estimated_pomodoros: None,
+        actual_pomodoros: 0,
+        total_focus_time: 0,
+        order_index: 1,
+        due_date: None,
+        priority: TaskPriority::default(),
+        planned_date: None,
+        recurrence: None,
+        series_id: None,
+        archived: false,
+        project_id: None,
+        tags: Vec::new(),
+        external: None,
+        hourly_rate: None,
+    }
+}
+
+pub fn subtask(id: &str, task_id: &str, name: &str) -> SubTask {
+    SubTask {
+        id: id.to_string(),
+        task_id: task_id.to_string(),
+        name: name.to_string(),
+        description: None,
+        completed: false,
+        created_at: "2024-01-01T00:00:00.000Z".to_string(),
+        estimated_pomodoros: None,
+        actual_pomodoros: 0,
+        total_focus_time: 0,
+        order_index: 1,
+        external: None,
+    }
+}
+
+// A completed work session; start and end are ISO timestamps and the duration is derived from them
+pub fn work_session(id: &str, start: &str, end: &str) -> Session {
+    let seconds = (iso_seconds(end) - iso_seconds(start)).max(0) as u32;
+    Session {
+        id: id.to_string(),
+        session_type: "Work".to_string(),
+        planned_duration: seconds,
+        actual_duration: seconds,
+        start_time: start.to_string(),
+        end_time: end.to_string(),
+        completed: true,
+        created_at: end.to_string(),
+        video_path: None,
+        task_id: None,
+        subtask_id: None,
+        manual: false,
+        tags: Vec::new(),
+    }
+}
+
+// Seconds since the epoch for "YYYY-MM-DDTHH:MM:SS(.sss)Z", enough for test fixtures
+fn iso_seconds(iso: &str) -> i64 {
+    let number = |range: std::ops::Range<usize>| iso[range].parse::<i64>().unwrap();
+    let days = days_from_civil(number(0..4) as i32, number(5..7) as u32, number(8..10) as u32);
+    days * 86400 + number(11..13) * 3600 + number(14..16) * 60 + number(17..19)
+}
+
+// The local clock in UTC: "YYYY-MM-DDTHH:MM:SS" starts on that day at that hour
+pub fn utc_start(iso: &str) -> Option<LocalStart> {
+    let number = |range: std::ops::Range<usize>| iso.get(range)?.parse::<u32>().ok();
+    Some(LocalStart {
+        day: iso.get(..10)?.to_string(),
+        hour: number(11..13)?,
+        seconds_into_hour: number(14..16)? * 60 + number(17..19)?,
+    })
+}
+
+// A local clock `offset_hours` ahead of UTC whose days start at `day_start_hour`; timestamps
+// without a trailing Z are already local
+pub fn offset_start(offset_hours: i64, day_start_hour: u32) -> impl Fn(&str) -> Option<LocalStart> {
+    move |iso| {
+        let offset = if iso.ends_with('Z') { offset_hours * 3600 } else { 0 };
+        let seconds = iso_seconds(iso) + offset;
+        let days = seconds.div_euclid(86400);
+        let hour = (seconds.rem_euclid(86400) / 3600) as u32;
+        Some(LocalStart {
+            day: date_key_from_days(if hour < day_start_hour { days - 1 } else { days }),
+            hour,
+            seconds_into_hour: seconds.rem_euclid(3600) as u32,
+        })
+    }
+}
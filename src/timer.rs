@@ -1,9 +1,12 @@
 use crate::components::CameraController;
 use crate::console_log;
+use crate::issues::post_issue_focus_comment;
 use crate::task::TaskController;
+use crate::taskwarrior::record_taskwarrior_focus;
 use crate::types::{
-    complete_work_session_with_task, generate_session_id, get_session_stats_from_db,
-    save_session_to_db, NewSession, SessionStats, SessionType, TimerSettings, TimerState,
+    check_budget_after_session, complete_work_session_with_task, generate_session_id,
+    get_session_stats_from_db, save_session_to_db, BudgetAlertSettings, BudgetEvent,
+    DailyGoalSettings, NewSession, SessionStats, SessionType, TimerSettings, TimerState,
 };
 use crate::utils::{clearInterval, get_current_iso_time, setInterval};
 use leptos::prelude::*;
@@ -29,6 +32,10 @@ pub struct TimerController {
     pub current_session_id: RwSignal<Option<String>>,
     pub loading: RwSignal<bool>,
     pub timer_settings: RwSignal<TimerSettings>,
+    pub include_manual_sessions: RwSignal<bool>, // whether statistics and focus charts count manually logged sessions
+    pub daily_goal: RwSignal<DailyGoalSettings>,
+    pub budget_settings: RwSignal<BudgetAlertSettings>,
+    pub budget_alerts: RwSignal<Vec<BudgetEvent>>, // crossed thresholds shown until dismissed
 }
 
 impl TimerController {
@@ -47,6 +54,10 @@ impl TimerController {
             current_session_id: RwSignal::new(None::<String>),
             loading: RwSignal::new(false),
             timer_settings: RwSignal::new(settings),
+            include_manual_sessions: RwSignal::new(true),
+            daily_goal: RwSignal::new(DailyGoalSettings::load_from_storage()),
+            budget_settings: RwSignal::new(BudgetAlertSettings::load_from_storage()),
+            budget_alerts: RwSignal::new(Vec::new()),
         };
 
         // Load initial stats from database
@@ -56,12 +67,19 @@ impl TimerController {
     }
 
     pub fn update_timer_settings(&self, new_settings: TimerSettings) {
+        let day_start_changed = self.timer_settings.get_untracked().day_start_hour != new_settings.day_start_hour;
+
         // Save to storage
         new_settings.save_to_storage();
 
         // Update signal
         self.timer_settings.set(new_settings.clone());
 
+        // Per-day views regroup their sessions once stats reload
+        if day_start_changed {
+            self.load_session_stats();
+        }
+
         // If timer is stopped, update time remaining for current session
         if self.timer_state.get() == TimerState::Stopped {
             let current_duration = self.session_type.get().duration_minutes(&new_settings) * 60;
@@ -108,6 +126,30 @@ impl TimerController {
     }
 
     pub fn stop_timer(&self) {
+        // A session stopped part-way is kept as incomplete so completion rates and interruptions
+        // count it. It has no task link, so it never adds to a task's focus time or timesheet.
+        if self.timer_state.get_untracked() != TimerState::Stopped {
+            if let Some(start_time) = self.session_start_time.get_untracked() {
+                let session_type = self.session_type.get_untracked();
+                let planned_duration = session_type.duration_minutes(&self.timer_settings.get_untracked()) * 60;
+                let actual_duration = planned_duration.saturating_sub(self.time_remaining.get_untracked());
+                if actual_duration > 0 {
+                    self.save_session(NewSession {
+                        session_type: session_type.to_string(),
+                        planned_duration,
+                        actual_duration,
+                        start_time,
+                        end_time: get_current_iso_time(),
+                        completed: false,
+                        video_path: None,
+                        task_id: None,
+                        subtask_id: None,
+                        manual: false,
+                    });
+                }
+            }
+        }
+
         self.timer_state.set(TimerState::Stopped);
         if let Some(id) = self.interval_id.get() {
             clearInterval(id);
@@ -184,11 +226,25 @@ impl TimerController {
         spawn_local(async move {
             controller.loading.set(true);
 
+            let task_id = session.task_id.clone();
+
             // Use the new task-aware session saving function
             match complete_work_session_with_task(session, focus_time_seconds).await {
-                Ok(_) => {
+                Ok(session_id) => {
                     console_log!("Session with task tracking saved successfully!");
                     controller.load_session_stats();
+
+                    controller.check_budget(&session_id).await;
+
+                    // Linked tasks report the pomodoro back to Taskwarrior or their issue tracker
+                    if let Some(task_id) = task_id {
+                        if let Err(e) = record_taskwarrior_focus(&task_id, focus_time_seconds).await {
+                            console_log!("Error updating Taskwarrior: {}", e);
+                        }
+                        if let Err(e) = post_issue_focus_comment(&task_id, focus_time_seconds).await {
+                            console_log!("Error posting issue comment: {}", e);
+                        }
+                    }
                 }
                 Err(e) => {
                     console_log!("Error saving session with task tracking: {}", e);
@@ -223,7 +279,7 @@ impl TimerController {
         spawn_local(async move {
             controller.loading.set(true);
 
-            match get_session_stats_from_db().await {
+            match get_session_stats_from_db(controller.include_manual_sessions.get_untracked()).await {
                 Ok(stats) => {
                     // Update historical stats for display
                     controller.completed_work_sessions.set(stats.work_sessions);
@@ -241,6 +297,42 @@ impl TimerController {
         });
     }
 
+    // Alerts for the budget thresholds a just-saved work session crossed, timed or logged by hand
+    pub async fn check_budget(&self, session_id: &str) {
+        let budget_settings = self.budget_settings.get_untracked();
+        match check_budget_after_session(session_id, &budget_settings).await {
+            Ok(events) if !events.is_empty() => self.send_budget_alerts(events).await,
+            Ok(_) => {}
+            Err(e) => console_log!("Error checking task budget: {}", e),
+        }
+    }
+
+    // Shows the banner and one system notification per task for the highest threshold crossed
+    async fn send_budget_alerts(&self, events: Vec<BudgetEvent>) {
+        for event in events.iter().filter(|e| {
+            !events
+                .iter()
+                .any(|o| o.task_id == e.task_id && o.subtask_id == e.subtask_id && o.threshold > e.threshold)
+        }) {
+            let title = if event.threshold >= 100 {
+                "⚠️ Task over estimate"
+            } else {
+                "⏳ Task nearing estimate"
+            };
+            let args = serde_wasm_bindgen::to_value(&serde_json::json!({
+                "title": title,
+                "body": event.message()
+            }))
+            .unwrap_or(JsValue::NULL);
+
+            let result = invoke("show_system_notification", args).await;
+            if let Ok(Err(e)) = serde_wasm_bindgen::from_value::<Result<String, String>>(result) {
+                console_log!("Failed to send budget notification: {}", e);
+            }
+        }
+        self.budget_alerts.update(|alerts| alerts.extend(events));
+    }
+
     async fn send_session_notification(&self, session_type: SessionType) {
         let session_type_str = session_type.to_string();
         let settings = self.timer_settings.get();
@@ -419,6 +511,7 @@ impl TimerController {
         } else {
             (None, None)
         };
+        let tracked_subtask_id = subtask_id.clone();
 
         // Send notification FIRST (before any async operations)
         let controller_for_notification = self.clone();
@@ -481,6 +574,7 @@ impl TimerController {
                     video_path,
                     task_id,
                     subtask_id,
+                    manual: false,
                 };
 
                 // Save session with task tracking for work sessions
@@ -501,6 +595,7 @@ impl TimerController {
                 video_path: None,
                 task_id,
                 subtask_id,
+                manual: false,
             };
 
             // Save session with task tracking for work sessions
@@ -514,6 +609,11 @@ impl TimerController {
         // Reload task stats if task controller is available
         if let Some(task_ctrl) = task_controller {
             task_ctrl.load_task_stats();
+            if session_type == SessionType::Work {
+                if let Some(subtask_id) = tracked_subtask_id.as_deref() {
+                    task_ctrl.request_subtask_outcome(subtask_id);
+                }
+            }
         }
 
         // Switch to next session type
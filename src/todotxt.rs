@@ -0,0 +1,932 @@
+use crate::console_log;
+use crate::task::TaskController;
+use crate::types::{
+    find_or_create_projects, get_all_tasks, save_all_tasks, save_task_to_db, ExternalRef, NewTask,
+    SyncConflict, Task, TaskPriority,
+};
+use crate::utils::{get_today_date_string, parse_date_key};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{window, Storage};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
+    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"])]
+    async fn listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> JsValue;
+}
+
+pub const TODOTXT_SOURCE: &str = "todotxt";
+
+// Matches CONFLICT_ERROR_PREFIX in the backend, which uses it for every sync write
+pub const CONFLICT_ERROR_PREFIX: &str = "conflict:";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TodoTxtSettings {
+    pub enabled: bool,
+    pub path: String, // mirrors the backend config, the only file the backend will open
+}
+
+impl TodoTxtSettings {
+    pub fn save_to_storage(&self) {
+        if let Ok(settings_json) = serde_json::to_string(&self) {
+            if let Some(storage) = get_local_storage() {
+                let _ = storage.set_item("pomodoro_todotxt_settings", &settings_json);
+            }
+        }
+    }
+
+    pub fn load_from_storage() -> Self {
+        if let Some(storage) = get_local_storage() {
+            if let Ok(Some(settings_json)) = storage.get_item("pomodoro_todotxt_settings") {
+                if let Ok(settings) = serde_json::from_str::<TodoTxtSettings>(&settings_json) {
+                    return settings;
+                }
+            }
+        }
+        Self::default()
+    }
+
+    fn is_active(&self) -> bool {
+        self.enabled && !self.path.trim().is_empty()
+    }
+}
+
+fn get_local_storage() -> Option<Storage> {
+    window()?.local_storage().ok()?
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TodoFileSnapshot {
+    pub path: String,
+    pub content: String,
+    pub revision: String,
+}
+
+// One line of a todo.txt file
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoItem {
+    pub completed: bool,
+    pub completion_date: Option<String>,
+    pub priority: Option<char>,
+    pub creation_date: Option<String>,
+    pub description: String, // text without projects, contexts and known key:value tags
+    pub projects: Vec<String>,
+    pub contexts: Vec<String>,
+    pub estimate: Option<u32>, // pomo:N
+    pub due_date: Option<String>,
+    pub id: Option<String>, // id:X, links the line to its task
+}
+
+impl TodoItem {
+    pub fn task_priority(&self) -> TaskPriority {
+        match self.priority {
+            Some('A') => TaskPriority::High,
+            Some('B') | None => TaskPriority::Medium,
+            Some(_) => TaskPriority::Low,
+        }
+    }
+}
+
+fn is_date_key(token: &str) -> bool {
+    token.len() == 10 && parse_date_key(token).is_some()
+}
+
+fn parse_priority(token: &str) -> Option<char> {
+    let bytes = token.as_bytes();
+    if bytes.len() == 3 && bytes[0] == b'(' && bytes[2] == b')' && bytes[1].is_ascii_uppercase() {
+        Some(bytes[1] as char)
+    } else {
+        None
+    }
+}
+
+// Splits a line into (completed, completion date, priority, creation date, remaining tokens)
+fn split_header(line: &str) -> (bool, Option<&str>, Option<char>, Option<&str>, Vec<&str>) {
+    let mut tokens: Vec<&str> = line.split_whitespace().collect();
+    let mut completed = false;
+    let mut completion_date = None;
+    let mut priority = None;
+    let mut creation_date = None;
+
+    if tokens.first() == Some(&"x") {
+        completed = true;
+        tokens.remove(0);
+        if tokens.first().is_some_and(|t| is_date_key(t)) {
+            completion_date = Some(tokens.remove(0));
+        }
+    }
+    if let Some(p) = tokens.first().and_then(|t| parse_priority(t)) {
+        priority = Some(p);
+        tokens.remove(0);
+    }
+    if tokens.first().is_some_and(|t| is_date_key(t)) {
+        creation_date = Some(tokens.remove(0));
+    }
+
+    (completed, completion_date, priority, creation_date, tokens)
+}
+
+fn key_value(token: &str) -> Option<(&str, &str)> {
+    let (key, value) = token.split_once(':')?;
+    // URLs are text, not tags
+    if key.is_empty() || value.is_empty() || value.starts_with("//") {
+        None
+    } else {
+        Some((key, value))
+    }
+}
+
+pub fn parse_todo_line(line: &str) -> Option<TodoItem> {
+    if line.trim().is_empty() {
+        return None;
+    }
+
+    let (completed, completion_date, mut priority, creation_date, tokens) = split_header(line);
+    let mut item = TodoItem {
+        completed,
+        completion_date: completion_date.map(str::to_string),
+        priority: None,
+        creation_date: creation_date.map(str::to_string),
+        description: String::new(),
+        projects: Vec::new(),
+        contexts: Vec::new(),
+        estimate: None,
+        due_date: None,
+        id: None,
+    };
+
+    let mut words = Vec::new();
+    for token in tokens {
+        if let Some(project) = token.strip_prefix('+').filter(|p| !p.is_empty()) {
+            item.projects.push(project.to_string());
+        } else if let Some(context) = token.strip_prefix('@').filter(|c| !c.is_empty()) {
+            item.contexts.push(context.to_string());
+        } else {
+            match key_value(token) {
+                Some(("pomo", value)) if value.parse::<u32>().is_ok() => item.estimate = value.parse().ok(),
+                Some(("due", value)) if is_date_key(value) => item.due_date = Some(value.to_string()),
+                Some(("id", value)) if item.id.is_none() => item.id = Some(value.to_string()),
+                // Completed lines keep their priority as pri:X
+                Some(("pri", value)) if priority.is_none() => priority = parse_priority(&format!("({})", value)),
+                _ => words.push(token),
+            }
+        }
+    }
+
+    item.priority = priority;
+    item.description = words.join(" ");
+    Some(item)
+}
+
+// Body of a line without its completion, priority and id tag. Only used to recognise lines
+// that have no id: tag yet by comparing them with the line last synced to a task.
+pub fn todo_sync_key(line: &str) -> Option<String> {
+    if line.trim().is_empty() {
+        return None;
+    }
+    let (_, _, _, _, tokens) = split_header(line);
+    let body: Vec<&str> = tokens
+        .into_iter()
+        .filter(|t| !matches!(key_value(t), Some(("pri", _)) | Some(("id", _))))
+        .collect();
+    Some(body.join(" "))
+}
+
+pub fn todo_line_id(line: &str) -> Option<String> {
+    parse_todo_line(line)?.id
+}
+
+// Appends " id:X" unless the line already carries an id
+pub fn with_line_id(line: &str, id: &str) -> String {
+    let line = line.trim();
+    if todo_line_id(line).is_some() {
+        line.to_string()
+    } else {
+        format!("{} id:{}", line, id)
+    }
+}
+
+// Whether `line` is the file's version of the task linked through `external`
+fn is_linked_line(line: &str, external: &ExternalRef) -> bool {
+    match todo_line_id(line) {
+        Some(id) => id == external.id,
+        None => external.etag.as_deref().and_then(todo_sync_key) == todo_sync_key(line),
+    }
+}
+
+// A line of the file and the index of the linked task it belongs to, if any
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoLine {
+    pub index: usize, // line number in the file, from 0
+    pub line: String,
+    pub item: TodoItem,
+    pub task: Option<usize>,
+}
+
+// Pairs every line with its task by id: tag, falling back to the last synced line for lines
+// without one. A task is claimed by its first line only; later copies of that line are dropped.
+pub fn link_todo_lines(content: &str, tasks: &[Task]) -> Vec<TodoLine> {
+    let mut claimed: Vec<usize> = Vec::new();
+    let mut seen_ids: Vec<String> = Vec::new();
+    let mut lines = Vec::new();
+
+    for (index, raw) in content.lines().enumerate() {
+        let Some(item) = parse_todo_line(raw) else {
+            continue;
+        };
+        if let Some(id) = &item.id {
+            if seen_ids.contains(id) {
+                continue;
+            }
+            seen_ids.push(id.clone());
+        }
+
+        let line = raw.trim().to_string();
+        let task = tasks.iter().enumerate().position(|(i, t)| {
+            !claimed.contains(&i)
+                && t.external
+                    .as_ref()
+                    .is_some_and(|e| e.source == TODOTXT_SOURCE && is_linked_line(&line, e))
+        });
+        if let Some(task) = task {
+            claimed.push(task);
+        }
+        lines.push(TodoLine { index, line, item, task });
+    }
+
+    lines
+}
+
+// Marks a line done as "x YYYY-MM-DD …" (moving its priority to pri:X) or reopens it
+pub fn set_line_completed(line: &str, completed: bool, today: &str) -> String {
+    let (is_completed, _, priority, creation_date, tokens) = split_header(line);
+    if is_completed == completed {
+        return line.to_string();
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    let mut body: Vec<String> = Vec::new();
+    let mut stored_priority = None;
+    for token in tokens {
+        match key_value(token) {
+            Some(("pri", value)) => stored_priority = Some(value.to_string()),
+            _ => body.push(token.to_string()),
+        }
+    }
+
+    if completed {
+        parts.push("x".to_string());
+        parts.push(today.to_string());
+        parts.extend(creation_date.map(str::to_string));
+        parts.extend(body);
+        if let Some(p) = priority {
+            parts.push(format!("pri:{}", p));
+        }
+    } else {
+        if let Some(p) = stored_priority {
+            parts.push(format!("({})", p));
+        }
+        parts.extend(creation_date.map(str::to_string));
+        parts.extend(body);
+    }
+
+    parts.join(" ")
+}
+
+fn apply_item_to_task(task: &mut Task, item: &TodoItem, line: &str, project_id: Option<String>) {
+    if !item.description.is_empty() {
+        task.name = item.description.clone();
+    }
+    task.completed = item.completed;
+    task.priority = item.task_priority();
+    task.estimated_pomodoros = item.estimate;
+    task.due_date = item.due_date.clone();
+    task.tags = item.contexts.clone();
+    if project_id.is_some() {
+        task.project_id = project_id;
+    }
+    if let Some(external) = task.external.as_mut() {
+        external.etag = Some(line.to_string());
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TodoSyncSummary {
+    pub created: u32,
+    pub updated: u32,
+    pub conflicts: Vec<SyncConflict>,
+    pub content: Option<String>, // file content with id: tags added, when any line lacked one
+}
+
+// Replaces whole lines by number, keeping the file's own line endings
+fn rewrite_lines(content: &str, replacements: &[(usize, String)]) -> String {
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut lines: Vec<String> = content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| match replacements.iter().find(|(i, _)| *i == index) {
+            Some((_, replacement)) => replacement.clone(),
+            None => line.to_string(),
+        })
+        .collect();
+    if content.ends_with('\n') {
+        lines.push(String::new());
+    }
+    lines.join(newline)
+}
+
+// Brings tasks in line with the file: new lines become tasks, changed lines update their task.
+// Lines without an id: tag get one, returned in `content` for writing back to the file.
+pub async fn apply_todo_snapshot(content: &str) -> Result<TodoSyncSummary, String> {
+    let today = get_today_date_string();
+    let mut summary = TodoSyncSummary::default();
+
+    let mut project_names: Vec<String> = Vec::new();
+    for item in content.lines().filter_map(parse_todo_line) {
+        if let Some(name) = item.projects.first() {
+            if !project_names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                project_names.push(name.clone());
+            }
+        }
+    }
+    let project_ids = find_or_create_projects(&project_names).await?;
+    let project_for = |item: &TodoItem| {
+        item.projects.first().and_then(|name| {
+            project_ids
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, id)| id.clone())
+        })
+    };
+
+    let mut tasks = get_all_tasks().await?;
+    let lines = link_todo_lines(content, &tasks);
+    let mut rewrites: Vec<(usize, String)> = Vec::new();
+    let mut new_lines = Vec::new();
+
+    for todo in &lines {
+        let Some(task) = todo.task.map(|i| &mut tasks[i]) else {
+            new_lines.push(todo);
+            continue;
+        };
+
+        // Lines matched by their previous text are tagged with the task's id from now on
+        let line = match &todo.item.id {
+            Some(_) => todo.line.clone(),
+            None => {
+                let tagged = with_line_id(&todo.line, &task.id);
+                rewrites.push((todo.index, tagged.clone()));
+                tagged
+            }
+        };
+        if let Some(external) = task.external.as_mut() {
+            external.id = todo.item.id.clone().unwrap_or_else(|| task.id.clone());
+        }
+
+        let synced_line = task.external.as_ref().and_then(|e| e.etag.clone());
+        if synced_line.as_deref() == Some(line.as_str()) {
+            continue;
+        }
+        if synced_line.as_deref() == Some(todo.line.as_str()) {
+            // Unchanged apart from the id tag just added
+            if let Some(external) = task.external.as_mut() {
+                external.etag = Some(line);
+            }
+            continue;
+        }
+
+        // The app changed completion since the last sync and the file disagrees with it
+        let synced_completed = synced_line.as_deref().and_then(parse_todo_line).map(|i| i.completed);
+        let changed_locally = synced_completed.is_some_and(|c| c != task.completed);
+        if changed_locally && todo.item.completed != task.completed {
+            summary.conflicts.push(SyncConflict {
+                source: TODOTXT_SOURCE.to_string(),
+                task_id: task.id.clone(),
+                task_name: task.name.clone(),
+                local_version: set_line_completed(&line, task.completed, &today),
+                remote_version: line.clone(),
+                message: "Completed state changed both in the app and in todo.txt".to_string(),
+            });
+            continue;
+        }
+
+        apply_item_to_task(task, &todo.item, &line, project_for(&todo.item));
+        summary.updated += 1;
+    }
+
+    save_all_tasks(&tasks)?;
+
+    let mut created_ids = Vec::new();
+    for todo in &new_lines {
+        let item = &todo.item;
+        let task_id = save_task_to_db(NewTask {
+            name: if item.description.is_empty() { todo.line.clone() } else { item.description.clone() },
+            description: None,
+            color: Task::get_random_color(),
+            estimated_pomodoros: item.estimate,
+            due_date: item.due_date.clone(),
+            priority: item.task_priority(),
+            recurrence: None,
+            project_id: project_for(item),
+        })
+        .await?;
+        created_ids.push(task_id);
+    }
+
+    // Link the new tasks to their lines in one write
+    if !created_ids.is_empty() {
+        let mut tasks = get_all_tasks().await?;
+        for (task_id, todo) in created_ids.iter().zip(&new_lines) {
+            let line = with_line_id(&todo.line, task_id);
+            if todo.item.id.is_none() {
+                rewrites.push((todo.index, line.clone()));
+            }
+            if let Some(task) = tasks.iter_mut().find(|t| &t.id == task_id) {
+                task.external = Some(ExternalRef {
+                    source: TODOTXT_SOURCE.to_string(),
+                    id: todo.item.id.clone().unwrap_or_else(|| task_id.clone()),
+                    url: None,
+                    etag: None,
+                });
+                apply_item_to_task(task, &todo.item, &line, None);
+            }
+        }
+        save_all_tasks(&tasks)?;
+        summary.created = created_ids.len() as u32;
+    }
+
+    if !rewrites.is_empty() {
+        summary.content = Some(rewrite_lines(content, &rewrites));
+    }
+
+    Ok(summary)
+}
+
+async fn todo_file_path() -> Result<Option<String>, String> {
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({})).map_err(|e| e.to_string())?;
+    let result = invoke("todo_txt_path", args).await;
+    serde_wasm_bindgen::from_value::<Result<Option<String>, String>>(result)
+        .map_err(|_| "Unexpected response from todo_txt_path".to_string())?
+}
+
+// Saves the file on the backend and returns the path as it was stored
+async fn set_todo_file_path(path: &str) -> Result<Option<String>, String> {
+    let path = Some(path).filter(|p| !p.is_empty());
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({ "path": path }))
+        .map_err(|e| e.to_string())?;
+    let result = invoke("set_todo_txt_path", args).await;
+    serde_wasm_bindgen::from_value::<Result<Option<String>, String>>(result)
+        .map_err(|_| "Unexpected response from set_todo_txt_path".to_string())?
+}
+
+async fn read_todo_file(path: &str) -> Result<TodoFileSnapshot, String> {
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({ "path": path }))
+        .map_err(|e| e.to_string())?;
+    let result = invoke("read_todo_txt", args).await;
+    serde_wasm_bindgen::from_value::<Result<TodoFileSnapshot, String>>(result)
+        .map_err(|_| "Unexpected response from read_todo_txt".to_string())?
+}
+
+async fn write_todo_file(path: &str, content: &str, expected_revision: &str) -> Result<TodoFileSnapshot, String> {
+    let args = serde_wasm_bindgen::to_value(&serde_json::json!({
+        "path": path,
+        "content": content,
+        "expectedRevision": expected_revision,
+    }))
+    .map_err(|e| e.to_string())?;
+    let result = invoke("write_todo_txt", args).await;
+    serde_wasm_bindgen::from_value::<Result<TodoFileSnapshot, String>>(result)
+        .map_err(|_| "Unexpected response from write_todo_txt".to_string())?
+}
+
+fn find_line_index(content: &str, external: &ExternalRef) -> Option<usize> {
+    content
+        .lines()
+        .position(|line| !line.trim().is_empty() && is_linked_line(line.trim(), external))
+}
+
+fn find_line<'a>(content: &'a str, external: &ExternalRef) -> Option<&'a str> {
+    let index = find_line_index(content, external)?;
+    content.lines().nth(index).map(str::trim)
+}
+
+fn conflict_for(task: &Task, local_version: String, remote_version: String, message: String) -> SyncConflict {
+    SyncConflict {
+        source: TODOTXT_SOURCE.to_string(),
+        task_id: task.id.clone(),
+        task_name: task.name.clone(),
+        local_version,
+        remote_version,
+        message,
+    }
+}
+
+// Writes the task's completed state back to its todo.txt line.
+// Returns a conflict instead of writing when the line changed since the last sync.
+pub async fn write_back_completion(task: &Task) -> Result<Option<SyncConflict>, String> {
+    let settings = TodoTxtSettings::load_from_storage();
+    let Some(external) = task.external.as_ref().filter(|e| e.source == TODOTXT_SOURCE) else {
+        return Ok(None);
+    };
+    if !settings.is_active() {
+        return Ok(None);
+    }
+
+    let snapshot = read_todo_file(&settings.path).await?;
+    let Some(current_line) = find_line(&snapshot.content, external) else {
+        return Err(format!("\"{}\" is no longer in todo.txt", task.name));
+    };
+
+    let new_line = with_line_id(
+        &set_line_completed(current_line, task.completed, &get_today_date_string()),
+        &external.id,
+    );
+    if external.etag.as_deref() != Some(current_line) {
+        return Ok(Some(conflict_for(
+            task,
+            new_line,
+            current_line.to_string(),
+            "The todo.txt line was edited since the last sync".to_string(),
+        )));
+    }
+
+    write_line(task, &settings.path, &snapshot, external, &new_line).await
+}
+
+async fn write_line(
+    task: &Task,
+    path: &str,
+    snapshot: &TodoFileSnapshot,
+    external: &ExternalRef,
+    new_line: &str,
+) -> Result<Option<SyncConflict>, String> {
+    let index = find_line_index(&snapshot.content, external)
+        .ok_or_else(|| format!("\"{}\" is no longer in todo.txt", task.name))?;
+    let content = rewrite_lines(&snapshot.content, &[(index, new_line.to_string())]);
+
+    match write_todo_file(path, &content, &snapshot.revision).await {
+        Ok(_) => {
+            let mut tasks = get_all_tasks().await?;
+            if let Some(stored) = tasks.iter_mut().find(|t| t.id == task.id) {
+                if let Some(external) = stored.external.as_mut() {
+                    external.etag = Some(new_line.to_string());
+                }
+            }
+            save_all_tasks(&tasks)?;
+            Ok(None)
+        }
+        Err(e) if e.starts_with(CONFLICT_ERROR_PREFIX) => Ok(Some(conflict_for(
+            task,
+            new_line.to_string(),
+            find_line(&snapshot.content, external).unwrap_or_default().to_string(),
+            e.trim_start_matches(CONFLICT_ERROR_PREFIX).trim().to_string(),
+        ))),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Clone)]
+pub struct TodoTxtSync {
+    pub settings: RwSignal<TodoTxtSettings>,
+    pub status: RwSignal<Option<String>>,
+    pub syncing: RwSignal<bool>,
+    task_controller: TaskController,
+}
+
+impl TodoTxtSync {
+    pub fn new(task_controller: TaskController) -> Self {
+        let sync = Self {
+            settings: RwSignal::new(TodoTxtSettings::load_from_storage()),
+            status: RwSignal::new(None),
+            syncing: RwSignal::new(false),
+            task_controller,
+        };
+
+        sync.listen_for_changes();
+
+        let startup = sync.clone();
+        spawn_local(async move {
+            let settings = startup.settings.get_untracked();
+            match todo_file_path().await {
+                Ok(Some(path)) if path != settings.path => {
+                    let settings = TodoTxtSettings { path, ..settings };
+                    settings.save_to_storage();
+                    startup.settings.set(settings);
+                }
+                // Older versions kept the path in localStorage only
+                Ok(None) if !settings.path.is_empty() => {
+                    if let Err(e) = set_todo_file_path(&settings.path).await {
+                        console_log!("Error saving the todo.txt path: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => console_log!("Error reading the todo.txt path: {}", e),
+            }
+            startup.start();
+        });
+
+        sync
+    }
+
+    pub fn update_settings(&self, settings: TodoTxtSettings) {
+        let sync = self.clone();
+        spawn_local(async move {
+            match set_todo_file_path(&settings.path).await {
+                Ok(path) => {
+                    let settings = TodoTxtSettings { path: path.unwrap_or_default(), ..settings };
+                    settings.save_to_storage();
+                    sync.settings.set(settings);
+                    sync.start();
+                }
+                Err(e) => sync.status.set(Some(format!("Error: {}", e))),
+            }
+        });
+    }
+
+    // (Re)starts the backend watcher for the configured file and runs a first sync
+    fn start(&self) {
+        let sync = self.clone();
+        spawn_local(async move {
+            let settings = sync.settings.get_untracked();
+            let path = settings.is_active().then(|| settings.path.clone());
+
+            let args = serde_wasm_bindgen::to_value(&serde_json::json!({ "path": path }))
+                .unwrap_or(JsValue::NULL);
+            let result = invoke("watch_todo_txt", args).await;
+            match serde_wasm_bindgen::from_value::<Result<String, String>>(result) {
+                Ok(Ok(message)) => console_log!("📝 {}", message),
+                Ok(Err(e)) => console_log!("Failed to watch todo.txt: {}", e),
+                Err(_) => console_log!("Unexpected response from watch_todo_txt"),
+            }
+
+            if settings.is_active() {
+                sync.sync_now();
+            }
+        });
+    }
+
+    fn listen_for_changes(&self) {
+        let sync = self.clone();
+        spawn_local(async move {
+            let handler_sync = sync.clone();
+            let handler = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+                let payload = js_sys::Reflect::get(&event, &"payload".into()).unwrap_or(JsValue::NULL);
+                match serde_wasm_bindgen::from_value::<TodoFileSnapshot>(payload) {
+                    Ok(snapshot) => {
+                        let settings = handler_sync.settings.get_untracked();
+                        if settings.is_active() && snapshot.path == settings.path {
+                            console_log!("📝 todo.txt changed on disk, syncing");
+                            handler_sync.apply(snapshot);
+                        }
+                    }
+                    Err(e) => console_log!("Invalid todo.txt change event: {:?}", e),
+                }
+            });
+
+            listen("todotxt-changed", &handler).await;
+            // The listener lives for the whole app session
+            handler.forget();
+        });
+    }
+
+    pub fn sync_now(&self) {
+        let sync = self.clone();
+        spawn_local(async move {
+            let path = sync.settings.get_untracked().path;
+            match read_todo_file(&path).await {
+                Ok(snapshot) => sync.apply(snapshot),
+                Err(e) => {
+                    console_log!("Error reading todo.txt: {}", e);
+                    sync.status.set(Some(format!("Error: {}", e)));
+                }
+            }
+        });
+    }
+
+    fn apply(&self, snapshot: TodoFileSnapshot) {
+        let sync = self.clone();
+        spawn_local(async move {
+            sync.syncing.set(true);
+            match apply_todo_snapshot(&snapshot.content).await {
+                Ok(summary) => {
+                    sync.status.set(Some(format!(
+                        "Synced at {}: {} new, {} updated, {} conflict(s)",
+                        js_sys::Date::new_0().to_locale_time_string("en-US"),
+                        summary.created,
+                        summary.updated,
+                        summary.conflicts.len()
+                    )));
+                    sync.record_conflicts(summary.conflicts);
+
+                    // Newly tagged lines go back to the file; if it changed meanwhile the watcher syncs again
+                    if let Some(content) = summary.content {
+                        if let Err(e) = write_todo_file(&snapshot.path, &content, &snapshot.revision).await {
+                            console_log!("Could not add ids to todo.txt: {}", e);
+                        }
+                    }
+
+                    sync.task_controller.load_projects();
+                    sync.task_controller.load_tasks();
+                    sync.task_controller.load_task_stats();
+                }
+                Err(e) => {
+                    console_log!("Error syncing todo.txt: {}", e);
+                    sync.status.set(Some(format!("Error: {}", e)));
+                }
+            }
+            sync.syncing.set(false);
+        });
+    }
+
+    // Replaces earlier todo.txt conflicts for the same tasks
+    fn record_conflicts(&self, conflicts: Vec<SyncConflict>) {
+        self.task_controller.sync_conflicts.update(|existing| {
+            for conflict in conflicts {
+                existing.retain(|c| !(c.source == conflict.source && c.task_id == conflict.task_id));
+                existing.push(conflict);
+            }
+        });
+    }
+
+    pub fn resolve_conflict(&self, conflict: SyncConflict, keep_local: bool) {
+        let sync = self.clone();
+        spawn_local(async move {
+            let result = if keep_local {
+                sync.overwrite_file_line(&conflict).await
+            } else {
+                sync.accept_file_line(&conflict).await
+            };
+
+            match result {
+                Ok(None) => {
+                    sync.task_controller
+                        .sync_conflicts
+                        .update(|list| list.retain(|c| c != &conflict));
+                    sync.task_controller.load_tasks();
+                    sync.task_controller.load_task_stats();
+                }
+                Ok(Some(new_conflict)) => sync.record_conflicts(vec![new_conflict]),
+                Err(e) => {
+                    console_log!("Error resolving todo.txt conflict: {}", e);
+                    sync.status.set(Some(format!("Error: {}", e)));
+                }
+            }
+        });
+    }
+
+    async fn accept_file_line(&self, conflict: &SyncConflict) -> Result<Option<SyncConflict>, String> {
+        let item = parse_todo_line(&conflict.remote_version).ok_or("Empty todo.txt line")?;
+        let mut tasks = get_all_tasks().await?;
+        let task = tasks
+            .iter_mut()
+            .find(|t| t.id == conflict.task_id)
+            .ok_or("Task not found")?;
+        apply_item_to_task(task, &item, &conflict.remote_version, None);
+        save_all_tasks(&tasks)?;
+        Ok(None)
+    }
+
+    async fn overwrite_file_line(&self, conflict: &SyncConflict) -> Result<Option<SyncConflict>, String> {
+        let path = self.settings.get_untracked().path;
+        let task = get_all_tasks()
+            .await?
+            .into_iter()
+            .find(|t| t.id == conflict.task_id)
+            .ok_or("Task not found")?;
+        let external = task.external.as_ref().ok_or("Task is not linked to todo.txt")?;
+
+        let snapshot = read_todo_file(&path).await?;
+        let current_line = find_line(&snapshot.content, external)
+            .ok_or_else(|| format!("\"{}\" is no longer in todo.txt", task.name))?;
+        let new_line = with_line_id(
+            &set_line_completed(current_line, task.completed, &get_today_date_string()),
+            &external.id,
+        );
+
+        write_line(&task, &path, &snapshot, external, &new_line).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::task;
+
+    fn linked_task(id: &str, external_id: &str, synced_line: &str) -> Task {
+        let mut linked = task(id, "Linked");
+        linked.external = Some(ExternalRef {
+            source: TODOTXT_SOURCE.to_string(),
+            id: external_id.to_string(),
+            url: None,
+            etag: Some(synced_line.to_string()),
+        });
+        linked
+    }
+
+    #[test]
+    fn parses_header_projects_contexts_and_tags() {
+        let item = parse_todo_line("(A) 2024-03-01 Call Mom +Family @phone pomo:2 due:2024-03-05 id:t1 see http://x.y").unwrap();
+
+        assert!(!item.completed);
+        assert_eq!(item.priority, Some('A'));
+        assert_eq!(item.creation_date.as_deref(), Some("2024-03-01"));
+        assert_eq!(item.description, "Call Mom see http://x.y");
+        assert_eq!(item.projects, vec!["Family"]);
+        assert_eq!(item.contexts, vec!["phone"]);
+        assert_eq!(item.estimate, Some(2));
+        assert_eq!(item.due_date.as_deref(), Some("2024-03-05"));
+        assert_eq!(item.id.as_deref(), Some("t1"));
+        assert_eq!(item.task_priority(), TaskPriority::High);
+    }
+
+    #[test]
+    fn parses_completed_line_with_stored_priority() {
+        let item = parse_todo_line("x 2024-03-02 2024-03-01 Call Mom pri:C").unwrap();
+
+        assert!(item.completed);
+        assert_eq!(item.completion_date.as_deref(), Some("2024-03-02"));
+        assert_eq!(item.creation_date.as_deref(), Some("2024-03-01"));
+        assert_eq!(item.priority, Some('C'));
+        assert_eq!(item.description, "Call Mom");
+        assert_eq!(item.task_priority(), TaskPriority::Low);
+    }
+
+    #[test]
+    fn keeps_invalid_tags_as_text() {
+        let item = parse_todo_line("Plan pomo:lots due:soon").unwrap();
+
+        assert_eq!(item.description, "Plan pomo:lots due:soon");
+        assert_eq!(item.estimate, None);
+        assert_eq!(item.due_date, None);
+        assert!(parse_todo_line("   ").is_none());
+    }
+
+    #[test]
+    fn completing_and_reopening_round_trips() {
+        let line = "(B) 2024-03-01 Write docs +Work id:t1";
+
+        let done = set_line_completed(line, true, "2024-03-04");
+        assert_eq!(done, "x 2024-03-04 2024-03-01 Write docs +Work id:t1 pri:B");
+        assert_eq!(set_line_completed(&done, true, "2024-03-05"), done);
+
+        let reopened = set_line_completed(&done, false, "2024-03-05");
+        assert_eq!(reopened, line);
+        assert_eq!(parse_todo_line(&reopened), parse_todo_line(line));
+    }
+
+    #[test]
+    fn line_ids_are_added_once() {
+        let tagged = with_line_id("  Write docs +Work ", "task_1");
+
+        assert_eq!(tagged, "Write docs +Work id:task_1");
+        assert_eq!(with_line_id(&tagged, "task_2"), tagged);
+        assert_eq!(todo_line_id(&tagged).as_deref(), Some("task_1"));
+        assert_eq!(todo_sync_key(&tagged), todo_sync_key("(A) Write docs +Work"));
+    }
+
+    #[test]
+    fn edited_line_stays_linked_through_its_id() {
+        let tasks = vec![linked_task("task_1", "task_1", "Write docs +Work pomo:2 id:task_1")];
+
+        let lines = link_todo_lines("(A) Write the docs +Home @desk pomo:3 due:2024-04-01 id:task_1\n", &tasks);
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].task, Some(0));
+    }
+
+    #[test]
+    fn untagged_line_falls_back_to_last_synced_line() {
+        let tasks = vec![linked_task("task_1", "Write docs +Work", "Write docs +Work")];
+
+        let unchanged = link_todo_lines("x 2024-03-04 Write docs +Work\n", &tasks);
+        let edited = link_todo_lines("Write more docs +Work\n", &tasks);
+
+        assert_eq!(unchanged[0].task, Some(0));
+        assert_eq!(edited[0].task, None);
+    }
+
+    #[test]
+    fn unknown_and_repeated_lines() {
+        let tasks = vec![linked_task("task_1", "task_1", "Write docs id:task_1")];
+        let content = "Write docs id:task_1\nWrite docs id:task_1\n\nBrand new id:mine\nUntagged\n";
+
+        let lines = link_todo_lines(content, &tasks);
+
+        let linked: Vec<(usize, Option<usize>)> = lines.iter().map(|l| (l.index, l.task)).collect();
+        assert_eq!(linked, vec![(0, Some(0)), (3, None), (4, None)]);
+        assert_eq!(lines[1].item.id.as_deref(), Some("mine"));
+    }
+
+    #[test]
+    fn rewrites_lines_keeping_line_endings() {
+        let replacements = [(1, "b id:2".to_string())];
+
+        assert_eq!(rewrite_lines("a\r\nb\r\n", &replacements), "a\r\nb id:2\r\n");
+        assert_eq!(rewrite_lines("a\nb", &replacements), "a\nb id:2");
+    }
+}
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use web_sys::{window, Storage};
 use crate::console_log;
+use crate::utils::{
+    add_days_to_date_key, date_key_from_days, date_key_from_iso, date_key_to_days, days_in_month,
+    format_duration_hours_minutes, format_iso_date, get_today_date_string, local_day_bounds_ms,
+    parse_date_key, set_day_start_hour, week_start_of_date_key, weekday_of_date_key,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TimerState {
@@ -25,6 +31,16 @@ pub struct TimerSettings {
     pub sessions_before_long_break: u32,
     pub auto_start_breaks: bool,
     pub auto_start_work: bool,
+    #[serde(default = "default_workday_end_hour")]
+    pub workday_end_hour: u32, // Hour of day (1-24) used to compute remaining daily capacity
+    #[serde(default)]
+    pub auto_advance_subtasks: bool, // select the next open subtask once the current one is done
+    #[serde(default)]
+    pub day_start_hour: u32, // local hour (0-23) at which a new day starts for stats and goals
+}
+
+fn default_workday_end_hour() -> u32 {
+    18
 }
 
 impl Default for TimerSettings {
@@ -37,12 +53,16 @@ impl Default for TimerSettings {
             sessions_before_long_break: 4,  // Long break after every 4 work sessions
             auto_start_breaks: false,
             auto_start_work: false,
+            workday_end_hour: default_workday_end_hour(),
+            auto_advance_subtasks: false,
+            day_start_hour: 0,
         }
     }
 }
 
 impl TimerSettings {
     pub fn save_to_storage(&self) {
+        set_day_start_hour(self.day_start_hour);
         if let Ok(settings_json) = serde_json::to_string(&self) {
             if let Some(storage) = get_local_storage() {
                 let _ = storage.set_item("pomodoro_timer_settings", &settings_json);
@@ -50,16 +70,42 @@ impl TimerSettings {
         }
     }
 
+    // Also applies the stored day-start hour to all date grouping
     pub fn load_from_storage() -> Self {
         if let Some(storage) = get_local_storage() {
             if let Ok(Some(settings_json)) = storage.get_item("pomodoro_timer_settings") {
                 if let Ok(settings) = serde_json::from_str::<TimerSettings>(&settings_json) {
+                    set_day_start_hour(settings.day_start_hour);
                     return settings;
                 }
             }
         }
         Self::default()
     }
+
+    // How many full work sessions (plus the breaks between them) fit into the given minutes
+    pub fn pomodoro_capacity(&self, available_minutes: u32) -> u32 {
+        if self.work_duration_minutes == 0 {
+            return 0;
+        }
+
+        let mut elapsed = 0;
+        let mut count = 0;
+        loop {
+            elapsed += self.work_duration_minutes;
+            if elapsed > available_minutes {
+                break;
+            }
+            count += 1;
+
+            elapsed += match SessionType::Work.next_session(count, self) {
+                SessionType::LongBreak => self.long_break_duration_minutes,
+                SessionType::ShortBreak => self.short_break_duration_minutes,
+                SessionType::Work => 0,
+            };
+        }
+        count
+    }
 }
 
 impl SessionType {
@@ -143,6 +189,10 @@ pub struct Session {
     pub video_path: Option<String>,
     pub task_id: Option<String>,
     pub subtask_id: Option<String>,
+    #[serde(default)]
+    pub manual: bool, // logged after the fact instead of timed
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -156,6 +206,8 @@ pub struct NewSession {
     pub video_path: Option<String>,
     pub task_id: Option<String>,
     pub subtask_id: Option<String>,
+    #[serde(default)]
+    pub manual: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -217,6 +269,327 @@ pub struct SessionStats {
     pub long_break_sessions: u32,
     pub average_session_duration: f64,
     pub completion_rate: f64,
+    pub project_stats: Vec<ProjectSessionStats>,
+}
+
+// Number of recent weeks shown in per-project focus rollups
+pub const PROJECT_ROLLUP_WEEKS: usize = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSessionStats {
+    pub project_id: Option<String>, // None collects sessions without a project
+    pub name: String,
+    pub color: String,
+    pub pomodoros: u32, // completed work sessions
+    pub total_focus_time: u32,
+    pub weekly_focus: Vec<(String, u32)>, // (Monday YYYY-MM-DD, focus seconds), oldest first
+}
+
+// Week ranges offered by the focus charts
+pub const CHART_WEEK_OPTIONS: [usize; 4] = [4, 8, 12, 26];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskFocusSegment {
+    pub task_id: Option<String>, // None collects work without a task
+    pub name: String,
+    pub color: String,
+    pub focus_time: u32, // seconds
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeeklyChartPoint {
+    pub week_start: String, // Monday YYYY-MM-DD
+    pub segments: Vec<TaskFocusSegment>, // focus per task, largest first
+    pub completed_sessions: u32,
+    pub total_sessions: u32,
+}
+
+impl WeeklyChartPoint {
+    pub fn focus_time(&self) -> u32 {
+        self.segments.iter().map(|s| s.focus_time).sum()
+    }
+
+    // None for weeks without any sessions
+    pub fn completion_rate(&self) -> Option<f64> {
+        (self.total_sessions > 0)
+            .then(|| self.completed_sessions as f64 / self.total_sessions as f64 * 100.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FocusCharts {
+    pub by_hour: [u32; 24],   // focus seconds per local hour of day
+    pub by_weekday: [u32; 7], // focus seconds per weekday, Monday first
+    pub weeks: Vec<WeeklyChartPoint>, // oldest first
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyReportTask {
+    pub name: String,
+    pub pomodoros: u32,  // completed work sessions this week
+    pub focus_time: u32, // seconds this week
+    pub estimated_pomodoros: Option<u32>,
+    pub total_pomodoros: u32, // completed work sessions overall, compared against the estimate
+}
+
+// Aggregated week sent to the backend, which renders it as Markdown and HTML
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyReport {
+    pub week_start: String, // Monday YYYY-MM-DD
+    pub week_end: String,   // Sunday YYYY-MM-DD
+    pub total_focus_time: u32,
+    pub pomodoros: u32,
+    pub completed_sessions: u32,
+    pub interruptions: u32, // sessions stopped before they finished
+    pub interrupted_time: u32,
+    pub tasks: Vec<WeeklyReportTask>,
+    pub best_hours: Vec<(u32, u32)>,     // (hour of day, focus seconds), best first
+    pub daily_focus: Vec<(String, u32)>, // (YYYY-MM-DD, focus seconds), Monday first
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub scope_creep: Vec<WeeklyReportBudgetEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyReportBudgetEvent {
+    pub date: String, // YYYY-MM-DD the threshold was crossed
+    pub name: String,
+    pub threshold: u32,
+    pub estimated_pomodoros: u32,
+    pub actual_pomodoros: u32,
+}
+
+pub const TIMESHEET_ROUNDING_OPTIONS: [u32; 5] = [0, 1, 6, 15, 30]; // minutes, 0 bills exact time
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum RoundingMode {
+    #[default]
+    Up,
+    Nearest,
+}
+
+impl RoundingMode {
+    pub fn describe(&self, increment_minutes: u32) -> String {
+        match (increment_minutes, self) {
+            (0, _) => "Exact time".to_string(),
+            (minutes, RoundingMode::Up) => format!("Rounded up to {} min per task and day", minutes),
+            (minutes, RoundingMode::Nearest) => format!("Rounded to the nearest {} min per task and day", minutes),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimesheetSettings {
+    pub rounding_minutes: u32,
+    pub rounding_mode: RoundingMode,
+    pub currency: String,
+}
+
+impl Default for TimesheetSettings {
+    fn default() -> Self {
+        Self {
+            rounding_minutes: 6,
+            rounding_mode: RoundingMode::Up,
+            currency: "USD".to_string(),
+        }
+    }
+}
+
+impl TimesheetSettings {
+    pub fn save_to_storage(&self) {
+        if let Ok(settings_json) = serde_json::to_string(&self) {
+            if let Some(storage) = get_local_storage() {
+                let _ = storage.set_item("pomodoro_timesheet_settings", &settings_json);
+            }
+        }
+    }
+
+    pub fn load_from_storage() -> Self {
+        if let Some(storage) = get_local_storage() {
+            if let Ok(Some(settings_json)) = storage.get_item("pomodoro_timesheet_settings") {
+                if let Ok(settings) = serde_json::from_str::<TimesheetSettings>(&settings_json) {
+                    return settings;
+                }
+            }
+        }
+        Self::default()
+    }
+}
+
+// Work on one task (or subtask) on one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimesheetEntry {
+    pub date: String, // YYYY-MM-DD
+    pub task_id: String,
+    pub subtask_id: Option<String>,
+    pub name: String, // "Task" or "Task → Subtask"
+    pub sessions: u32,
+    pub focus_time: u32,  // seconds tracked
+    pub billed_time: u32, // seconds after rounding
+    pub hourly_rate: Option<f64>,
+    pub amount: Option<f64>, // None when neither the task nor its project has a rate
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimesheetTaskTotal {
+    pub task_id: String,
+    pub name: String,
+    pub focus_time: u32,
+    pub billed_time: u32,
+    pub amount: f64,
+}
+
+// Sent to the backend, which writes it as CSV and a printable HTML invoice
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Timesheet {
+    pub from: String, // YYYY-MM-DD, inclusive
+    pub to: String,   // YYYY-MM-DD, inclusive
+    pub currency: String,
+    pub rounding: String, // description of the rounding rule
+    pub entries: Vec<TimesheetEntry>, // grouped by task, then by day
+    pub tasks: Vec<TimesheetTaskTotal>,
+    pub total_focus_time: u32,
+    pub total_billed_time: u32,
+    pub total_amount: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum GoalMetric {
+    #[default]
+    FocusMinutes,
+    Pomodoros, // completed work sessions
+}
+
+impl GoalMetric {
+    pub fn unit(&self) -> &'static str {
+        match self {
+            GoalMetric::FocusMinutes => "min",
+            GoalMetric::Pomodoros => "pomodoros",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyGoalSettings {
+    pub enabled: bool,
+    pub metric: GoalMetric,
+    pub weekday_target: u32,
+    pub weekend_target: u32, // 0 makes weekends rest days
+    #[serde(default)]
+    pub skipped_days: Vec<String>, // YYYY-MM-DD holidays and days off
+}
+
+impl Default for DailyGoalSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            metric: GoalMetric::FocusMinutes,
+            weekday_target: 120,
+            weekend_target: 0,
+            skipped_days: Vec::new(),
+        }
+    }
+}
+
+impl DailyGoalSettings {
+    pub fn save_to_storage(&self) {
+        if let Ok(settings_json) = serde_json::to_string(&self) {
+            if let Some(storage) = get_local_storage() {
+                let _ = storage.set_item("pomodoro_daily_goal", &settings_json);
+            }
+        }
+    }
+
+    pub fn load_from_storage() -> Self {
+        if let Some(storage) = get_local_storage() {
+            if let Ok(Some(settings_json)) = storage.get_item("pomodoro_daily_goal") {
+                if let Ok(settings) = serde_json::from_str::<DailyGoalSettings>(&settings_json) {
+                    return settings;
+                }
+            }
+        }
+        Self::default()
+    }
+
+    // Goal for a day; 0 means a rest day that neither extends nor breaks a streak
+    pub fn target_for(&self, date_key: &str) -> u32 {
+        if self.skipped_days.iter().any(|d| d == date_key) {
+            return 0;
+        }
+        match weekday_of_date_key(date_key) {
+            Some(0) | Some(6) => self.weekend_target,
+            Some(_) => self.weekday_target,
+            None => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyGoalStatus {
+    pub metric: GoalMetric,
+    pub progress: u32, // today's value in the goal's unit
+    pub target: u32,   // today's goal, 0 on rest days
+    pub current_streak: u32,
+    pub longest_streak: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetAlertSettings {
+    pub enabled: bool,
+    pub thresholds: Vec<u32>, // percent of the estimate, ascending
+}
+
+impl Default for BudgetAlertSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            thresholds: vec![80, 100, 150],
+        }
+    }
+}
+
+impl BudgetAlertSettings {
+    pub fn save_to_storage(&self) {
+        if let Ok(settings_json) = serde_json::to_string(&self) {
+            if let Some(storage) = get_local_storage() {
+                let _ = storage.set_item("pomodoro_budget_alerts", &settings_json);
+            }
+        }
+    }
+
+    pub fn load_from_storage() -> Self {
+        if let Some(storage) = get_local_storage() {
+            if let Ok(Some(settings_json)) = storage.get_item("pomodoro_budget_alerts") {
+                if let Ok(settings) = serde_json::from_str::<BudgetAlertSettings>(&settings_json) {
+                    return settings;
+                }
+            }
+        }
+        Self::default()
+    }
+}
+
+// A task or subtask crossing one of the budget thresholds, kept so reports can show scope creep
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetEvent {
+    pub id: String,
+    pub task_id: String,
+    pub subtask_id: Option<String>,
+    pub name: String, // "Task" or "Task → Subtask" when the threshold was crossed
+    pub threshold: u32,
+    pub estimated_pomodoros: u32,
+    pub actual_pomodoros: u32, // completed work sessions at that point
+    pub session_id: String,
+    pub created_at: String,
+}
+
+impl BudgetEvent {
+    pub fn message(&self) -> String {
+        format!(
+            "{} reached {}% of its estimate ({} of {} pomodoros)",
+            self.name, self.threshold, self.actual_pomodoros, self.estimated_pomodoros
+        )
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -227,6 +600,111 @@ pub struct SessionQuery {
     pub completed: Option<bool>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub enum TaskPriority {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl TaskPriority {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TaskPriority::Low => "Low",
+            TaskPriority::Medium => "Medium",
+            TaskPriority::High => "High",
+        }
+    }
+
+    pub fn badge_class(&self) -> &'static str {
+        match self {
+            TaskPriority::Low => "bg-gray-100 dark:bg-gray-700 text-gray-600 dark:text-gray-300",
+            TaskPriority::Medium => "bg-yellow-100 dark:bg-yellow-800 text-yellow-800 dark:text-yellow-200",
+            TaskPriority::High => "bg-red-100 dark:bg-red-800 text-red-800 dark:text-red-200",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Self {
+        match s {
+            "Low" => TaskPriority::Low,
+            "High" => TaskPriority::High,
+            _ => TaskPriority::Medium,
+        }
+    }
+
+    pub fn all() -> Vec<TaskPriority> {
+        vec![TaskPriority::High, TaskPriority::Medium, TaskPriority::Low]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RecurrenceRule {
+    Daily,
+    Weekdays,
+    Weekly { days: Vec<u32> }, // 0 = Sunday .. 6 = Saturday
+    Monthly { day_of_month: u32 },
+}
+
+pub const WEEKDAY_NAMES: &[&str] = &["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+impl RecurrenceRule {
+    pub fn describe(&self) -> String {
+        match self {
+            RecurrenceRule::Daily => "Daily".to_string(),
+            RecurrenceRule::Weekdays => "Weekdays".to_string(),
+            RecurrenceRule::Weekly { days } => {
+                let names: Vec<&str> = days
+                    .iter()
+                    .filter_map(|d| WEEKDAY_NAMES.get(*d as usize).copied())
+                    .collect();
+                format!("Weekly on {}", names.join(", "))
+            }
+            RecurrenceRule::Monthly { day_of_month } => format!("Monthly on day {}", day_of_month),
+        }
+    }
+
+    // First occurrence strictly after the given YYYY-MM-DD date
+    pub fn next_date_after(&self, date_key: &str) -> Option<String> {
+        match self {
+            RecurrenceRule::Daily => add_days_to_date_key(date_key, 1),
+            RecurrenceRule::Weekdays => (1..=7)
+                .filter_map(|offset| add_days_to_date_key(date_key, offset))
+                .find(|candidate| matches!(weekday_of_date_key(candidate), Some(1..=5))),
+            RecurrenceRule::Weekly { days } => {
+                if days.is_empty() {
+                    return add_days_to_date_key(date_key, 7);
+                }
+                (1..=7)
+                    .filter_map(|offset| add_days_to_date_key(date_key, offset))
+                    .find(|candidate| {
+                        weekday_of_date_key(candidate).is_some_and(|wd| days.contains(&wd))
+                    })
+            }
+            RecurrenceRule::Monthly { day_of_month } => {
+                let (year, month, day) = parse_date_key(date_key)?;
+                // Clamp e.g. "day 31" to the last day of shorter months
+                let this_month = (*day_of_month).clamp(1, days_in_month(year, month));
+                if this_month > day {
+                    return Some(format!("{:04}-{:02}-{:02}", year, month, this_month));
+                }
+                let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+                let day = (*day_of_month).clamp(1, days_in_month(next_year, next_month));
+                Some(format!("{:04}-{:02}-{:02}", next_year, next_month, day))
+            }
+        }
+    }
+
+    // Next occurrence after `date_key` that is not in the past relative to `today`
+    pub fn next_date_from(&self, date_key: &str, today: &str) -> Option<String> {
+        let mut next = self.next_date_after(date_key)?;
+        while next.as_str() < today {
+            next = self.next_date_after(&next)?;
+        }
+        Some(next)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: String,
@@ -239,6 +717,105 @@ pub struct Task {
     pub actual_pomodoros: u32,
     pub total_focus_time: u32, // in seconds
     pub order_index: u32, // for sorting
+    #[serde(default)]
+    pub due_date: Option<String>, // YYYY-MM-DD
+    #[serde(default)]
+    pub priority: TaskPriority,
+    #[serde(default)]
+    pub planned_date: Option<String>, // YYYY-MM-DD of the day the task is planned for
+    #[serde(default)]
+    pub recurrence: Option<RecurrenceRule>,
+    #[serde(default)]
+    pub series_id: Option<String>, // shared by all instances of a recurring task
+    #[serde(default)]
+    pub archived: bool, // hidden from selection but kept for history and stats
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub external: Option<ExternalRef>, // set when the task mirrors an item in another tool
+    #[serde(default)]
+    pub hourly_rate: Option<f64>, // overrides the project's rate on timesheets
+}
+
+// Link from a task to the item it mirrors in an external tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalRef {
+    pub source: String, // e.g. "todotxt"
+    pub id: String,     // identifier of the item within that source
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub etag: Option<String>, // last synced version, used to detect concurrent edits
+}
+
+// A task whose local and external versions both changed since the last sync
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub source: String,
+    pub task_id: String,
+    pub task_name: String,
+    pub local_version: String,
+    pub remote_version: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub color: String,
+    pub created_at: String,
+    pub archived: bool,
+    pub order_index: u32, // for sorting
+    #[serde(default)]
+    pub hourly_rate: Option<f64>, // billed for the project's tasks without their own rate
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewProject {
+    pub name: String,
+    pub description: Option<String>,
+    pub color: String,
+    pub hourly_rate: Option<f64>,
+}
+
+// Project filter shared by the task list, task selector and session history
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ProjectFilter {
+    #[default]
+    All,
+    Unassigned,
+    Project(String),
+}
+
+impl ProjectFilter {
+    pub fn matches(&self, project_id: Option<&str>) -> bool {
+        match self {
+            ProjectFilter::All => true,
+            ProjectFilter::Unassigned => project_id.is_none(),
+            ProjectFilter::Project(id) => project_id == Some(id.as_str()),
+        }
+    }
+
+    // Value used in <select> options
+    pub fn to_value(&self) -> String {
+        match self {
+            ProjectFilter::All => "all".to_string(),
+            ProjectFilter::Unassigned => "none".to_string(),
+            ProjectFilter::Project(id) => id.clone(),
+        }
+    }
+
+    pub fn from_value(value: &str) -> Self {
+        match value {
+            "" | "all" => ProjectFilter::All,
+            "none" => ProjectFilter::Unassigned,
+            id => ProjectFilter::Project(id.to_string()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -253,6 +830,8 @@ pub struct SubTask {
     pub actual_pomodoros: u32,
     pub total_focus_time: u32, // in seconds
     pub order_index: u32, // for sorting
+    #[serde(default)]
+    pub external: Option<ExternalRef>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -271,6 +850,10 @@ pub struct NewTask {
     pub description: Option<String>,
     pub color: String,
     pub estimated_pomodoros: Option<u32>,
+    pub due_date: Option<String>,
+    pub priority: TaskPriority,
+    pub recurrence: Option<RecurrenceRule>,
+    pub project_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -294,6 +877,8 @@ pub async fn save_session_to_db(session: NewSession) -> Result<String, String> {
     let storage = get_local_storage().ok_or("Cannot access localStorage")?;
     let session_id = generate_session_id();
     let now = js_sys::Date::new_0().to_iso_string();
+    // Manual entries sort by when they happened, not when they were logged
+    let created_at = if session.manual { session.end_time.clone() } else { now.into() };
 
     let session_record = Session {
         id: session_id.clone(),
@@ -303,10 +888,12 @@ pub async fn save_session_to_db(session: NewSession) -> Result<String, String> {
         start_time: session.start_time,
         end_time: session.end_time,
         completed: session.completed,
-        created_at: now.into(),
+        created_at,
         video_path: session.video_path,
         task_id: session.task_id,       
         subtask_id: session.subtask_id,
+        manual: session.manual,
+        tags: Vec::new(),
     };
 
     // Get existing sessions
@@ -356,8 +943,11 @@ async fn get_all_sessions() -> Result<Vec<Session>, String> {
     }
 }
 
-pub async fn get_session_stats_from_db() -> Result<SessionStats, String> {
-    let sessions = get_all_sessions().await.unwrap_or_default();
+pub async fn get_session_stats_from_db(include_manual: bool) -> Result<SessionStats, String> {
+    let mut sessions = get_all_sessions().await.unwrap_or_default();
+    if !include_manual {
+        sessions.retain(|s| !s.manual);
+    }
 
     let total_sessions = sessions.len() as u32;
     let completed_sessions = sessions.iter().filter(|s| s.completed).count() as u32;
@@ -408,6 +998,8 @@ pub async fn get_session_stats_from_db() -> Result<SessionStats, String> {
         0.0
     };
 
+    let project_stats = get_project_session_stats(&sessions).await;
+
     Ok(SessionStats {
         total_sessions,
         completed_sessions,
@@ -418,34 +1010,1294 @@ pub async fn get_session_stats_from_db() -> Result<SessionStats, String> {
         long_break_sessions,
         average_session_duration,
         completion_rate,
+        project_stats,
     })
 }
 
-pub async fn delete_session_from_db(session_id: String) -> Result<bool, String> {
-    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
-    let mut sessions = get_all_sessions().await.unwrap_or_default();
-
-    let initial_len = sessions.len();
-    sessions.retain(|s| s.id != session_id);
+// Pomodoro counts and recent weekly focus per project, from completed work sessions
+async fn get_project_session_stats(sessions: &[Session]) -> Vec<ProjectSessionStats> {
+    let tasks = get_all_tasks().await.unwrap_or_default();
+    let projects = get_all_projects().await.unwrap_or_default();
 
-    if sessions.len() < initial_len {
-        let all_sessions_json = serde_json::to_string(&sessions).map_err(|e| e.to_string())?;
-        storage
-            .set_item("pomodoro_sessions", &all_sessions_json)
-            .map_err(|e| format!("{:?}", e))?;
-        Ok(true)
-    } else {
-        Ok(false)
-    }
-}
+    let this_week = week_start_of_date_key(&get_today_date_string()).unwrap_or_default();
+    let weeks: Vec<String> = (0..PROJECT_ROLLUP_WEEKS as i64)
+        .rev()
+        .filter_map(|i| add_days_to_date_key(&this_week, -7 * i))
+        .collect();
+
+    let mut stats: Vec<ProjectSessionStats> = projects
+        .iter()
+        .filter(|p| !p.archived)
+        .map(|p| ProjectSessionStats {
+            project_id: Some(p.id.clone()),
+            name: p.name.clone(),
+            color: p.color.clone(),
+            pomodoros: 0,
+            total_focus_time: 0,
+            weekly_focus: weeks.iter().map(|w| (w.clone(), 0)).collect(),
+        })
+        .collect();
+
+    for session in sessions.iter().filter(|s| s.session_type == "Work" && s.completed) {
+        let project_id = session
+            .task_id
+            .as_ref()
+            .and_then(|task_id| tasks.iter().find(|t| &t.id == task_id))
+            .and_then(|t| t.project_id.clone())
+            .filter(|id| projects.iter().any(|p| &p.id == id));
+
+        let index = match stats.iter().position(|p| p.project_id == project_id) {
+            Some(index) => index,
+            None => {
+                // Archived projects and unassigned work only appear once they have sessions
+                let project = project_id
+                    .as_ref()
+                    .and_then(|id| projects.iter().find(|p| &p.id == id));
+                stats.push(ProjectSessionStats {
+                    project_id: project_id.clone(),
+                    name: project.map_or("No project".to_string(), |p| p.name.clone()),
+                    color: project.map_or("#9CA3AF".to_string(), |p| p.color.clone()),
+                    pomodoros: 0,
+                    total_focus_time: 0,
+                    weekly_focus: weeks.iter().map(|w| (w.clone(), 0)).collect(),
+                });
+                stats.len() - 1
+            }
+        };
+
+        let entry = &mut stats[index];
+        entry.pomodoros += 1;
+        entry.total_focus_time += session.actual_duration;
+
+        if let Some(week) = week_start_of_date_key(&date_key_from_iso(&session.start_time)) {
+            if let Some(slot) = entry.weekly_focus.iter_mut().find(|(w, _)| *w == week) {
+                slot.1 += session.actual_duration;
+            }
+        }
+    }
+
+    stats
+}
+
+// Where a session starts on the local clock
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalStart {
+    pub day: String, // day the session is grouped under, honouring the day-start hour
+    pub hour: u32,
+    pub seconds_into_hour: u32,
+}
+
+pub fn local_start(iso_string: &str) -> Option<LocalStart> {
+    let date = js_sys::Date::new(&iso_string.into());
+    if date.get_time().is_nan() {
+        return None;
+    }
+    Some(LocalStart {
+        day: date_key_from_iso(iso_string),
+        hour: date.get_hours(),
+        seconds_into_hour: date.get_minutes() * 60 + date.get_seconds(),
+    })
+}
+
+// Splits a session's focus time across the local hours it ran in
+fn add_focus_by_hour(by_hour: &mut [u32; 24], start: &LocalStart, focus_seconds: u32) {
+    let mut hour = start.hour as usize % 24;
+    let mut seconds_into_hour = start.seconds_into_hour.min(3599);
+    let mut remaining = focus_seconds;
+    while remaining > 0 {
+        let chunk = remaining.min(3600 - seconds_into_hour);
+        by_hour[hour] += chunk;
+        remaining -= chunk;
+        hour = (hour + 1) % 24;
+        seconds_into_hour = 0;
+    }
+}
+
+// Focus distribution over the `weeks` weeks ending with the week of `today`. Hour, weekday and
+// per-task totals count completed work sessions; completion rates count every session type,
+// including sessions stopped part-way. `local_start` places a start time on the local clock.
+pub fn aggregate_focus_charts(
+    sessions: &[Session],
+    tasks: &[Task],
+    weeks: usize,
+    today: &str,
+    local_start: impl Fn(&str) -> Option<LocalStart>,
+) -> FocusCharts {
+    let this_week = week_start_of_date_key(today).unwrap_or_default();
+    let mut points: Vec<WeeklyChartPoint> = (0..weeks as i64)
+        .rev()
+        .filter_map(|i| add_days_to_date_key(&this_week, -7 * i))
+        .map(|week_start| WeeklyChartPoint {
+            week_start,
+            segments: Vec::new(),
+            completed_sessions: 0,
+            total_sessions: 0,
+        })
+        .collect();
+    let mut by_hour = [0; 24];
+    let mut by_weekday = [0; 7];
+
+    for session in sessions {
+        let Some(start) = local_start(&session.start_time) else {
+            continue;
+        };
+        let Some(point) = week_start_of_date_key(&start.day)
+            .and_then(|week| points.iter_mut().find(|p| p.week_start == week))
+        else {
+            continue;
+        };
+
+        point.total_sessions += 1;
+        if !session.completed {
+            continue;
+        }
+        point.completed_sessions += 1;
+        if session.session_type != "Work" {
+            continue;
+        }
+
+        let task = session
+            .task_id
+            .as_ref()
+            .and_then(|id| tasks.iter().find(|t| &t.id == id));
+        let task_id = task.map(|t| t.id.clone());
+        match point.segments.iter_mut().find(|s| s.task_id == task_id) {
+            Some(segment) => segment.focus_time += session.actual_duration,
+            None => point.segments.push(TaskFocusSegment {
+                task_id,
+                name: task.map_or("No task".to_string(), |t| t.name.clone()),
+                color: task.map_or("#9CA3AF".to_string(), |t| t.color.clone()),
+                focus_time: session.actual_duration,
+            }),
+        }
+
+        if let Some(weekday) = weekday_of_date_key(&start.day) {
+            by_weekday[((weekday + 6) % 7) as usize] += session.actual_duration;
+        }
+        add_focus_by_hour(&mut by_hour, &start, session.actual_duration);
+    }
+
+    for point in &mut points {
+        point.segments.sort_by_key(|s| std::cmp::Reverse(s.focus_time));
+    }
+
+    FocusCharts {
+        by_hour,
+        by_weekday,
+        weeks: points,
+    }
+}
+
+pub async fn get_focus_charts_from_db(weeks: usize, include_manual: bool) -> Result<FocusCharts, String> {
+    let mut sessions = get_all_sessions().await?;
+    if !include_manual {
+        sessions.retain(|s| !s.manual);
+    }
+    let tasks = get_all_tasks().await?;
+    Ok(aggregate_focus_charts(&sessions, &tasks, weeks, &get_today_date_string(), local_start))
+}
+
+// Days in a row with completed work: the run ending at `until` (or the day before, when
+// `until` has none yet) and the longest run up to `until`
+pub fn focus_streaks(sessions: &[Session], until: &str) -> (u32, u32) {
+    let Some(until_days) = date_key_to_days(until) else {
+        return (0, 0);
+    };
+    let mut days: Vec<i64> = sessions
+        .iter()
+        .filter(|s| s.session_type == "Work" && s.completed)
+        .filter_map(|s| date_key_to_days(&date_key_from_iso(&s.start_time)))
+        .filter(|d| *d <= until_days)
+        .collect();
+    days.sort_unstable();
+    days.dedup();
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous = None;
+    for day in &days {
+        run = if previous == Some(day - 1) { run + 1 } else { 1 };
+        longest = longest.max(run);
+        previous = Some(*day);
+    }
+
+    let current = match days.last() {
+        Some(last) if *last >= until_days - 1 => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+pub async fn build_weekly_report(week_start: &str) -> Result<WeeklyReport, String> {
+    let week_start = week_start_of_date_key(week_start).ok_or("Invalid week")?;
+    let week_days: Vec<String> = (0..7)
+        .filter_map(|i| add_days_to_date_key(&week_start, i))
+        .collect();
+    let week_end = week_days.last().cloned().ok_or("Invalid week")?;
+
+    let sessions = get_all_sessions().await?;
+    let tasks = get_all_tasks().await?;
+    let week_sessions: Vec<&Session> = sessions
+        .iter()
+        .filter(|s| week_days.contains(&date_key_from_iso(&s.start_time)))
+        .collect();
+    let work: Vec<&Session> = week_sessions
+        .iter()
+        .copied()
+        .filter(|s| s.session_type == "Work" && s.completed)
+        .collect();
+
+    let mut by_hour = [0; 24];
+    let mut report_tasks: Vec<(String, WeeklyReportTask)> = Vec::new();
+    for session in &work {
+        if let Some(start) = local_start(&session.start_time) {
+            add_focus_by_hour(&mut by_hour, &start, session.actual_duration);
+        }
+        let Some(task) = session
+            .task_id
+            .as_ref()
+            .and_then(|id| tasks.iter().find(|t| &t.id == id))
+        else {
+            continue;
+        };
+        let index = match report_tasks.iter().position(|(id, _)| id == &task.id) {
+            Some(index) => index,
+            None => {
+                let total_pomodoros = sessions
+                    .iter()
+                    .filter(|s| s.session_type == "Work" && s.completed && s.task_id.as_ref() == Some(&task.id))
+                    .count() as u32;
+                report_tasks.push((
+                    task.id.clone(),
+                    WeeklyReportTask {
+                        name: task.name.clone(),
+                        pomodoros: 0,
+                        focus_time: 0,
+                        estimated_pomodoros: task.estimated_pomodoros,
+                        total_pomodoros,
+                    },
+                ));
+                report_tasks.len() - 1
+            }
+        };
+        report_tasks[index].1.pomodoros += 1;
+        report_tasks[index].1.focus_time += session.actual_duration;
+    }
+    let mut report_tasks: Vec<WeeklyReportTask> = report_tasks.into_iter().map(|(_, t)| t).collect();
+    report_tasks.sort_by_key(|t| std::cmp::Reverse(t.focus_time));
+
+    let mut best_hours: Vec<(u32, u32)> = by_hour
+        .iter()
+        .enumerate()
+        .filter(|(_, focus)| **focus > 0)
+        .map(|(hour, focus)| (hour as u32, *focus))
+        .collect();
+    best_hours.sort_by_key(|(_, focus)| std::cmp::Reverse(*focus));
+    best_hours.truncate(3);
+
+    let daily_focus = week_days
+        .iter()
+        .map(|day| {
+            let focus = work
+                .iter()
+                .filter(|s| &date_key_from_iso(&s.start_time) == day)
+                .map(|s| s.actual_duration)
+                .sum();
+            (day.clone(), focus)
+        })
+        .collect();
+
+    let interrupted: Vec<&&Session> = week_sessions.iter().filter(|s| !s.completed).collect();
+    // A week still in progress counts its streak up to today
+    let streak_until = week_end.clone().min(get_today_date_string());
+    let (current_streak, longest_streak) = focus_streaks(&sessions, &streak_until);
+
+    let scope_creep = load_budget_events()
+        .into_iter()
+        .map(|event| WeeklyReportBudgetEvent {
+            date: date_key_from_iso(&event.created_at),
+            name: event.name,
+            threshold: event.threshold,
+            estimated_pomodoros: event.estimated_pomodoros,
+            actual_pomodoros: event.actual_pomodoros,
+        })
+        .filter(|event| week_days.contains(&event.date))
+        .collect();
+
+    Ok(WeeklyReport {
+        week_start,
+        week_end,
+        total_focus_time: work.iter().map(|s| s.actual_duration).sum(),
+        pomodoros: work.len() as u32,
+        completed_sessions: week_sessions.iter().filter(|s| s.completed).count() as u32,
+        interruptions: interrupted.len() as u32,
+        interrupted_time: interrupted.iter().map(|s| s.actual_duration).sum(),
+        tasks: report_tasks,
+        best_hours,
+        daily_focus,
+        current_streak,
+        longest_streak,
+        scope_creep,
+    })
+}
+
+// Each local day's progress in the goal's unit. Sessions count towards the day they started
+// on, so one running past midnight belongs to the earlier day.
+pub fn daily_goal_values(
+    sessions: &[Session],
+    metric: GoalMetric,
+    local_start: impl Fn(&str) -> Option<LocalStart>,
+) -> HashMap<String, u32> {
+    let mut seconds: HashMap<String, (u32, u32)> = HashMap::new();
+    for session in sessions.iter().filter(|s| s.session_type == "Work" && s.completed) {
+        let Some(start) = local_start(&session.start_time) else {
+            continue;
+        };
+        let entry = seconds.entry(start.day).or_default();
+        entry.0 += session.actual_duration;
+        entry.1 += 1;
+    }
+    seconds
+        .into_iter()
+        .map(|(day, (focus, count))| {
+            let value = match metric {
+                GoalMetric::FocusMinutes => focus / 60,
+                GoalMetric::Pomodoros => count,
+            };
+            (day, value)
+        })
+        .collect()
+}
+
+// Current and longest runs of days meeting the goal, up to and including `today`.
+// Rest days (target 0) are passed over without breaking a run, and today only counts once
+// it is met, so an unfinished today doesn't reset the current streak.
+pub fn goal_streaks(values: &HashMap<String, u32>, settings: &DailyGoalSettings, today: &str) -> (u32, u32) {
+    let Some(today_days) = date_key_to_days(today) else {
+        return (0, 0);
+    };
+    let Some(first_days) = values.keys().filter_map(|day| date_key_to_days(day)).min() else {
+        return (0, 0);
+    };
+
+    let mut longest = 0;
+    let mut run = 0;
+    for days in first_days..=today_days {
+        let day = date_key_from_days(days);
+        let target = settings.target_for(&day);
+        if target == 0 {
+            continue;
+        }
+        if values.get(&day).copied().unwrap_or(0) >= target {
+            run += 1;
+            longest = longest.max(run);
+        } else if days < today_days {
+            run = 0;
+        }
+    }
+    (run, longest)
+}
+
+// Bills `seconds` in steps of `increment_minutes`; 0 keeps the exact time
+pub fn round_billable_seconds(seconds: u32, increment_minutes: u32, mode: RoundingMode) -> u32 {
+    if increment_minutes == 0 {
+        return seconds;
+    }
+    let step = increment_minutes * 60;
+    match mode {
+        RoundingMode::Up => seconds.div_ceil(step) * step,
+        RoundingMode::Nearest => (seconds + step / 2) / step * step,
+    }
+}
+
+fn round_to_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+// Groups work sessions between `from` and `to` by task, subtask and day. Entries are named
+// after the task here; the caller swaps in full task paths.
+pub fn aggregate_timesheet(
+    sessions: &[Session],
+    tasks: &[Task],
+    projects: &[Project],
+    from: &str,
+    to: &str,
+    settings: &TimesheetSettings,
+) -> Timesheet {
+    let mut entries: Vec<TimesheetEntry> = Vec::new();
+    for session in sessions.iter().filter(|s| s.session_type == "Work" && s.actual_duration > 0) {
+        let Some(task_id) = &session.task_id else {
+            continue;
+        };
+        let date = date_key_from_iso(&session.start_time);
+        if date.as_str() < from || date.as_str() > to {
+            continue;
+        }
+        match entries
+            .iter_mut()
+            .find(|e| &e.task_id == task_id && e.subtask_id == session.subtask_id && e.date == date)
+        {
+            Some(entry) => {
+                entry.sessions += 1;
+                entry.focus_time += session.actual_duration;
+            }
+            None => {
+                let task = tasks.iter().find(|t| &t.id == task_id);
+                let hourly_rate = task.and_then(|t| {
+                    t.hourly_rate.or_else(|| {
+                        t.project_id
+                            .as_ref()
+                            .and_then(|id| projects.iter().find(|p| &p.id == id))
+                            .and_then(|p| p.hourly_rate)
+                    })
+                });
+                entries.push(TimesheetEntry {
+                    date,
+                    task_id: task_id.clone(),
+                    subtask_id: session.subtask_id.clone(),
+                    name: task.map(|t| t.name.clone()).unwrap_or_else(|| "Unknown task".to_string()),
+                    sessions: 1,
+                    focus_time: session.actual_duration,
+                    billed_time: 0,
+                    hourly_rate,
+                    amount: None,
+                });
+            }
+        }
+    }
+
+    let mut task_totals: Vec<TimesheetTaskTotal> = Vec::new();
+    for entry in entries.iter_mut() {
+        entry.billed_time = round_billable_seconds(entry.focus_time, settings.rounding_minutes, settings.rounding_mode);
+        entry.amount = entry
+            .hourly_rate
+            .map(|rate| round_to_cents(entry.billed_time as f64 / 3600.0 * rate));
+
+        let index = match task_totals.iter().position(|t| t.task_id == entry.task_id) {
+            Some(index) => index,
+            None => {
+                task_totals.push(TimesheetTaskTotal {
+                    task_id: entry.task_id.clone(),
+                    name: entry.name.clone(),
+                    focus_time: 0,
+                    billed_time: 0,
+                    amount: 0.0,
+                });
+                task_totals.len() - 1
+            }
+        };
+        task_totals[index].focus_time += entry.focus_time;
+        task_totals[index].billed_time += entry.billed_time;
+        task_totals[index].amount += entry.amount.unwrap_or(0.0);
+    }
+    task_totals.sort_by_key(|t| t.name.to_lowercase());
+
+    // Follow the task order, then list each task's days chronologically
+    entries.sort_by(|a, b| {
+        let position = |id: &str| task_totals.iter().position(|t| t.task_id == id);
+        position(&a.task_id)
+            .cmp(&position(&b.task_id))
+            .then_with(|| a.date.cmp(&b.date))
+            .then_with(|| a.subtask_id.cmp(&b.subtask_id))
+    });
+
+    Timesheet {
+        from: from.to_string(),
+        to: to.to_string(),
+        currency: settings.currency.clone(),
+        rounding: settings.rounding_mode.describe(settings.rounding_minutes),
+        total_focus_time: task_totals.iter().map(|t| t.focus_time).sum(),
+        total_billed_time: task_totals.iter().map(|t| t.billed_time).sum(),
+        total_amount: round_to_cents(task_totals.iter().map(|t| t.amount).sum()),
+        entries,
+        tasks: task_totals,
+    }
+}
+
+pub async fn build_timesheet(from: &str, to: &str, settings: &TimesheetSettings) -> Result<Timesheet, String> {
+    if from > to {
+        return Err("The start date must not be after the end date".to_string());
+    }
+    let sessions = get_all_sessions().await?;
+    let tasks = get_all_tasks().await?;
+    let projects = get_all_projects().await?;
+    let mut timesheet = aggregate_timesheet(&sessions, &tasks, &projects, from, to, settings);
+
+    let mut paths: HashMap<(String, Option<String>), String> = HashMap::new();
+    for entry in timesheet.entries.iter_mut() {
+        let key = (entry.task_id.clone(), entry.subtask_id.clone());
+        if !paths.contains_key(&key) {
+            if let Some(path) = get_task_path_by_ids(Some(&entry.task_id), entry.subtask_id.as_deref()).await? {
+                paths.insert(key.clone(), path);
+            }
+        }
+        if let Some(path) = paths.get(&key) {
+            entry.name = path.clone();
+        }
+    }
+
+    Ok(timesheet)
+}
+
+pub async fn get_daily_goal_status_from_db(settings: &DailyGoalSettings) -> Result<DailyGoalStatus, String> {
+    let sessions = get_all_sessions().await?;
+    let values = daily_goal_values(&sessions, settings.metric, local_start);
+    let today = get_today_date_string();
+    let (current_streak, longest_streak) = goal_streaks(&values, settings, &today);
+
+    Ok(DailyGoalStatus {
+        metric: settings.metric,
+        progress: values.get(&today).copied().unwrap_or(0),
+        target: settings.target_for(&today),
+        current_streak,
+        longest_streak,
+    })
+}
+
+pub async fn delete_session_from_db(session_id: String) -> Result<bool, String> {
+    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
+    let mut sessions = get_all_sessions().await.unwrap_or_default();
+
+    let Some(removed) = sessions.iter().position(|s| s.id == session_id).map(|i| sessions.remove(i)) else {
+        return Ok(false);
+    };
+
+    // The task or subtask gives back the time the session added
+    let mut tasks = get_all_tasks().await.unwrap_or_default();
+    let mut subtasks = get_all_subtasks().await.unwrap_or_default();
+    apply_session_focus(&removed, &mut tasks, &mut subtasks, true);
+    save_all_tasks(&tasks)?;
+    save_all_subtasks(&subtasks)?;
+
+    let all_sessions_json = serde_json::to_string(&sessions).map_err(|e| e.to_string())?;
+    storage
+        .set_item("pomodoro_sessions", &all_sessions_json)
+        .map_err(|e| format!("{:?}", e))?;
+    Ok(true)
+}
+
+// New task/subtask link, times and focus duration for a recorded session
+#[derive(Debug, Clone)]
+pub struct SessionEdit {
+    pub task_id: Option<String>,
+    pub subtask_id: Option<String>,
+    pub start_time: String,
+    pub end_time: String,
+    pub actual_duration: u32, // in seconds
+}
+
+fn iso_to_ms(iso_string: &str) -> Result<f64, String> {
+    let ms = js_sys::Date::parse(iso_string);
+    if ms.is_nan() {
+        Err(format!("Invalid time: {}", iso_string))
+    } else {
+        Ok(ms)
+    }
+}
+
+fn ms_to_iso(ms: f64) -> String {
+    js_sys::Date::new(&ms.into()).to_iso_string().into()
+}
+
+// Adds a completed work session's focus time to its subtask (or task), the same way
+// complete_work_session_with_task does; `remove` takes it back out again
+fn apply_session_focus(session: &Session, tasks: &mut [Task], subtasks: &mut [SubTask], remove: bool) {
+    if session.session_type != "Work" || !session.completed || session.actual_duration == 0 {
+        return;
+    }
+    let seconds = session.actual_duration;
+    let minutes = seconds.div_ceil(60);
+
+    let (total_focus_time, actual_pomodoros) = if let Some(subtask_id) = &session.subtask_id {
+        match subtasks.iter_mut().find(|st| &st.id == subtask_id) {
+            Some(subtask) => (&mut subtask.total_focus_time, &mut subtask.actual_pomodoros),
+            None => return,
+        }
+    } else if let Some(task_id) = &session.task_id {
+        match tasks.iter_mut().find(|t| &t.id == task_id) {
+            Some(task) => (&mut task.total_focus_time, &mut task.actual_pomodoros),
+            None => return,
+        }
+    } else {
+        return;
+    };
+
+    if remove {
+        *total_focus_time = total_focus_time.saturating_sub(seconds);
+        *actual_pomodoros = actual_pomodoros.saturating_sub(minutes);
+    } else {
+        *total_focus_time += seconds;
+        *actual_pomodoros += minutes;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntegrityRepair {
+    None,
+    Totals, // recompute task and subtask totals from the session log
+    All,    // also drop orphaned subtasks, detach dangling session links and fix duplicate IDs
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntegrityIssue {
+    // (seconds, minutes) stored on the record and recomputed from its sessions
+    TaskTotals { id: String, name: String, stored: (u32, u32), expected: (u32, u32) },
+    SubtaskTotals { id: String, name: String, stored: (u32, u32), expected: (u32, u32) },
+    OrphanedSubtask { id: String, name: String },
+    OrphanedSession { id: String, start_time: String },
+    DuplicateId { collection: &'static str, id: String, count: usize },
+}
+
+impl IntegrityIssue {
+    pub fn is_totals(&self) -> bool {
+        matches!(self, IntegrityIssue::TaskTotals { .. } | IntegrityIssue::SubtaskTotals { .. })
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            IntegrityIssue::TaskTotals { name, stored, expected, .. } => format!(
+                "Task \"{}\" shows {} ({} min) but its sessions add up to {} ({} min)",
+                name,
+                format_duration_hours_minutes(stored.0),
+                stored.1,
+                format_duration_hours_minutes(expected.0),
+                expected.1
+            ),
+            IntegrityIssue::SubtaskTotals { name, stored, expected, .. } => format!(
+                "Subtask \"{}\" shows {} ({} min) but its sessions add up to {} ({} min)",
+                name,
+                format_duration_hours_minutes(stored.0),
+                stored.1,
+                format_duration_hours_minutes(expected.0),
+                expected.1
+            ),
+            IntegrityIssue::OrphanedSubtask { name, .. } => {
+                format!("Subtask \"{}\" belongs to a task that no longer exists", name)
+            }
+            IntegrityIssue::OrphanedSession { start_time, .. } => {
+                format!("Session from {} links to a deleted task or subtask", format_iso_date(start_time))
+            }
+            IntegrityIssue::DuplicateId { collection, id, count } => {
+                format!("{} {} records share the ID {}", count, collection, id)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegrityReport {
+    pub tasks: usize,
+    pub subtasks: usize,
+    pub sessions: usize,
+    pub issues: Vec<IntegrityIssue>, // as found, before any repair
+    pub repair: IntegrityRepair,
+    pub checked_at: String,
+}
+
+impl IntegrityReport {
+    pub fn is_fixed(&self, issue: &IntegrityIssue) -> bool {
+        match self.repair {
+            IntegrityRepair::None => false,
+            IntegrityRepair::Totals => issue.is_totals(),
+            IntegrityRepair::All => true,
+        }
+    }
+
+    pub fn repaired(&self) -> usize {
+        self.issues.iter().filter(|issue| self.is_fixed(issue)).count()
+    }
+
+    pub fn unresolved(&self) -> usize {
+        self.issues.len() - self.repaired()
+    }
+}
+
+fn duplicate_ids<'a>(collection: &'static str, ids: impl Iterator<Item = &'a str>) -> Vec<IntegrityIssue> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let mut order = Vec::new();
+    for id in ids {
+        let count = counts.entry(id).or_insert(0);
+        if *count == 0 {
+            order.push(id);
+        }
+        *count += 1;
+    }
+    order
+        .into_iter()
+        .filter(|id| counts[id] > 1)
+        .map(|id| IntegrityIssue::DuplicateId { collection, id: id.to_string(), count: counts[id] })
+        .collect()
+}
+
+// Task and subtask totals as the session log says they should be
+fn recomputed_totals(tasks: &[Task], subtasks: &[SubTask], sessions: &[Session]) -> (Vec<Task>, Vec<SubTask>) {
+    let mut tasks = tasks.to_vec();
+    let mut subtasks = subtasks.to_vec();
+    for task in tasks.iter_mut() {
+        task.total_focus_time = 0;
+        task.actual_pomodoros = 0;
+    }
+    for subtask in subtasks.iter_mut() {
+        subtask.total_focus_time = 0;
+        subtask.actual_pomodoros = 0;
+    }
+    for session in sessions {
+        apply_session_focus(session, &mut tasks, &mut subtasks, false);
+    }
+    (tasks, subtasks)
+}
+
+pub fn check_integrity(tasks: &[Task], subtasks: &[SubTask], sessions: &[Session]) -> Vec<IntegrityIssue> {
+    let mut issues = Vec::new();
+    issues.extend(duplicate_ids("task", tasks.iter().map(|t| t.id.as_str())));
+    issues.extend(duplicate_ids("subtask", subtasks.iter().map(|st| st.id.as_str())));
+    issues.extend(duplicate_ids("session", sessions.iter().map(|s| s.id.as_str())));
+
+    for subtask in subtasks.iter().filter(|st| !tasks.iter().any(|t| t.id == st.task_id)) {
+        issues.push(IntegrityIssue::OrphanedSubtask { id: subtask.id.clone(), name: subtask.name.clone() });
+    }
+    for session in sessions {
+        let missing_task = session.task_id.as_ref().is_some_and(|id| !tasks.iter().any(|t| &t.id == id));
+        let missing_subtask = session.subtask_id.as_ref().is_some_and(|id| !subtasks.iter().any(|st| &st.id == id));
+        if missing_task || missing_subtask {
+            issues.push(IntegrityIssue::OrphanedSession {
+                id: session.id.clone(),
+                start_time: session.start_time.clone(),
+            });
+        }
+    }
+
+    let (expected_tasks, expected_subtasks) = recomputed_totals(tasks, subtasks, sessions);
+    for (task, expected) in tasks.iter().zip(&expected_tasks) {
+        if (task.total_focus_time, task.actual_pomodoros) != (expected.total_focus_time, expected.actual_pomodoros) {
+            issues.push(IntegrityIssue::TaskTotals {
+                id: task.id.clone(),
+                name: task.name.clone(),
+                stored: (task.total_focus_time, task.actual_pomodoros),
+                expected: (expected.total_focus_time, expected.actual_pomodoros),
+            });
+        }
+    }
+    for (subtask, expected) in subtasks.iter().zip(&expected_subtasks) {
+        if (subtask.total_focus_time, subtask.actual_pomodoros) != (expected.total_focus_time, expected.actual_pomodoros) {
+            issues.push(IntegrityIssue::SubtaskTotals {
+                id: subtask.id.clone(),
+                name: subtask.name.clone(),
+                stored: (subtask.total_focus_time, subtask.actual_pomodoros),
+                expected: (expected.total_focus_time, expected.actual_pomodoros),
+            });
+        }
+    }
+
+    issues
+}
+
+// Exact copies are dropped; records that only share an ID get a suffixed one
+fn dedupe_records<T: Serialize>(records: &mut Vec<T>, id_of: fn(&mut T) -> &mut String) {
+    let mut seen: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
+    records.retain_mut(|record| {
+        let value = serde_json::to_value(&*record).unwrap_or_default();
+        let id = id_of(record);
+        let copies = seen.entry(id.clone()).or_default();
+        if copies.contains(&value) {
+            return false;
+        }
+        if !copies.is_empty() {
+            *id = format!("{}_{}", id, copies.len() + 1);
+        }
+        copies.push(value);
+        true
+    });
+}
+
+pub fn repair_integrity(tasks: &mut Vec<Task>, subtasks: &mut Vec<SubTask>, sessions: &mut Vec<Session>, repair: IntegrityRepair) {
+    if repair == IntegrityRepair::None {
+        return;
+    }
+    if repair == IntegrityRepair::All {
+        dedupe_records(tasks, |t| &mut t.id);
+        dedupe_records(subtasks, |st| &mut st.id);
+        dedupe_records(sessions, |s| &mut s.id);
+
+        subtasks.retain(|st| tasks.iter().any(|t| t.id == st.task_id));
+        // Sessions stay in the history, only their dangling links are cleared
+        for session in sessions.iter_mut() {
+            if session.subtask_id.as_ref().is_some_and(|id| !subtasks.iter().any(|st| &st.id == id)) {
+                session.subtask_id = None;
+            }
+            if session.task_id.as_ref().is_some_and(|id| !tasks.iter().any(|t| &t.id == id)) {
+                session.task_id = None;
+                session.subtask_id = None;
+            }
+        }
+    }
+
+    let (expected_tasks, expected_subtasks) = recomputed_totals(tasks, subtasks, sessions);
+    *tasks = expected_tasks;
+    *subtasks = expected_subtasks;
+}
+
+pub async fn verify_data_integrity(repair: IntegrityRepair) -> Result<IntegrityReport, String> {
+    let mut tasks = get_all_tasks().await?;
+    let mut subtasks = get_all_subtasks().await?;
+    let mut sessions = get_all_sessions().await?;
+    let issues = check_integrity(&tasks, &subtasks, &sessions);
+
+    let report = IntegrityReport {
+        tasks: tasks.len(),
+        subtasks: subtasks.len(),
+        sessions: sessions.len(),
+        issues,
+        repair,
+        checked_at: js_sys::Date::new_0().to_iso_string().into(),
+    };
+
+    if report.repaired() > 0 {
+        repair_integrity(&mut tasks, &mut subtasks, &mut sessions, repair);
+        save_all_tasks(&tasks)?;
+        save_all_subtasks(&subtasks)?;
+        if repair == IntegrityRepair::All {
+            save_all_sessions(&sessions)?;
+        }
+    }
+
+    Ok(report)
+}
+
+// Sessions may not share any time with each other
+fn check_session_overlap(sessions: &[Session], start_ms: f64, end_ms: f64, ignore: &[&str]) -> Result<(), String> {
+    for other in sessions.iter().filter(|s| !ignore.contains(&s.id.as_str())) {
+        let (Ok(other_start), Ok(other_end)) = (iso_to_ms(&other.start_time), iso_to_ms(&other.end_time)) else {
+            continue;
+        };
+        if other_start < end_ms && start_ms < other_end {
+            return Err(format!(
+                "Overlaps the {} session from {}",
+                other.session_type,
+                format_iso_date(&other.start_time)
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Sessions directly before and after the given one, by start time
+pub async fn get_adjacent_sessions(session_id: &str) -> Result<(Option<Session>, Option<Session>), String> {
+    let mut sessions = get_all_sessions().await?;
+    sessions.sort_by(|a, b| {
+        let a_start = iso_to_ms(&a.start_time).unwrap_or(0.0);
+        let b_start = iso_to_ms(&b.start_time).unwrap_or(0.0);
+        a_start.total_cmp(&b_start)
+    });
+
+    let Some(index) = sessions.iter().position(|s| s.id == session_id) else {
+        return Ok((None, None));
+    };
+    let previous = index.checked_sub(1).and_then(|i| sessions.get(i)).cloned();
+    let next = sessions.get(index + 1).cloned();
+    Ok((previous, next))
+}
+
+// Number of week columns in the focus heatmap, enough to cover a full year
+pub const HEATMAP_WEEKS: i64 = 53;
+
+// Completed work time per local day from `from_key` to `to_key` inclusive, oldest first.
+// Days without sessions are included with 0 so the result lines up with a calendar.
+pub async fn get_daily_focus_from_db(from_key: &str, to_key: &str) -> Result<Vec<(String, u32)>, String> {
+    let from_days = date_key_to_days(from_key).ok_or("Invalid start date")?;
+    let to_days = date_key_to_days(to_key).ok_or("Invalid end date")?;
+    let mut totals: HashMap<String, u32> = HashMap::new();
+    for session in get_all_sessions().await? {
+        if session.session_type == "Work" && session.completed {
+            *totals.entry(date_key_from_iso(&session.start_time)).or_default() += session.actual_duration;
+        }
+    }
+
+    Ok((from_days..=to_days)
+        .map(|days| {
+            let key = date_key_from_days(days);
+            let focus = totals.get(&key).copied().unwrap_or(0);
+            (key, focus)
+        })
+        .collect())
+}
+
+// Sessions overlapping the given local day, by start time
+pub async fn get_sessions_for_day(date_key: &str) -> Result<Vec<Session>, String> {
+    let (day_start, day_end) = local_day_bounds_ms(date_key).ok_or("Invalid date")?;
+    let mut sessions: Vec<(f64, Session)> = get_all_sessions()
+        .await?
+        .into_iter()
+        .filter_map(|s| {
+            let start = iso_to_ms(&s.start_time).ok()?;
+            let end = iso_to_ms(&s.end_time).ok()?;
+            (start < day_end && end > day_start).then_some((start, s))
+        })
+        .collect();
+    sessions.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    Ok(sessions.into_iter().map(|(_, s)| s).collect())
+}
+
+pub async fn update_session_in_db(session_id: String, edit: SessionEdit) -> Result<(), String> {
+    let start_ms = iso_to_ms(&edit.start_time)?;
+    let end_ms = iso_to_ms(&edit.end_time)?;
+    if end_ms <= start_ms {
+        return Err("The session must end after it starts".to_string());
+    }
+    let span_seconds = ((end_ms - start_ms) / 1000.0).round() as u32;
+    if edit.actual_duration > span_seconds {
+        return Err(format!(
+            "Duration can't be longer than the time between start and end ({})",
+            format_duration_hours_minutes(span_seconds)
+        ));
+    }
+
+    let mut tasks = get_all_tasks().await?;
+    let mut subtasks = get_all_subtasks().await?;
+    let mut sessions = get_all_sessions().await?;
+
+    // A subtask always implies its parent task
+    let task_id = match &edit.subtask_id {
+        Some(subtask_id) => {
+            let subtask = subtasks
+                .iter()
+                .find(|st| &st.id == subtask_id)
+                .ok_or("Subtask not found")?;
+            if edit.task_id.as_ref().is_some_and(|id| id != &subtask.task_id) {
+                return Err("The subtask belongs to a different task".to_string());
+            }
+            Some(subtask.task_id.clone())
+        }
+        None => edit.task_id.clone(),
+    };
+    if let Some(task_id) = &task_id {
+        if !tasks.iter().any(|t| &t.id == task_id) {
+            return Err("Task not found".to_string());
+        }
+    }
+
+    check_session_overlap(&sessions, start_ms, end_ms, &[session_id.as_str()])?;
+
+    let session = sessions
+        .iter_mut()
+        .find(|s| s.id == session_id)
+        .ok_or("Session not found")?;
+
+    apply_session_focus(session, &mut tasks, &mut subtasks, true);
+    session.task_id = task_id;
+    session.subtask_id = edit.subtask_id;
+    session.start_time = edit.start_time;
+    session.end_time = edit.end_time;
+    session.actual_duration = edit.actual_duration;
+    apply_session_focus(session, &mut tasks, &mut subtasks, false);
+
+    save_all_tasks(&tasks)?;
+    save_all_subtasks(&subtasks)?;
+    save_all_sessions(&sessions)?;
+    Ok(())
+}
+
+// Splits a session in two at `split_time`, sharing its durations in proportion to the
+// time on each side. Returns the id of the second part; any recording stays with the first.
+pub async fn split_session_in_db(session_id: String, split_time: String) -> Result<String, String> {
+    let mut tasks = get_all_tasks().await?;
+    let mut subtasks = get_all_subtasks().await?;
+    let mut sessions = get_all_sessions().await?;
+
+    let index = sessions
+        .iter()
+        .position(|s| s.id == session_id)
+        .ok_or("Session not found")?;
+    let original = sessions[index].clone();
+
+    let start_ms = iso_to_ms(&original.start_time)?;
+    let end_ms = iso_to_ms(&original.end_time)?;
+    let split_ms = iso_to_ms(&split_time)?;
+    if split_ms <= start_ms || split_ms >= end_ms {
+        return Err("Choose a split time between the session's start and end".to_string());
+    }
+
+    let fraction = (split_ms - start_ms) / (end_ms - start_ms);
+    let first_actual = (original.actual_duration as f64 * fraction).round() as u32;
+    let first_planned = (original.planned_duration as f64 * fraction).round() as u32;
+    let split_iso = ms_to_iso(split_ms);
+
+    let mut first = original.clone();
+    first.end_time = split_iso.clone();
+    first.created_at = split_iso.clone();
+    first.actual_duration = first_actual;
+    first.planned_duration = first_planned;
+
+    let mut second = original.clone();
+    second.id = generate_record_id("session");
+    second.start_time = split_iso;
+    second.actual_duration = original.actual_duration - first_actual;
+    second.planned_duration = original.planned_duration - first_planned;
+    second.video_path = None;
+
+    // Per-session rounding to whole minutes can shift the minute count, so re-credit both parts
+    apply_session_focus(&original, &mut tasks, &mut subtasks, true);
+    apply_session_focus(&first, &mut tasks, &mut subtasks, false);
+    apply_session_focus(&second, &mut tasks, &mut subtasks, false);
+
+    let second_id = second.id.clone();
+    sessions[index] = first;
+    sessions.insert(index + 1, second);
+
+    save_all_tasks(&tasks)?;
+    save_all_subtasks(&subtasks)?;
+    save_all_sessions(&sessions)?;
+    Ok(second_id)
+}
+
+// Merges two neighbouring sessions of the same type into the earlier one, which keeps its
+// task link. Durations add up; the merged session spans both, including any gap between them.
+pub async fn merge_sessions_in_db(first_id: String, second_id: String) -> Result<(), String> {
+    let mut tasks = get_all_tasks().await?;
+    let mut subtasks = get_all_subtasks().await?;
+    let mut sessions = get_all_sessions().await?;
+
+    let a = sessions.iter().find(|s| s.id == first_id).cloned().ok_or("Session not found")?;
+    let b = sessions.iter().find(|s| s.id == second_id).cloned().ok_or("Session not found")?;
+    let (earlier, later) = if iso_to_ms(&a.start_time)? <= iso_to_ms(&b.start_time)? {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    if earlier.session_type != later.session_type {
+        return Err("Only sessions of the same type can be merged".to_string());
+    }
+
+    let start_ms = iso_to_ms(&earlier.start_time)?;
+    let end_ms = iso_to_ms(&earlier.end_time)?.max(iso_to_ms(&later.end_time)?);
+    check_session_overlap(&sessions, start_ms, end_ms, &[earlier.id.as_str(), later.id.as_str()])
+        .map_err(|e| format!("The sessions are not adjacent: {}", e))?;
+
+    let mut merged = earlier.clone();
+    merged.end_time = ms_to_iso(end_ms);
+    merged.created_at = later.created_at.clone();
+    merged.actual_duration = earlier.actual_duration + later.actual_duration;
+    merged.planned_duration = earlier.planned_duration + later.planned_duration;
+    merged.completed = earlier.completed || later.completed;
+    merged.video_path = earlier.video_path.clone().or(later.video_path.clone());
+
+    apply_session_focus(&earlier, &mut tasks, &mut subtasks, true);
+    apply_session_focus(&later, &mut tasks, &mut subtasks, true);
+    apply_session_focus(&merged, &mut tasks, &mut subtasks, false);
+
+    sessions.retain(|s| s.id != later.id);
+    if let Some(session) = sessions.iter_mut().find(|s| s.id == merged.id) {
+        *session = merged;
+    }
+
+    save_all_tasks(&tasks)?;
+    save_all_subtasks(&subtasks)?;
+    save_all_sessions(&sessions)?;
+    Ok(())
+}
+
+// A past session the user forgot to time
+#[derive(Debug, Clone)]
+pub struct ManualSessionEntry {
+    pub session_type: SessionType,
+    pub task_id: Option<String>,
+    pub subtask_id: Option<String>,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkSessionAction {
+    Reassign { task_id: Option<String>, subtask_id: Option<String> }, // None detaches
+    AddTag(String),
+    RemoveTag(String),
+    Delete,
+}
+
+impl BulkSessionAction {
+    // Label for the undo history, e.g. "Reassign 12 sessions"
+    pub fn label(&self, count: usize) -> String {
+        let sessions = if count == 1 { "session".to_string() } else { format!("{} sessions", count) };
+        match self {
+            BulkSessionAction::Reassign { .. } => format!("Reassign {}", sessions),
+            BulkSessionAction::AddTag(tag) => format!("Tag {} with \"{}\"", sessions, tag),
+            BulkSessionAction::RemoveTag(tag) => format!("Remove \"{}\" from {}", tag, sessions),
+            BulkSessionAction::Delete => format!("Delete {}", sessions),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BulkSessionOutcome {
+    pub changed: usize,
+    pub skipped_breaks: usize, // break sessions are never linked to tasks, so Reassign leaves them
+}
+
+fn apply_bulk_session_action(
+    sessions: &mut Vec<Session>,
+    tasks: &mut [Task],
+    subtasks: &mut [SubTask],
+    selected: &HashSet<&str>,
+    action: &BulkSessionAction,
+) -> Result<BulkSessionOutcome, String> {
+    let mut changed = 0;
+    let mut skipped_breaks = 0;
+
+    match action {
+        BulkSessionAction::Reassign { task_id, subtask_id } => {
+            // A subtask always brings its own task along
+            let task_id = match subtask_id {
+                Some(id) => Some(
+                    subtasks
+                        .iter()
+                        .find(|st| &st.id == id)
+                        .ok_or("Subtask to reassign sessions to was not found")?
+                        .task_id
+                        .clone(),
+                ),
+                None => task_id.clone(),
+            };
+            if task_id.as_ref().is_some_and(|id| !tasks.iter().any(|t| &t.id == id)) {
+                return Err("Task to reassign sessions to was not found".to_string());
+            }
+
+            for session in sessions.iter_mut().filter(|s| selected.contains(s.id.as_str())) {
+                if session.session_type != "Work" {
+                    skipped_breaks += 1;
+                    continue;
+                }
+                if session.task_id == task_id && &session.subtask_id == subtask_id {
+                    continue;
+                }
+                // Move the tracked time along with the session
+                apply_session_focus(session, tasks, subtasks, true);
+                session.task_id = task_id.clone();
+                session.subtask_id = subtask_id.clone();
+                apply_session_focus(session, tasks, subtasks, false);
+                changed += 1;
+            }
+        }
+        BulkSessionAction::AddTag(tag) => {
+            for session in sessions.iter_mut().filter(|s| selected.contains(s.id.as_str())) {
+                if !session.tags.contains(tag) {
+                    session.tags.push(tag.clone());
+                    changed += 1;
+                }
+            }
+        }
+        BulkSessionAction::RemoveTag(tag) => {
+            for session in sessions.iter_mut().filter(|s| selected.contains(s.id.as_str())) {
+                if session.tags.contains(tag) {
+                    session.tags.retain(|t| t != tag);
+                    changed += 1;
+                }
+            }
+        }
+        BulkSessionAction::Delete => {
+            sessions.retain(|session| {
+                if !selected.contains(session.id.as_str()) {
+                    return true;
+                }
+                apply_session_focus(session, tasks, subtasks, true);
+                changed += 1;
+                false
+            });
+        }
+    }
+
+    Ok(BulkSessionOutcome { changed, skipped_breaks })
+}
+
+// Applies one action to many sessions with a single read and write of each collection
+pub async fn bulk_update_sessions_in_db(
+    session_ids: &[String],
+    action: BulkSessionAction,
+) -> Result<BulkSessionOutcome, String> {
+    let selected: HashSet<&str> = session_ids.iter().map(|id| id.as_str()).collect();
+    let mut sessions = get_all_sessions().await?;
+    let mut tasks = get_all_tasks().await?;
+    let mut subtasks = get_all_subtasks().await?;
+    let outcome = apply_bulk_session_action(&mut sessions, &mut tasks, &mut subtasks, &selected, &action)?;
+
+    if outcome.changed > 0 {
+        save_all_sessions(&sessions)?;
+        if matches!(action, BulkSessionAction::Reassign { .. } | BulkSessionAction::Delete) {
+            save_all_tasks(&tasks)?;
+            save_all_subtasks(&subtasks)?;
+        }
+    }
+
+    Ok(outcome)
+}
+
+// Validates and saves a manual entry through complete_work_session_with_task, so work
+// sessions credit their task or subtask like timed ones. Returns the new session's id.
+pub async fn log_manual_session(entry: ManualSessionEntry) -> Result<String, String> {
+    let start_ms = iso_to_ms(&entry.start_time)?;
+    let end_ms = iso_to_ms(&entry.end_time)?;
+    if end_ms <= start_ms {
+        return Err("The session must end after it starts".to_string());
+    }
+    if end_ms > js_sys::Date::now() {
+        return Err("Only past sessions can be logged".to_string());
+    }
+
+    // Breaks are never linked to tasks
+    let (task_id, subtask_id) = if entry.session_type == SessionType::Work {
+        match &entry.subtask_id {
+            Some(subtask_id) => {
+                let subtasks = get_all_subtasks().await?;
+                let subtask = subtasks
+                    .iter()
+                    .find(|st| &st.id == subtask_id)
+                    .ok_or("Subtask not found")?;
+                if entry.task_id.as_ref().is_some_and(|id| id != &subtask.task_id) {
+                    return Err("The subtask belongs to a different task".to_string());
+                }
+                (Some(subtask.task_id.clone()), Some(subtask_id.clone()))
+            }
+            None => (entry.task_id.clone(), None),
+        }
+    } else {
+        (None, None)
+    };
+
+    let sessions = get_all_sessions().await?;
+    check_session_overlap(&sessions, start_ms, end_ms, &[])?;
+
+    let duration = ((end_ms - start_ms) / 1000.0).round() as u32;
+    let session = NewSession {
+        session_type: entry.session_type.to_string(),
+        planned_duration: duration,
+        actual_duration: duration,
+        start_time: ms_to_iso(start_ms),
+        end_time: ms_to_iso(end_ms),
+        completed: true,
+        video_path: None,
+        task_id,
+        subtask_id,
+        manual: true,
+    };
+    complete_work_session_with_task(session, duration).await
+}
+
+// Task database operations
+// Timestamp plus a random suffix, so records created within the same millisecond stay distinct
+fn generate_record_id(prefix: &str) -> String {
+    format!(
+        "{}_{}_{:06}",
+        prefix,
+        js_sys::Date::now() as u64,
+        (js_sys::Math::random() * 1_000_000.0) as u32
+    )
+}
 
-// Task database operations
 pub fn generate_task_id() -> String {
-    format!("task_{}", js_sys::Date::now() as u64)
+    generate_record_id("task")
 }
 
 pub fn generate_subtask_id() -> String {
-    format!("subtask_{}", js_sys::Date::now() as u64)
+    generate_record_id("subtask")
 }
 
 // Task Colors (predefined set)
@@ -463,6 +2315,10 @@ pub const TASK_COLORS: &[&str] = &[
 ];
 
 impl Task {
+    pub fn external_source(&self) -> Option<&str> {
+        self.external.as_ref().map(|e| e.source.as_str())
+    }
+
     pub fn get_random_color() -> String {
         let index = (js_sys::Math::random() * TASK_COLORS.len() as f64) as usize;
         TASK_COLORS[index].to_string()
@@ -476,14 +2332,42 @@ impl Task {
             (completed_subtasks as f64 / subtasks.len() as f64) * 100.0
         }
     }
+
+    // Dates are YYYY-MM-DD strings, so lexical comparison matches chronological order
+    pub fn is_overdue(&self, today: &str) -> bool {
+        !self.completed && self.due_date.as_deref().is_some_and(|due| due < today)
+    }
+
+    pub fn is_planned_for(&self, day: &str) -> bool {
+        self.planned_date.as_deref() == Some(day)
+    }
+
+    // Pomodoros still expected for this task, based on its own or its subtasks' estimates
+    pub fn remaining_pomodoros(&self, subtasks: &[SubTask], settings: &TimerSettings) -> u32 {
+        let estimated = self.estimated_pomodoros.unwrap_or_else(|| {
+            subtasks.iter().filter_map(|st| st.estimated_pomodoros).sum()
+        });
+
+        let focus_seconds = self.total_focus_time
+            + subtasks.iter().map(|st| st.total_focus_time).sum::<u32>();
+        let session_seconds = (settings.work_duration_minutes * 60).max(1);
+
+        estimated.saturating_sub(focus_seconds / session_seconds)
+    }
 }
 
 // Database operations for tasks
 pub async fn save_task_to_db(task: NewTask) -> Result<String, String> {
-    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
     let task_id = generate_task_id();
     let now = js_sys::Date::new_0().to_iso_string();
 
+    // Recurring tasks need an anchor date to compute the next instance from
+    let due_date = if task.recurrence.is_some() {
+        task.due_date.or_else(|| Some(get_today_date_string()))
+    } else {
+        task.due_date
+    };
+
     let task_record = Task {
         id: task_id.clone(),
         name: task.name,
@@ -495,16 +2379,54 @@ pub async fn save_task_to_db(task: NewTask) -> Result<String, String> {
         actual_pomodoros: 0,
         total_focus_time: 0,
         order_index: get_next_task_order().await,
+        due_date,
+        priority: task.priority,
+        planned_date: None,
+        series_id: task.recurrence.as_ref().map(|_| task_id.clone()),
+        recurrence: task.recurrence,
+        archived: false,
+        project_id: task.project_id,
+        tags: Vec::new(),
+        external: None,
+        hourly_rate: None,
     };
 
     let mut tasks = get_all_tasks().await.unwrap_or_default();
     tasks.push(task_record);
+    save_all_tasks(&tasks)?;
+
+    Ok(task_id)
+}
 
-    let all_tasks_json = serde_json::to_string(&tasks).map_err(|e| e.to_string())?;
+pub fn save_all_tasks(tasks: &[Task]) -> Result<(), String> {
+    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
+    let all_tasks_json = serde_json::to_string(tasks).map_err(|e| e.to_string())?;
     storage.set_item("pomodoro_tasks", &all_tasks_json)
-        .map_err(|e| format!("{:?}", e))?;
+        .map_err(|e| format!("{:?}", e))
+}
 
-    Ok(task_id)
+pub fn save_all_subtasks(subtasks: &[SubTask]) -> Result<(), String> {
+    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
+    let all_subtasks_json = serde_json::to_string(subtasks).map_err(|e| e.to_string())?;
+    storage.set_item("pomodoro_subtasks", &all_subtasks_json)
+        .map_err(|e| format!("{:?}", e))
+}
+
+pub const RECENT_SELECTION_LIMIT: usize = 20;
+
+// Ids of recently selected tasks and subtasks, most recent first
+pub fn load_recent_selections() -> Vec<String> {
+    get_local_storage()
+        .and_then(|storage| storage.get_item("pomodoro_recent_selections").ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_recent_selections(ids: &[String]) -> Result<(), String> {
+    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
+    let ids_json = serde_json::to_string(ids).map_err(|e| e.to_string())?;
+    storage.set_item("pomodoro_recent_selections", &ids_json)
+        .map_err(|e| format!("{:?}", e))
 }
 
 pub async fn save_subtask_to_db(subtask: NewSubTask) -> Result<String, String> {
@@ -523,6 +2445,7 @@ pub async fn save_subtask_to_db(subtask: NewSubTask) -> Result<String, String> {
         actual_pomodoros: 0,
         total_focus_time: 0,
         order_index: get_next_subtask_order(&subtask.task_id).await,
+        external: None,
     };
 
     let mut subtasks = get_all_subtasks().await.unwrap_or_default();
@@ -596,47 +2519,205 @@ pub async fn update_subtask_in_db(updated_subtask: SubTask) -> Result<(), String
     }
 }
 
-pub async fn delete_task_from_db(task_id: String) -> Result<bool, String> {
-    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
-
+// Moves a task to `target_index` in the overall task order and renumbers every task
+pub async fn move_task_in_db(task_id: &str, target_index: usize) -> Result<(), String> {
+    let mut tasks = get_all_tasks().await?;
+    let from = tasks
+        .iter()
+        .position(|t| t.id == task_id)
+        .ok_or("Task not found")?;
+
+    let task = tasks.remove(from);
+    tasks.insert(target_index.min(tasks.len()), task);
+
+    for (index, task) in tasks.iter_mut().enumerate() {
+        task.order_index = index as u32 + 1;
+    }
+
+    save_all_tasks(&tasks)
+}
+
+// Moves a subtask to `target_index` under `target_task_id`, renumbering both the old and new siblings
+pub async fn move_subtask_in_db(
+    subtask_id: &str,
+    target_task_id: &str,
+    target_index: usize,
+) -> Result<(), String> {
+    let tasks = get_all_tasks().await?;
+    if !tasks.iter().any(|t| t.id == target_task_id) {
+        return Err("Target task not found".to_string());
+    }
+
+    let mut subtasks = get_all_subtasks().await?;
+    let from = subtasks
+        .iter()
+        .position(|st| st.id == subtask_id)
+        .ok_or("SubTask not found")?;
+
+    let mut subtask = subtasks.remove(from);
+    let source_task_id = subtask.task_id.clone();
+    subtask.task_id = target_task_id.to_string();
+
+    // Insert relative to the new siblings only, then renumber both parents
+    let insert_at = subtasks
+        .iter()
+        .enumerate()
+        .filter(|(_, st)| st.task_id == target_task_id)
+        .map(|(i, _)| i)
+        .nth(target_index)
+        .unwrap_or(subtasks.len());
+    subtasks.insert(insert_at, subtask);
+
+    for parent_id in [source_task_id.as_str(), target_task_id] {
+        for (index, sibling) in subtasks
+            .iter_mut()
+            .filter(|st| st.task_id == parent_id)
+            .enumerate()
+        {
+            sibling.order_index = index as u32 + 1;
+        }
+    }
+
+    // Sessions logged on the subtask follow it to its new parent
+    if source_task_id != target_task_id {
+        let mut sessions = get_all_sessions().await?;
+        let mut changed = false;
+        for session in sessions
+            .iter_mut()
+            .filter(|s| s.subtask_id.as_deref() == Some(subtask_id))
+        {
+            session.task_id = Some(target_task_id.to_string());
+            changed = true;
+        }
+        if changed {
+            save_all_sessions(&sessions)?;
+        }
+    }
+
+    save_all_subtasks(&subtasks)
+}
+
+// What happens to the sessions recorded against a task when the task is deleted
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionLinkAction {
+    Detach,
+    Reassign { task_id: String },
+    Delete,
+}
+
+fn save_all_sessions(sessions: &[Session]) -> Result<(), String> {
+    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
+    let all_sessions_json = serde_json::to_string(sessions).map_err(|e| e.to_string())?;
+    storage
+        .set_item("pomodoro_sessions", &all_sessions_json)
+        .map_err(|e| format!("{:?}", e))
+}
+
+pub async fn count_sessions_for_task(task_id: &str) -> Result<usize, String> {
+    let subtask_ids: Vec<String> = get_subtasks_for_task(task_id)
+        .await?
+        .into_iter()
+        .map(|st| st.id)
+        .collect();
+    let sessions = get_all_sessions().await?;
+
+    Ok(sessions
+        .iter()
+        .filter(|s| {
+            s.task_id.as_deref() == Some(task_id)
+                || s.subtask_id.as_ref().is_some_and(|id| subtask_ids.contains(id))
+        })
+        .count())
+}
+
+pub async fn delete_task_from_db(
+    task_id: String,
+    session_action: SessionLinkAction,
+) -> Result<bool, String> {
     // Delete task
     let mut tasks = get_all_tasks().await.unwrap_or_default();
     let initial_len = tasks.len();
     tasks.retain(|t| t.id != task_id);
 
+    if tasks.len() == initial_len {
+        return Ok(false);
+    }
+
     // Delete associated subtasks
     let mut subtasks = get_all_subtasks().await.unwrap_or_default();
+    let subtask_ids: Vec<String> = subtasks
+        .iter()
+        .filter(|st| st.task_id == task_id)
+        .map(|st| st.id.clone())
+        .collect();
     subtasks.retain(|st| st.task_id != task_id);
 
-    if tasks.len() < initial_len {
-        let all_tasks_json = serde_json::to_string(&tasks).map_err(|e| e.to_string())?;
-        storage.set_item("pomodoro_tasks", &all_tasks_json)
-            .map_err(|e| format!("{:?}", e))?;
+    // Resolve sessions that pointed at the task or one of its subtasks
+    let mut sessions = get_all_sessions().await.unwrap_or_default();
+    let is_linked = |s: &Session| {
+        s.task_id.as_deref() == Some(task_id.as_str())
+            || s.subtask_id.as_ref().is_some_and(|id| subtask_ids.contains(id))
+    };
 
-        let all_subtasks_json = serde_json::to_string(&subtasks).map_err(|e| e.to_string())?;
-        storage.set_item("pomodoro_subtasks", &all_subtasks_json)
-            .map_err(|e| format!("{:?}", e))?;
+    match &session_action {
+        SessionLinkAction::Detach => {
+            for session in sessions.iter_mut().filter(|s| is_linked(s)) {
+                session.task_id = None;
+                session.subtask_id = None;
+            }
+        }
+        SessionLinkAction::Reassign { task_id: target_id } => {
+            if !tasks.iter().any(|t| &t.id == target_id) {
+                return Err("Task to reassign sessions to was not found".to_string());
+            }
 
-        Ok(true)
-    } else {
-        Ok(false)
+            for session in sessions.iter_mut().filter(|s| is_linked(s)) {
+                session.task_id = Some(target_id.clone());
+                session.subtask_id = None;
+
+                // Keep the target's tracked time in line with its sessions
+                apply_session_focus(session, &mut tasks, &mut subtasks, false);
+            }
+        }
+        SessionLinkAction::Delete => {
+            sessions.retain(|s| !is_linked(s));
+        }
     }
+
+    save_all_tasks(&tasks)?;
+    save_all_subtasks(&subtasks)?;
+    save_all_sessions(&sessions)?;
+
+    Ok(true)
 }
 
 pub async fn delete_subtask_from_db(subtask_id: String) -> Result<bool, String> {
-    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
     let mut subtasks = get_all_subtasks().await.unwrap_or_default();
-    let initial_len = subtasks.len();
-    subtasks.retain(|st| st.id != subtask_id);
+    let Some(removed) = subtasks.iter().position(|st| st.id == subtask_id).map(|i| subtasks.remove(i)) else {
+        return Ok(false);
+    };
 
-    if subtasks.len() < initial_len {
-        let all_subtasks_json = serde_json::to_string(&subtasks).map_err(|e| e.to_string())?;
-        storage.set_item("pomodoro_subtasks", &all_subtasks_json)
-            .map_err(|e| format!("{:?}", e))?;
-        Ok(true)
-    } else {
-        Ok(false)
+    // Sessions fall back to the parent task, which also takes over the tracked time
+    let mut tasks = get_all_tasks().await.unwrap_or_default();
+    if let Some(parent) = tasks.iter_mut().find(|t| t.id == removed.task_id) {
+        parent.total_focus_time += removed.total_focus_time;
+        parent.actual_pomodoros += removed.actual_pomodoros;
+    }
+
+    let mut sessions = get_all_sessions().await.unwrap_or_default();
+    for session in sessions
+        .iter_mut()
+        .filter(|s| s.subtask_id.as_deref() == Some(subtask_id.as_str()))
+    {
+        session.subtask_id = None;
+        session.task_id = Some(removed.task_id.clone());
     }
+
+    save_all_subtasks(&subtasks)?;
+    save_all_tasks(&tasks)?;
+    save_all_sessions(&sessions)?;
+
+    Ok(true)
 }
 
 pub async fn get_task_stats() -> Result<Vec<TaskStats>, String> {
@@ -678,6 +2759,501 @@ pub async fn get_task_stats() -> Result<Vec<TaskStats>, String> {
     Ok(task_stats)
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesStats {
+    pub series_id: String,
+    pub name: String,
+    pub color: String,
+    pub recurrence: RecurrenceRule,
+    pub instances: u32,
+    pub completed_instances: u32,
+    pub total_focus_time: u32,
+    pub total_pomodoros: u32,
+}
+
+// Rolls per-instance stats of recurring tasks up to one entry per series
+pub fn aggregate_series_stats(task_stats: &[TaskStats]) -> Vec<SeriesStats> {
+    let mut series: Vec<SeriesStats> = Vec::new();
+    let mut latest_due: Vec<Option<String>> = Vec::new();
+
+    for stat in task_stats {
+        let (Some(series_id), Some(rule)) = (&stat.task.series_id, &stat.task.recurrence) else {
+            continue;
+        };
+
+        let index = match series.iter().position(|s| &s.series_id == series_id) {
+            Some(index) => index,
+            None => {
+                series.push(SeriesStats {
+                    series_id: series_id.clone(),
+                    name: stat.task.name.clone(),
+                    color: stat.task.color.clone(),
+                    recurrence: rule.clone(),
+                    instances: 0,
+                    completed_instances: 0,
+                    total_focus_time: 0,
+                    total_pomodoros: 0,
+                });
+                latest_due.push(None);
+                series.len() - 1
+            }
+        };
+
+        let entry = &mut series[index];
+        entry.instances += 1;
+        if stat.task.completed {
+            entry.completed_instances += 1;
+        }
+        entry.total_focus_time += stat.total_focus_time;
+        entry.total_pomodoros += stat.total_pomodoros;
+
+        // Name, colour and rule follow the most recent instance
+        if stat.task.due_date >= latest_due[index] {
+            latest_due[index] = stat.task.due_date.clone();
+            entry.name = stat.task.name.clone();
+            entry.color = stat.task.color.clone();
+            entry.recurrence = rule.clone();
+        }
+    }
+
+    series
+}
+
+// Project database operations
+pub fn generate_project_id() -> String {
+    generate_record_id("project")
+}
+
+pub async fn get_all_projects() -> Result<Vec<Project>, String> {
+    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
+
+    match storage.get_item("pomodoro_projects").map_err(|e| format!("{:?}", e))? {
+        Some(json) => {
+            let mut projects: Vec<Project> = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+            projects.sort_by_key(|p| p.order_index);
+            Ok(projects)
+        },
+        None => Ok(Vec::new()),
+    }
+}
+
+fn save_all_projects(projects: &[Project]) -> Result<(), String> {
+    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
+    let all_projects_json = serde_json::to_string(projects).map_err(|e| e.to_string())?;
+    storage.set_item("pomodoro_projects", &all_projects_json)
+        .map_err(|e| format!("{:?}", e))
+}
+
+pub async fn save_project_to_db(project: NewProject) -> Result<String, String> {
+    let project_id = generate_project_id();
+    let now = js_sys::Date::new_0().to_iso_string();
+
+    let mut projects = get_all_projects().await.unwrap_or_default();
+    let order_index = projects.iter().map(|p| p.order_index).max().unwrap_or(0) + 1;
+    projects.push(Project {
+        id: project_id.clone(),
+        name: project.name,
+        description: project.description,
+        color: project.color,
+        created_at: now.into(),
+        archived: false,
+        order_index,
+        hourly_rate: project.hourly_rate,
+    });
+    save_all_projects(&projects)?;
+
+    Ok(project_id)
+}
+
+pub async fn update_project_in_db(updated_project: Project) -> Result<(), String> {
+    let mut projects = get_all_projects().await.unwrap_or_default();
+
+    if let Some(index) = projects.iter().position(|p| p.id == updated_project.id) {
+        projects[index] = updated_project;
+        save_all_projects(&projects)
+    } else {
+        Err("Project not found".to_string())
+    }
+}
+
+// Looks up projects by name (ignoring case) and creates the missing ones.
+// Returns (name, project ID) pairs in the order the names were given.
+pub async fn find_or_create_projects(names: &[String]) -> Result<Vec<(String, String)>, String> {
+    let mut projects = get_all_projects().await?;
+    let mut resolved = Vec::new();
+
+    for name in names {
+        let project_id = match projects.iter().find(|p| p.name.eq_ignore_ascii_case(name)) {
+            Some(project) => project.id.clone(),
+            None => {
+                let id = save_project_to_db(NewProject {
+                    name: name.clone(),
+                    description: None,
+                    color: Task::get_random_color(),
+                    hourly_rate: None,
+                })
+                .await?;
+                projects = get_all_projects().await?;
+                id
+            }
+        };
+        resolved.push((name.clone(), project_id));
+    }
+
+    Ok(resolved)
+}
+
+// Deleting a project keeps its tasks; they simply lose their project
+pub async fn delete_project_from_db(project_id: String) -> Result<bool, String> {
+    let mut projects = get_all_projects().await.unwrap_or_default();
+    let initial_len = projects.len();
+    projects.retain(|p| p.id != project_id);
+    if projects.len() == initial_len {
+        return Ok(false);
+    }
+
+    let mut tasks = get_all_tasks().await.unwrap_or_default();
+    for task in tasks
+        .iter_mut()
+        .filter(|t| t.project_id.as_deref() == Some(project_id.as_str()))
+    {
+        task.project_id = None;
+    }
+
+    save_all_tasks(&tasks)?;
+    save_all_projects(&projects)?;
+
+    Ok(true)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectStats {
+    pub project: Project,
+    pub tasks: u32,
+    pub completed_tasks: u32,
+    pub total_focus_time: u32,
+    pub total_pomodoros: u32,
+}
+
+// Rolls per-task stats up to one entry per project
+pub fn aggregate_project_stats(task_stats: &[TaskStats], projects: &[Project]) -> Vec<ProjectStats> {
+    projects
+        .iter()
+        .map(|project| {
+            let mut stats = ProjectStats {
+                project: project.clone(),
+                tasks: 0,
+                completed_tasks: 0,
+                total_focus_time: 0,
+                total_pomodoros: 0,
+            };
+            for stat in task_stats
+                .iter()
+                .filter(|ts| ts.task.project_id.as_deref() == Some(project.id.as_str()))
+            {
+                stats.tasks += 1;
+                if stat.task.completed {
+                    stats.completed_tasks += 1;
+                }
+                stats.total_focus_time += stat.total_focus_time;
+                stats.total_pomodoros += stat.total_pomodoros;
+            }
+            stats
+        })
+        .filter(|stats| !stats.project.archived || stats.tasks > 0)
+        .collect()
+}
+
+// Days of recent work used to project completion dates
+pub const FORECAST_WINDOW_DAYS: i64 = 14;
+
+// Below this many finished, estimated items the estimation error falls back to defaults
+const MIN_ESTIMATE_SAMPLES: usize = 3;
+
+// Actual-to-estimated session ratios of finished work: 25th percentile, median, 75th percentile
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EstimationAccuracy {
+    pub samples: usize,
+    pub low: f64,
+    pub typical: f64,
+    pub high: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskForecast {
+    pub task: Task,
+    pub spent: u32,          // completed work sessions so far
+    pub remaining: u32,      // sessions still needed at the typical estimation error
+    pub remaining_low: u32,  // optimistic end of the range
+    pub remaining_high: u32, // pessimistic end of the range
+    pub budget: Option<u32>, // the task's estimate, or its subtasks' estimates summed
+    pub over_budget: bool,   // spent plus remaining exceeds the budget
+    pub daily_rate: f64,     // sessions per day on this task over the forecast window
+    pub completion_date: Option<String>, // None without recent work on the task
+}
+
+fn percentile(sorted: &[f64], fraction: f64) -> f64 {
+    sorted[((sorted.len() - 1) as f64 * fraction).round() as usize]
+}
+
+fn completed_work_sessions(sessions: &[Session]) -> impl Iterator<Item = &Session> {
+    sessions.iter().filter(|s| s.session_type == "Work" && s.completed)
+}
+
+// How far actual sessions ran over or under the estimate on finished tasks and subtasks
+pub fn estimation_accuracy(tasks: &[Task], subtasks: &[SubTask], sessions: &[Session]) -> EstimationAccuracy {
+    let mut ratios: Vec<f64> = Vec::new();
+    for subtask in subtasks.iter().filter(|st| st.completed) {
+        let spent = completed_work_sessions(sessions)
+            .filter(|s| s.subtask_id.as_ref() == Some(&subtask.id))
+            .count();
+        if let Some(estimate) = subtask.estimated_pomodoros.filter(|e| *e > 0) {
+            if spent > 0 {
+                ratios.push(spent as f64 / estimate as f64);
+            }
+        }
+    }
+    // Tasks with subtasks are already covered through them
+    for task in tasks
+        .iter()
+        .filter(|t| t.completed && !subtasks.iter().any(|st| st.task_id == t.id))
+    {
+        let spent = completed_work_sessions(sessions)
+            .filter(|s| s.task_id.as_ref() == Some(&task.id))
+            .count();
+        if let Some(estimate) = task.estimated_pomodoros.filter(|e| *e > 0) {
+            if spent > 0 {
+                ratios.push(spent as f64 / estimate as f64);
+            }
+        }
+    }
+
+    if ratios.len() < MIN_ESTIMATE_SAMPLES {
+        return EstimationAccuracy {
+            samples: ratios.len(),
+            low: 0.8,
+            typical: 1.0,
+            high: 1.5,
+        };
+    }
+    ratios.sort_by(|a, b| a.total_cmp(b));
+    EstimationAccuracy {
+        samples: ratios.len(),
+        low: percentile(&ratios, 0.25),
+        typical: percentile(&ratios, 0.5),
+        high: percentile(&ratios, 0.75),
+    }
+}
+
+// Remaining-effort forecasts for open tasks that have estimates, over-budget tasks first.
+// Open subtasks without an estimate borrow the median estimate of their siblings.
+pub fn forecast_tasks(
+    tasks: &[Task],
+    subtasks: &[SubTask],
+    sessions: &[Session],
+    accuracy: EstimationAccuracy,
+    today: &str,
+) -> Vec<TaskForecast> {
+    let window_start = add_days_to_date_key(today, -(FORECAST_WINDOW_DAYS - 1)).unwrap_or_default();
+
+    let mut forecasts: Vec<TaskForecast> = tasks
+        .iter()
+        .filter(|t| !t.completed && !t.archived)
+        .filter_map(|task| {
+            let task_sessions: Vec<&Session> = completed_work_sessions(sessions)
+                .filter(|s| s.task_id.as_ref() == Some(&task.id))
+                .collect();
+            let spent = task_sessions.len() as u32;
+            let task_subtasks: Vec<&SubTask> = subtasks.iter().filter(|st| st.task_id == task.id).collect();
+
+            // (estimate, sessions already spent) for each piece of work still open
+            let open_work: Vec<(f64, f64)> = if task_subtasks.is_empty() {
+                vec![(task.estimated_pomodoros? as f64, spent as f64)]
+            } else {
+                let mut estimates: Vec<f64> = task_subtasks
+                    .iter()
+                    .filter_map(|st| st.estimated_pomodoros.map(|e| e as f64))
+                    .collect();
+                estimates.sort_by(|a, b| a.total_cmp(b));
+                let fallback = (!estimates.is_empty()).then(|| percentile(&estimates, 0.5));
+                task_subtasks
+                    .iter()
+                    .filter(|st| !st.completed)
+                    .map(|st| {
+                        let estimate = st.estimated_pomodoros.map(|e| e as f64).or(fallback)?;
+                        let spent_on_subtask = task_sessions
+                            .iter()
+                            .filter(|s| s.subtask_id.as_ref() == Some(&st.id))
+                            .count();
+                        Some((estimate, spent_on_subtask as f64))
+                    })
+                    .collect::<Option<Vec<_>>>()?
+            };
+
+            let remaining_at = |ratio: f64| {
+                open_work
+                    .iter()
+                    .map(|(estimate, spent)| (estimate * ratio - spent).max(0.0))
+                    .sum::<f64>()
+                    .ceil() as u32
+            };
+            let remaining = remaining_at(accuracy.typical);
+
+            let budget = task.estimated_pomodoros.or_else(|| {
+                let sum: u32 = task_subtasks.iter().filter_map(|st| st.estimated_pomodoros).sum();
+                (sum > 0).then_some(sum)
+            });
+
+            let recent = task_sessions
+                .iter()
+                .filter(|s| date_key_from_iso(&s.start_time) >= window_start)
+                .count();
+            let daily_rate = recent as f64 / FORECAST_WINDOW_DAYS as f64;
+            let completion_date = if remaining == 0 {
+                Some(today.to_string())
+            } else if daily_rate > 0.0 {
+                add_days_to_date_key(today, (remaining as f64 / daily_rate).ceil() as i64)
+            } else {
+                None
+            };
+
+            Some(TaskForecast {
+                task: task.clone(),
+                spent,
+                remaining,
+                remaining_low: remaining_at(accuracy.low).min(remaining),
+                remaining_high: remaining_at(accuracy.high).max(remaining),
+                budget,
+                over_budget: budget.is_some_and(|b| spent + remaining > b),
+                daily_rate,
+                completion_date,
+            })
+        })
+        .collect();
+
+    forecasts.sort_by_key(|f| (std::cmp::Reverse(f.over_budget), std::cmp::Reverse(f.remaining)));
+    forecasts
+}
+
+pub async fn get_task_forecasts_from_db() -> Result<(EstimationAccuracy, Vec<TaskForecast>), String> {
+    let tasks = get_all_tasks().await?;
+    let subtasks = get_all_subtasks().await?;
+    let sessions = get_all_sessions().await?;
+    let accuracy = estimation_accuracy(&tasks, &subtasks, &sessions);
+    let forecasts = forecast_tasks(&tasks, &subtasks, &sessions, accuracy, &get_today_date_string());
+    Ok((accuracy, forecasts))
+}
+
+// Creates the instance that follows `task` in its series, unless a later one already exists
+pub async fn create_next_recurrence(task: &Task) -> Result<Option<String>, String> {
+    let (Some(rule), Some(series_id)) = (&task.recurrence, &task.series_id) else {
+        return Ok(None);
+    };
+
+    let today = get_today_date_string();
+    let anchor = task
+        .due_date
+        .clone()
+        .unwrap_or_else(|| task.created_at.chars().take(10).collect());
+
+    let mut tasks = get_all_tasks().await?;
+    let has_later_instance = tasks.iter().any(|t| {
+        t.id != task.id
+            && t.series_id.as_deref() == Some(series_id.as_str())
+            && t.due_date.as_deref().is_some_and(|due| due > anchor.as_str())
+    });
+    if has_later_instance {
+        return Ok(None);
+    }
+
+    let next_due = rule
+        .next_date_from(&anchor, &today)
+        .ok_or_else(|| format!("Invalid recurrence anchor date: {}", anchor))?;
+
+    let new_task_id = generate_task_id();
+    let now: String = js_sys::Date::new_0().to_iso_string().into();
+
+    tasks.push(Task {
+        id: new_task_id.clone(),
+        name: task.name.clone(),
+        description: task.description.clone(),
+        color: task.color.clone(),
+        created_at: now.clone(),
+        completed: false,
+        estimated_pomodoros: task.estimated_pomodoros,
+        actual_pomodoros: 0,
+        total_focus_time: 0,
+        order_index: tasks.iter().map(|t| t.order_index).max().unwrap_or(0) + 1,
+        due_date: Some(next_due.clone()),
+        priority: task.priority,
+        planned_date: None,
+        recurrence: Some(rule.clone()),
+        series_id: Some(series_id.clone()),
+        archived: false,
+        project_id: task.project_id.clone(),
+        tags: task.tags.clone(),
+        external: None,
+        hourly_rate: task.hourly_rate,
+    });
+
+    // Each instance starts with a fresh copy of the previous instance's checklist
+    let mut subtasks = get_all_subtasks().await?;
+    let template: Vec<SubTask> = subtasks
+        .iter()
+        .filter(|st| st.task_id == task.id)
+        .cloned()
+        .collect();
+    for subtask in template {
+        subtasks.push(SubTask {
+            id: generate_subtask_id(),
+            task_id: new_task_id.clone(),
+            name: subtask.name,
+            description: subtask.description,
+            completed: false,
+            created_at: now.clone(),
+            estimated_pomodoros: subtask.estimated_pomodoros,
+            actual_pomodoros: 0,
+            total_focus_time: 0,
+            order_index: subtask.order_index,
+            external: None,
+        });
+    }
+
+    save_all_tasks(&tasks)?;
+    save_all_subtasks(&subtasks)?;
+
+    console_log!("Created next instance {} of series {} due {}", new_task_id, series_id, next_due);
+    Ok(Some(new_task_id))
+}
+
+// Generates the current instance for every series whose latest instance's period has passed
+pub async fn roll_over_recurring_tasks() -> Result<u32, String> {
+    let today = get_today_date_string();
+    let tasks = get_all_tasks().await?;
+
+    let mut latest: Vec<Task> = Vec::new();
+    for task in tasks.into_iter().filter(|t| t.recurrence.is_some() && t.series_id.is_some()) {
+        match latest.iter_mut().find(|t| t.series_id == task.series_id) {
+            Some(current) if task.due_date > current.due_date => *current = task,
+            Some(_) => {}
+            None => latest.push(task),
+        }
+    }
+
+    let mut created = 0;
+    for task in latest {
+        // Archiving the latest instance stops the series
+        if !task.archived
+            && task.due_date.as_deref().is_some_and(|due| due < today.as_str())
+            && create_next_recurrence(&task).await?.is_some()
+        {
+            created += 1;
+        }
+    }
+
+    Ok(created)
+}
+
 async fn get_next_task_order() -> u32 {
     let tasks = get_all_tasks().await.unwrap_or_default();
     tasks.iter().map(|t| t.order_index).max().unwrap_or(0) + 1
@@ -730,6 +3306,107 @@ pub async fn complete_work_session_with_task(
     Ok(session_id)
 }
 
+pub fn load_budget_events() -> Vec<BudgetEvent> {
+    get_local_storage()
+        .and_then(|storage| storage.get_item("pomodoro_budget_events").ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_budget_events(events: &[BudgetEvent]) -> Result<(), String> {
+    let storage = get_local_storage().ok_or("Cannot access localStorage")?;
+    let events_json = serde_json::to_string(events).map_err(|e| e.to_string())?;
+    storage.set_item("pomodoro_budget_events", &events_json)
+        .map_err(|e| format!("{:?}", e))
+}
+
+// Thresholds reached by `actual` sessions against `estimate` that haven't fired before
+pub fn crossed_budget_thresholds(thresholds: &[u32], estimate: u32, actual: u32, fired: &[u32]) -> Vec<u32> {
+    if estimate == 0 {
+        return Vec::new();
+    }
+    let mut crossed: Vec<u32> = thresholds
+        .iter()
+        .copied()
+        .filter(|t| actual * 100 >= t * estimate && !fired.contains(t))
+        .collect();
+    crossed.sort();
+    crossed.dedup();
+    crossed
+}
+
+// Records the thresholds the task (and subtask) of a just-saved work session crossed
+pub async fn check_budget_after_session(
+    session_id: &str,
+    settings: &BudgetAlertSettings,
+) -> Result<Vec<BudgetEvent>, String> {
+    if !settings.enabled {
+        return Ok(Vec::new());
+    }
+    let sessions = get_all_sessions().await?;
+    let Some(session) = sessions.iter().find(|s| s.id == session_id) else {
+        return Ok(Vec::new());
+    };
+    let Some(task) = get_all_tasks()
+        .await?
+        .into_iter()
+        .find(|t| session.task_id.as_ref() == Some(&t.id))
+    else {
+        return Ok(Vec::new());
+    };
+
+    // (subtask id, name, estimate, completed sessions) for everything with an estimate
+    let mut budgets: Vec<(Option<String>, String, u32, u32)> = Vec::new();
+    if let Some(estimate) = task.estimated_pomodoros {
+        let actual = completed_work_sessions(&sessions)
+            .filter(|s| s.task_id.as_ref() == Some(&task.id))
+            .count() as u32;
+        budgets.push((None, task.name.clone(), estimate, actual));
+    }
+    if let Some(subtask_id) = &session.subtask_id {
+        let subtask = get_subtasks_for_task(&task.id)
+            .await?
+            .into_iter()
+            .find(|st| &st.id == subtask_id);
+        if let Some((subtask, estimate)) = subtask.and_then(|st| st.estimated_pomodoros.map(|e| (st, e))) {
+            let actual = completed_work_sessions(&sessions)
+                .filter(|s| s.subtask_id.as_ref() == Some(&subtask.id))
+                .count() as u32;
+            budgets.push((Some(subtask.id), format!("{} → {}", task.name, subtask.name), estimate, actual));
+        }
+    }
+
+    let mut events = load_budget_events();
+    let now: String = js_sys::Date::new_0().to_iso_string().into();
+    let mut new_events = Vec::new();
+    for (subtask_id, name, estimate, actual) in budgets {
+        let fired: Vec<u32> = events
+            .iter()
+            .filter(|e| e.task_id == task.id && e.subtask_id == subtask_id)
+            .map(|e| e.threshold)
+            .collect();
+        for threshold in crossed_budget_thresholds(&settings.thresholds, estimate, actual, &fired) {
+            new_events.push(BudgetEvent {
+                id: generate_record_id("budget"),
+                task_id: task.id.clone(),
+                subtask_id: subtask_id.clone(),
+                name: name.clone(),
+                threshold,
+                estimated_pomodoros: estimate,
+                actual_pomodoros: actual,
+                session_id: session_id.to_string(),
+                created_at: now.clone(),
+            });
+        }
+    }
+
+    if !new_events.is_empty() {
+        events.extend(new_events.iter().cloned());
+        save_budget_events(&events)?;
+    }
+    Ok(new_events)
+}
+
 // Helper function to get task name by ID
 pub async fn get_task_name_by_id(task_id: &str) -> Result<Option<String>, String> {
     let tasks = get_all_tasks().await?;
@@ -761,4 +3438,288 @@ pub async fn get_task_path_by_ids(task_id: Option<&str>, subtask_id: Option<&str
         }
     }
     Ok(None)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{subtask, task, utc_start, work_session};
+
+    fn on_task(mut session: Session, task_id: &str) -> Session {
+        session.task_id = Some(task_id.to_string());
+        session
+    }
+
+    fn stopped(mut session: Session) -> Session {
+        session.completed = false;
+        session
+    }
+
+    fn on_subtask(mut session: Session, task_id: &str, subtask_id: &str) -> Session {
+        session.task_id = Some(task_id.to_string());
+        session.subtask_id = Some(subtask_id.to_string());
+        session
+    }
+
+    // Task "a" with subtask "s", one 25-minute session on each, and stored totals to match
+    fn consistent_data() -> (Vec<Task>, Vec<SubTask>, Vec<Session>) {
+        let mut a = task("a", "Write");
+        a.total_focus_time = 1500;
+        a.actual_pomodoros = 25;
+        let mut s = subtask("s", "a", "Outline");
+        s.total_focus_time = 1500;
+        s.actual_pomodoros = 25;
+        let sessions = vec![
+            on_task(work_session("1", "2024-01-15T09:00:00.000Z", "2024-01-15T09:25:00.000Z"), "a"),
+            on_subtask(work_session("2", "2024-01-15T10:00:00.000Z", "2024-01-15T10:25:00.000Z"), "a", "s"),
+            stopped(on_task(work_session("3", "2024-01-15T11:00:00.000Z", "2024-01-15T11:10:00.000Z"), "a")),
+        ];
+        (vec![a], vec![s], sessions)
+    }
+
+    #[test]
+    fn integrity_check_passes_consistent_data() {
+        let (tasks, subtasks, sessions) = consistent_data();
+        assert_eq!(check_integrity(&tasks, &subtasks, &sessions), Vec::new());
+    }
+
+    #[test]
+    fn integrity_check_finds_each_kind_of_issue() {
+        let (mut tasks, mut subtasks, mut sessions) = consistent_data();
+        tasks[0].total_focus_time = 60;
+        subtasks.push(subtask("orphan", "gone", "Left behind"));
+        sessions.push(on_task(work_session("4", "2024-01-16T09:00:00.000Z", "2024-01-16T09:25:00.000Z"), "gone"));
+        sessions.push(work_session("1", "2024-01-17T09:00:00.000Z", "2024-01-17T09:25:00.000Z"));
+
+        let issues = check_integrity(&tasks, &subtasks, &sessions);
+
+        assert_eq!(
+            issues,
+            vec![
+                IntegrityIssue::DuplicateId { collection: "session", id: "1".to_string(), count: 2 },
+                IntegrityIssue::OrphanedSubtask { id: "orphan".to_string(), name: "Left behind".to_string() },
+                IntegrityIssue::OrphanedSession { id: "4".to_string(), start_time: "2024-01-16T09:00:00.000Z".to_string() },
+                IntegrityIssue::TaskTotals {
+                    id: "a".to_string(),
+                    name: "Write".to_string(),
+                    stored: (60, 25),
+                    expected: (1500, 25),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn totals_repair_only_recomputes_totals() {
+        let (mut tasks, mut subtasks, mut sessions) = consistent_data();
+        tasks[0].total_focus_time = 60;
+        subtasks[0].actual_pomodoros = 0;
+        subtasks.push(subtask("orphan", "gone", "Left behind"));
+
+        repair_integrity(&mut tasks, &mut subtasks, &mut sessions, IntegrityRepair::Totals);
+
+        assert_eq!((tasks[0].total_focus_time, tasks[0].actual_pomodoros), (1500, 25));
+        assert_eq!((subtasks[0].total_focus_time, subtasks[0].actual_pomodoros), (1500, 25));
+        assert_eq!(subtasks.len(), 2);
+        assert_eq!(sessions.len(), 3);
+    }
+
+    #[test]
+    fn full_repair_leaves_no_issues() {
+        let (mut tasks, mut subtasks, mut sessions) = consistent_data();
+        subtasks.push(subtask("orphan", "gone", "Left behind"));
+        sessions.push(on_subtask(work_session("4", "2024-01-16T09:00:00.000Z", "2024-01-16T09:25:00.000Z"), "gone", "orphan"));
+        sessions.push(sessions[0].clone());
+        let mut same_id = work_session("1", "2024-01-17T09:00:00.000Z", "2024-01-17T09:25:00.000Z");
+        same_id.task_id = Some("a".to_string());
+        sessions.push(same_id);
+
+        repair_integrity(&mut tasks, &mut subtasks, &mut sessions, IntegrityRepair::All);
+
+        assert_eq!(check_integrity(&tasks, &subtasks, &sessions), Vec::new());
+        let ids: Vec<&str> = sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "3", "4", "1_2"]);
+        // The orphaned subtask is gone and its session kept without links
+        assert_eq!(subtasks.len(), 1);
+        assert_eq!((sessions[3].task_id.as_deref(), sessions[3].subtask_id.as_deref()), (None, None));
+        // The renamed duplicate still counts towards its task
+        assert_eq!((tasks[0].total_focus_time, tasks[0].actual_pomodoros), (3000, 50));
+    }
+
+    #[test]
+    fn bulk_reassign_moves_focus_and_counts_skipped_breaks() {
+        let (mut tasks, mut subtasks, mut sessions) = consistent_data();
+        tasks.push(task("b", "Read"));
+        let mut short_break = work_session("5", "2024-01-15T09:25:00.000Z", "2024-01-15T09:30:00.000Z");
+        short_break.session_type = "ShortBreak".to_string();
+        sessions.push(short_break);
+        let selected = HashSet::from(["1", "5"]);
+        let action = BulkSessionAction::Reassign { task_id: Some("b".to_string()), subtask_id: None };
+
+        let outcome = apply_bulk_session_action(&mut sessions, &mut tasks, &mut subtasks, &selected, &action).unwrap();
+
+        assert_eq!(outcome, BulkSessionOutcome { changed: 1, skipped_breaks: 1 });
+        assert_eq!(sessions[0].task_id.as_deref(), Some("b"));
+        assert_eq!(sessions[3].task_id, None);
+        assert_eq!((tasks[0].total_focus_time, tasks[1].total_focus_time), (0, 1500));
+    }
+
+    fn goal(weekday_target: u32, skipped_days: &[&str]) -> DailyGoalSettings {
+        DailyGoalSettings {
+            enabled: true,
+            metric: GoalMetric::FocusMinutes,
+            weekday_target,
+            weekend_target: 0,
+            skipped_days: skipped_days.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    // One 50-minute work session at 09:00 on each day
+    fn focus_on(days: &[&str]) -> HashMap<String, u32> {
+        let sessions: Vec<Session> = days
+            .iter()
+            .enumerate()
+            .map(|(i, day)| {
+                work_session(&i.to_string(), &format!("{}T09:00:00.000Z", day), &format!("{}T09:50:00.000Z", day))
+            })
+            .collect();
+        daily_goal_values(&sessions, GoalMetric::FocusMinutes, utc_start)
+    }
+
+    #[test]
+    fn goal_values_count_a_session_crossing_midnight_on_its_start_day() {
+        let sessions = vec![
+            work_session("1", "2024-01-15T23:50:00.000Z", "2024-01-16T00:40:00.000Z"),
+            stopped(work_session("2", "2024-01-16T09:00:00.000Z", "2024-01-16T09:10:00.000Z")),
+        ];
+
+        let minutes = daily_goal_values(&sessions, GoalMetric::FocusMinutes, utc_start);
+        assert_eq!(minutes, HashMap::from([("2024-01-15".to_string(), 50)]));
+
+        let pomodoros = daily_goal_values(&sessions, GoalMetric::Pomodoros, utc_start);
+        assert_eq!(pomodoros, HashMap::from([("2024-01-15".to_string(), 1)]));
+
+        // The late session doesn't carry Tuesday, so Monday alone is the run
+        assert_eq!(goal_streaks(&minutes, &goal(50, &[]), "2024-01-16"), (1, 1));
+    }
+
+    #[test]
+    fn goal_streaks_pass_over_rest_and_skipped_days() {
+        // Fri, then Mon and Wed around the weekend and a skipped Tuesday
+        let values = focus_on(&["2024-01-19", "2024-01-22", "2024-01-24"]);
+
+        assert_eq!(goal_streaks(&values, &goal(50, &["2024-01-23"]), "2024-01-24"), (3, 3));
+        // Without the skipped day Tuesday is a miss
+        assert_eq!(goal_streaks(&values, &goal(50, &[]), "2024-01-24"), (1, 2));
+    }
+
+    #[test]
+    fn goal_streaks_keep_the_run_while_today_is_unmet() {
+        let mut values = focus_on(&["2024-01-15", "2024-01-16"]);
+        values.insert("2024-01-17".to_string(), 20);
+
+        assert_eq!(goal_streaks(&values, &goal(50, &[]), "2024-01-17"), (2, 2));
+        // Once the day is over, the miss ends the run
+        assert_eq!(goal_streaks(&values, &goal(50, &[]), "2024-01-18"), (0, 2));
+    }
+
+    #[test]
+    fn goal_streaks_start_at_the_first_day_with_data() {
+        assert_eq!(goal_streaks(&HashMap::new(), &goal(50, &[]), "2024-01-17"), (0, 0));
+
+        // Days before the first session are not misses
+        let values = focus_on(&["2024-01-16"]);
+        assert_eq!(goal_streaks(&values, &goal(50, &[]), "2024-01-17"), (1, 1));
+
+        let started_today = HashMap::from([("2024-01-17".to_string(), 20)]);
+        assert_eq!(goal_streaks(&started_today, &goal(50, &[]), "2024-01-17"), (0, 0));
+    }
+
+    #[test]
+    fn monthly_recurrence_uses_the_day_still_due_this_month() {
+        let rule = RecurrenceRule::Monthly { day_of_month: 20 };
+        assert_eq!(rule.next_date_after("2024-01-05").as_deref(), Some("2024-01-20"));
+        assert_eq!(rule.next_date_after("2024-01-20").as_deref(), Some("2024-02-20"));
+        assert_eq!(rule.next_date_after("2024-01-25").as_deref(), Some("2024-02-20"));
+        assert_eq!(rule.next_date_after("2024-12-25").as_deref(), Some("2025-01-20"));
+    }
+
+    #[test]
+    fn monthly_recurrence_clamps_to_short_months() {
+        let rule = RecurrenceRule::Monthly { day_of_month: 31 };
+        assert_eq!(rule.next_date_after("2024-02-10").as_deref(), Some("2024-02-29"));
+        assert_eq!(rule.next_date_after("2024-02-29").as_deref(), Some("2024-03-31"));
+        assert_eq!(rule.next_date_after("2024-03-31").as_deref(), Some("2024-04-30"));
+        assert_eq!(rule.next_date_from("2024-01-05", "2024-03-01").as_deref(), Some("2024-03-31"));
+    }
+
+    #[test]
+    fn focus_charts_bucket_weeks_by_monday_and_tasks() {
+        let tasks = vec![task("a", "Write"), task("b", "Read")];
+        let sessions = vec![
+            // Sunday of the week before
+            on_task(work_session("1", "2024-01-14T09:00:00.000Z", "2024-01-14T09:25:00.000Z"), "a"),
+            // Monday and Wednesday of this week
+            on_task(work_session("2", "2024-01-15T09:00:00.000Z", "2024-01-15T09:25:00.000Z"), "a"),
+            on_task(work_session("3", "2024-01-17T10:00:00.000Z", "2024-01-17T10:50:00.000Z"), "b"),
+            work_session("4", "2024-01-17T11:00:00.000Z", "2024-01-17T11:25:00.000Z"),
+            // Older than the charted weeks
+            on_task(work_session("5", "2024-01-01T09:00:00.000Z", "2024-01-01T09:25:00.000Z"), "a"),
+        ];
+
+        let charts = aggregate_focus_charts(&sessions, &tasks, 2, "2024-01-18", utc_start);
+
+        let weeks: Vec<&str> = charts.weeks.iter().map(|w| w.week_start.as_str()).collect();
+        assert_eq!(weeks, vec!["2024-01-08", "2024-01-15"]);
+        assert_eq!(charts.weeks[0].focus_time(), 25 * 60);
+
+        let segments: Vec<(Option<&str>, u32)> = charts.weeks[1]
+            .segments
+            .iter()
+            .map(|s| (s.task_id.as_deref(), s.focus_time))
+            .collect();
+        assert_eq!(segments, vec![(Some("b"), 50 * 60), (Some("a"), 25 * 60), (None, 25 * 60)]);
+        assert_eq!(charts.weeks[1].segments[2].name, "No task");
+    }
+
+    #[test]
+    fn focus_charts_total_weekdays_monday_first() {
+        let sessions = vec![
+            work_session("1", "2024-01-15T09:00:00.000Z", "2024-01-15T09:25:00.000Z"),
+            work_session("2", "2024-01-17T09:00:00.000Z", "2024-01-17T09:50:00.000Z"),
+            work_session("3", "2024-01-21T09:00:00.000Z", "2024-01-21T09:10:00.000Z"),
+        ];
+
+        let charts = aggregate_focus_charts(&sessions, &[], 1, "2024-01-21", utc_start);
+
+        assert_eq!(charts.by_weekday, [25 * 60, 0, 50 * 60, 0, 0, 0, 10 * 60]);
+    }
+
+    #[test]
+    fn focus_charts_split_sessions_across_hours() {
+        let sessions = vec![work_session("1", "2024-01-15T23:50:00.000Z", "2024-01-16T00:15:00.000Z")];
+
+        let charts = aggregate_focus_charts(&sessions, &[], 1, "2024-01-16", utc_start);
+
+        assert_eq!(charts.by_hour[23], 10 * 60);
+        assert_eq!(charts.by_hour[0], 15 * 60);
+        assert_eq!(charts.by_hour.iter().sum::<u32>(), 25 * 60);
+    }
+
+    #[test]
+    fn focus_charts_count_stopped_sessions_in_completion_only() {
+        let mut short_break = work_session("3", "2024-01-15T09:25:00.000Z", "2024-01-15T09:30:00.000Z");
+        short_break.session_type = "ShortBreak".to_string();
+        let sessions = vec![
+            work_session("1", "2024-01-15T09:00:00.000Z", "2024-01-15T09:25:00.000Z"),
+            stopped(work_session("2", "2024-01-15T10:00:00.000Z", "2024-01-15T10:10:00.000Z")),
+            short_break,
+        ];
+
+        let charts = aggregate_focus_charts(&sessions, &[], 1, "2024-01-15", utc_start);
+
+        assert_eq!(charts.weeks[0].total_sessions, 3);
+        assert_eq!(charts.weeks[0].completed_sessions, 2);
+        assert_eq!(charts.weeks[0].focus_time(), 25 * 60);
+        assert_eq!(charts.by_hour[10], 0);
+    }
+}
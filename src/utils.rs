@@ -1,3 +1,4 @@
+use std::cell::Cell;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -53,4 +54,274 @@ pub fn format_iso_date(iso_string: &str) -> String {
     js_sys::Reflect::set(&options, &"minute".into(), &"2-digit".into()).unwrap();
 
     date.to_locale_string("en-US", &options).into()
-}
\ No newline at end of file
+}
+thread_local! {
+    // Local hour at which a new day begins; earlier times still belong to the previous day
+    static DAY_START_HOUR: Cell<u32> = const { Cell::new(0) };
+}
+
+pub fn set_day_start_hour(hour: u32) {
+    DAY_START_HOUR.with(|h| h.set(hour.min(23)));
+}
+
+pub fn day_start_hour() -> u32 {
+    DAY_START_HOUR.with(|h| h.get())
+}
+
+// Local calendar date as YYYY-MM-DD
+pub fn format_date_key(date: &js_sys::Date) -> String {
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.get_full_year(),
+        date.get_month() + 1, // JavaScript months are 0-based
+        date.get_date()
+    )
+}
+
+// Day a moment is grouped under: its local date, or the one before when it falls ahead of
+// the day-start hour. Compares wall-clock hours, so DST changes don't shift the boundary.
+pub fn logical_date_key(date: &js_sys::Date) -> String {
+    let date_key = format_date_key(date);
+    if date.get_hours() < day_start_hour() {
+        add_days_to_date_key(&date_key, -1).unwrap_or(date_key)
+    } else {
+        date_key
+    }
+}
+
+pub fn get_today_date_string() -> String {
+    logical_date_key(&js_sys::Date::new_0())
+}
+
+// Minutes left until the given local hour today (0 if already past)
+pub fn minutes_until_hour(hour: u32) -> u32 {
+    let now = js_sys::Date::new_0();
+    let now_minutes = now.get_hours() * 60 + now.get_minutes();
+    (hour * 60).saturating_sub(now_minutes)
+}
+
+// Calendar arithmetic on YYYY-MM-DD keys (proleptic Gregorian, no timezone involved)
+pub fn parse_date_key(date_key: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = date_key.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.get(..2)?.parse().ok()?;
+    if (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month) {
+        Some((year, month, day))
+    } else {
+        None
+    }
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        _ => {
+            if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 {
+                29
+            } else {
+                28
+            }
+        }
+    }
+}
+
+// Days since 1970-01-01
+pub fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year } as i64;
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+pub fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+    (year, month, day)
+}
+
+pub fn date_key_from_days(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+pub fn date_key_to_days(date_key: &str) -> Option<i64> {
+    parse_date_key(date_key).map(|(year, month, day)| days_from_civil(year, month, day))
+}
+
+pub fn add_days_to_date_key(date_key: &str, days: i64) -> Option<String> {
+    date_key_to_days(date_key).map(|d| date_key_from_days(d + days))
+}
+
+// 0 = Sunday .. 6 = Saturday, matching JavaScript's Date.getDay()
+pub fn weekday_of_date_key(date_key: &str) -> Option<u32> {
+    // 1970-01-01 was a Thursday
+    date_key_to_days(date_key).map(|d| (d + 4).rem_euclid(7) as u32)
+}
+
+// Monday of the week containing the given date
+pub fn week_start_of_date_key(date_key: &str) -> Option<String> {
+    let weekday = weekday_of_date_key(date_key)?;
+    add_days_to_date_key(date_key, -(((weekday + 6) % 7) as i64))
+}
+
+// Day an ISO timestamp is grouped under, in the current local timezone
+pub fn date_key_from_iso(iso_string: &str) -> String {
+    logical_date_key(&js_sys::Date::new(&iso_string.into()))
+}
+
+// "20240115T230000Z", the basic format iCalendar and Taskwarrior use, as "2024-01-15T23:00:00Z".
+// A trailing Z is kept, so floating times stay floating.
+pub fn iso_from_basic_date_time(value: &str) -> Option<String> {
+    let (date_time, zone) = match value.strip_suffix('Z') {
+        Some(date_time) => (date_time, "Z"),
+        None => (value, ""),
+    };
+    let (date, time) = date_time.split_once('T')?;
+    if date.len() != 8 || time.len() != 6 || !date.chars().chain(time.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(format!(
+        "{}-{}-{}T{}:{}:{}{}",
+        &date[..4],
+        &date[4..6],
+        &date[6..],
+        &time[..2],
+        &time[2..4],
+        &time[4..],
+        zone
+    ))
+}
+
+// UTC instant ("...Z") of a wall-clock time "YYYY-MM-DDTHH:MM:SS" in an IANA time zone such
+// as "Europe/Berlin"; None when the zone is unknown
+pub fn zoned_time_to_utc_iso(wall_time: &str, time_zone: &str) -> Option<String> {
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &"timeZone".into(), &time_zone.into()).ok()?;
+    js_sys::Reflect::set(&options, &"hourCycle".into(), &"h23".into()).ok()?;
+    for field in ["year", "month", "day", "hour", "minute", "second"] {
+        js_sys::Reflect::set(&options, &field.into(), &"numeric".into()).ok()?;
+    }
+    // Constructing through Reflect turns the RangeError for an unknown zone into an Err
+    let intl = js_sys::Reflect::get(&js_sys::global(), &"Intl".into()).ok()?;
+    let constructor = js_sys::Reflect::get(&intl, &"DateTimeFormat".into()).ok()?;
+    let format: js_sys::Intl::DateTimeFormat =
+        js_sys::Reflect::construct(constructor.unchecked_ref::<js_sys::Function>(), &js_sys::Array::of2(&"en-US".into(), &options))
+            .ok()?
+            .unchecked_into();
+
+    // The zone's wall clock at an instant, as milliseconds on a UTC clock
+    let wall_ms = |ms: f64| -> Option<f64> {
+        let mut fields = [0i64; 6];
+        for part in format.format_to_parts(&js_sys::Date::new(&ms.into())).iter() {
+            let kind = js_sys::Reflect::get(&part, &"type".into()).ok()?.as_string()?;
+            let value = js_sys::Reflect::get(&part, &"value".into()).ok()?.as_string()?;
+            if let Some(i) = ["year", "month", "day", "hour", "minute", "second"].iter().position(|f| *f == kind) {
+                fields[i] = value.parse().ok()?;
+            }
+        }
+        let days = days_from_civil(fields[0] as i32, fields[1] as u32, fields[2] as u32);
+        Some((((days * 24 + fields[3]) * 60 + fields[4]) * 60 + fields[5]) as f64 * 1000.0)
+    };
+
+    let target = js_sys::Date::parse(&format!("{}Z", wall_time));
+    if target.is_nan() {
+        return None;
+    }
+    // Subtract the zone's offset, taking it again at the result for times near a DST change
+    let mut instant = target;
+    for _ in 0..2 {
+        instant = target - (wall_ms(instant)? - instant);
+    }
+    Some(js_sys::Date::new(&instant.into()).to_iso_string().into())
+}
+
+// Start and end of a day in epoch milliseconds, from the day-start hour to the next day's;
+// not always 24h apart around DST
+pub fn local_day_bounds_ms(date_key: &str) -> Option<(f64, f64)> {
+    let hour = day_start_hour() as i32;
+    let start_of = |(year, month, day): (i32, u32, u32)| {
+        js_sys::Date::new_with_year_month_day_hr(year as u32, month as i32 - 1, day as i32, hour).get_time()
+    };
+    let start = start_of(parse_date_key(date_key)?);
+    let end = start_of(parse_date_key(&add_days_to_date_key(date_key, 1)?)?);
+    Some((start, end))
+}
+
+// Local "YYYY-MM-DDTHH:MM", the value format of <input type="datetime-local">
+pub fn iso_to_datetime_local(iso_string: &str) -> String {
+    let date = js_sys::Date::new(&iso_string.into());
+    format!(
+        "{}T{:02}:{:02}",
+        format_date_key(&date),
+        date.get_hours(),
+        date.get_minutes()
+    )
+}
+
+pub fn datetime_local_to_iso(value: &str) -> Option<String> {
+    let date = js_sys::Date::new(&value.into());
+    if date.get_time().is_nan() {
+        None
+    } else {
+        Some(date.to_iso_string().into())
+    }
+}
+
+// Case-insensitive subsequence match of `query` in `text`; None when some character is missing.
+// Matches at word starts, runs of consecutive characters and whole substrings score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let query = query.trim().to_lowercase();
+    let pattern: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).collect();
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    let haystack = text.to_lowercase();
+    let chars: Vec<char> = haystack.chars().collect();
+    let mut score = 0u32;
+    let mut matched = 0;
+    let mut previous: Option<usize> = None;
+    let mut first: Option<usize> = None;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if matched == pattern.len() {
+            break;
+        }
+        if c != pattern[matched] {
+            continue;
+        }
+        score += 4;
+        if i == 0 || !chars[i - 1].is_alphanumeric() {
+            score += 8;
+        }
+        if previous.is_some_and(|p| p + 1 == i) {
+            score += 6;
+        }
+        first.get_or_insert(i);
+        previous = Some(i);
+        matched += 1;
+    }
+
+    if matched < pattern.len() {
+        return None;
+    }
+    if haystack.contains(&query) {
+        score += 20;
+    }
+    // Prefer matches near the start and shorter texts
+    score += 10u32.saturating_sub(first.unwrap_or(0) as u32);
+    score += 10u32.saturating_sub(chars.len() as u32 / 8);
+    Some(score)
+}